@@ -0,0 +1,12 @@
+$here = $PSScriptRoot
+$project_root = (Get-Item $here).Parent.FullName
+$target = "debug"
+$module_dir = "$project_root\target\$target"
+
+# See test.ps1.
+$ErrorActionPreference = 'Continue'
+emacs --version
+emacs --batch --directory "$module_dir" `
+  -l ert `
+  -l "$project_root\test-module\tests\bench.el" `
+  -f ert-run-tests-batch-and-exit
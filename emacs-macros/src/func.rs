@@ -15,7 +15,7 @@ use crate::util::{self, report};
 #[derive(Debug)]
 enum Arg {
     Env { span: Span },
-    Val { span: Span, access: Access, nth: usize, name: Option<Ident> },
+    Val { span: Span, access: Access, nth: usize, name: Option<Ident>, optional: bool },
 }
 
 /// Kinds of argument.
@@ -68,6 +68,24 @@ struct FuncOpts {
     /// How the return value should be embedded in Lisp as a `user-ptr`. `None` means no embedding.
     #[darling(default)]
     user_ptr: Option<UserPtr>,
+    /// Whether the function should be a command, and its interactive spec. `None` means it is not
+    /// a command.
+    #[darling(default)]
+    interactive: Option<Interactive>,
+}
+
+/// The argument of a command's `interactive` form. `None` means the form has no argument.
+#[derive(Debug)]
+struct Interactive(Option<String>);
+
+impl FromMeta for Interactive {
+    fn from_word() -> darling::Result<Interactive> {
+        Ok(Interactive(None))
+    }
+
+    fn from_string(value: &str) -> darling::Result<Interactive> {
+        Ok(Interactive(Some(value.to_owned())))
+    }
 }
 
 #[derive(Debug)]
@@ -152,11 +170,19 @@ impl LispFunc {
                     // error is confusing (i.e expecting Env, found &Env).
                     args.append_all(quote_spanned!(span=> &**#env,))
                 }
-                Arg::Val { span, access, nth, .. } => {
+                Arg::Val { span, access, nth, optional, .. } => {
                     let name = util::arg("arg", nth);
                     // TODO: Create a slice of `emacs_value` once and iterate through it, instead of
                     // using `get_arg`, which creates a slice each call.
                     bindings.append_all(match access {
+                        // Omitted optional arguments are `None`, like explicitly passed `nil`s.
+                        Access::Owned if optional => quote_spanned! {span=>
+                            let #name = if #nth < #env.raw_args().len() {
+                                #env.get_arg(#nth).into_rust()?
+                            } else {
+                                ::std::option::Option::None
+                            };
+                        },
                         Access::Owned => quote_spanned! {span=>
                             let #name = #env.get_arg(#nth).into_rust()?;
                         },
@@ -216,8 +242,11 @@ impl LispFunc {
         let exporter = self.exporter_ident();
         let (min, max) = (self.arities.start, self.arities.end);
         let mut doc = util::doc(&self.def);
-        doc.push_str("\n\n");
-        doc.push_str(&lisp_signature(&self.args));
+        // Keep the signature if it was explicitly specified, e.g. to give arguments better names.
+        if !has_lisp_signature(&doc) {
+            doc.push_str("\n\n");
+            doc.push_str(&lisp_signature(&self.args));
+        }
         let path = match &self.opts.mod_in_name {
             None => {
                 let crate_mod_in_name = util::mod_in_name_path();
@@ -238,15 +267,34 @@ impl LispFunc {
         };
         // TODO: Consider defining `extern "C" fn` directly instead of using export_functions! and
         // CallEnv wrapper.
-        quote! {
-            #define_wrapper
-            fn #exporter(env: &::emacs::Env) -> ::emacs::Result<()> {
-                let prefix = ::emacs::init::lisp_path(#path);
+        let export = match &self.opts.interactive {
+            None => quote! {
                 ::emacs::__export_functions! {
                     env, prefix, {
                         #lisp_name => (#wrapper, #min..#max, #doc),
                     }
                 }
+            },
+            // Module functions cannot be interactive, so we wrap them in Lisp commands.
+            Some(Interactive(spec)) => {
+                let spec = match spec {
+                    Some(spec) => quote!(Some(#spec)),
+                    None => quote!(None),
+                };
+                quote! {
+                    {
+                        use ::emacs::func::Manage;
+                        let func = ::emacs::lambda!(env, #wrapper, #min..#max, #doc)?;
+                        env.fset(&format!("{}{}", prefix, #lisp_name), env.make_command(func, #spec)?)?;
+                    }
+                }
+            }
+        };
+        quote! {
+            #define_wrapper
+            fn #exporter(env: &::emacs::Env) -> ::emacs::Result<()> {
+                let prefix = ::emacs::init::lisp_path(#path);
+                #export
                 Ok(())
             }
         }
@@ -293,6 +341,7 @@ fn check_signature(sig: &Signature) -> Result<(Vec<Arg>, Range<usize>, Span), To
     let mut i: usize = 0;
     let mut err = TokenStream2::new();
     let mut has_env = false;
+    let mut required: Option<usize> = None;
     let mut args: Vec<Arg> = vec![];
     let errors = &mut err;
     for fn_arg in &sig.inputs {
@@ -325,7 +374,21 @@ fn check_signature(sig: &Signature) -> Result<(Vec<Arg>, Range<usize>, Span), To
                             continue;
                         }
                     };
-                    let a = Arg::Val { span, access, name, nth: i };
+                    // Trailing `Option` arguments are optional in Lisp.
+                    let optional = match access {
+                        Access::Owned => is_option(ty),
+                        _ => false,
+                    };
+                    match (optional, required) {
+                        (true, None) => required = Some(i),
+                        (false, Some(_)) => report(
+                            errors,
+                            fn_arg,
+                            "Required argument cannot follow an optional (Option) argument",
+                        ),
+                        _ => (),
+                    }
+                    let a = Arg::Val { span, access, name, nth: i, optional };
                     i += 1;
                     a
                 });
@@ -342,7 +405,7 @@ fn check_signature(sig: &Signature) -> Result<(Vec<Arg>, Range<usize>, Span), To
         }
     };
     if err.is_empty() {
-        Ok((args, Range { start: i, end: i }, output_span))
+        Ok((args, Range { start: required.unwrap_or(i), end: i }, output_span))
     } else {
         Err(err)
     }
@@ -360,6 +423,18 @@ fn is_env(ty: &Type) -> bool {
     }
 }
 
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => {
+            match path.segments.last() {
+                Some(segment) => segment.ident == "Option",
+                None => false,
+            }
+        }
+        _ => false,
+    }
+}
+
 fn lisp_name(arg: &Arg) -> Option<String> {
     match arg {
         Arg::Env { .. } => None,
@@ -368,11 +443,27 @@ fn lisp_name(arg: &Arg) -> Option<String> {
     }
 }
 
+fn has_lisp_signature(doc: &str) -> bool {
+    match doc.trim_end().lines().last() {
+        Some(line) => line == "(fn)" || line.starts_with("(fn ") && line.ends_with(')'),
+        None => false,
+    }
+}
+
 fn lisp_signature(args: &[Arg]) -> String {
     let mut sig = "(fn".to_owned();
-    for arg in args.iter().flat_map(lisp_name) {
-        sig.push_str(" ");
-        sig.push_str(&arg);
+    let mut optional = false;
+    for arg in args {
+        if let Arg::Val { optional: true, .. } = arg {
+            if !optional {
+                sig.push_str(" &optional");
+                optional = true;
+            }
+        }
+        if let Some(name) = lisp_name(arg) {
+            sig.push_str(" ");
+            sig.push_str(&name);
+        }
     }
     sig.push_str(")");
     sig
@@ -67,6 +67,9 @@ pub fn module(attr_ts: TokenStream, item_ts: TokenStream) -> TokenStream {
 /// function's Lisp signature. This is unnecessary if there is already another parameter with type
 /// [`Value`], which allows accessing the runtime through `Value.env`.
 ///
+/// Trailing parameters of type `Option<T>` are `&optional` in Lisp. They are `None` if omitted by
+/// the caller. A required parameter cannot follow an optional one.
+///
 /// # Return Value
 ///
 /// The return type must be [`Result<T>`], where `T` is one of the following:
@@ -99,6 +102,18 @@ pub fn module(attr_ts: TokenStream, item_ts: TokenStream) -> TokenStream {
 /// - `base-name` is the function's Rust name (with `_` replaced by `-`). This can be overridden
 /// with the option `name`, e.g. `#[defun(name = "foo:bar")]`.
 ///
+/// # Commands
+///
+/// The option `interactive` makes the function a command, which can be called with `M-x`, or bound
+/// to a key. Its value is passed through verbatim as the argument of the `interactive` form, e.g.
+/// `#[defun(interactive = "p")]`. `#[defun(interactive)]` means the command reads no arguments.
+///
+/// Since module functions cannot be interactive, the function is wrapped in a Lisp closure that
+/// has the `interactive` form. The arguments read by the spec are passed to the function as is, so
+/// their number must be allowed by the function's signature, and their types must be convertible
+/// to its parameters' types. For example, `"r"` passes 2 integers, and `"P"` passes the raw prefix
+/// argument, which is better accepted as a [`Value`].
+///
 /// [`module`]: attr.module.html
 /// [`Result<T>`]: /emacs/*/emacs/type.Result.html
 /// [`FromLisp`]: /emacs/*/emacs/trait.FromLisp.html
@@ -124,6 +139,13 @@ pub fn impl_lisp_args_for_tuples(arity: TokenStream) -> TokenStream {
     lisp_args::impl_for_tuples(arity.base10_parse::<usize>().unwrap()).into()
 }
 
+#[doc(hidden)]
+#[proc_macro]
+pub fn impl_into_lisp_for_tuples(arity: TokenStream) -> TokenStream {
+    let arity: LitInt = parse_macro_input!(arity);
+    lisp_args::impl_into_lisp_for_tuples(arity.base10_parse::<usize>().unwrap()).into()
+}
+
 #[doc(hidden)]
 #[proc_macro]
 pub fn impl_lisp_args_for_arrays(length: TokenStream) -> TokenStream {
@@ -10,6 +10,14 @@ pub fn impl_for_tuples(max_arity: usize) -> TokenStream2 {
     impls
 }
 
+pub fn impl_into_lisp_for_tuples(max_arity: usize) -> TokenStream2 {
+    let mut impls = TokenStream2::new();
+    for arity in 1..=max_arity {
+        impls.append_all(impl_into_lisp_for_tuple(arity));
+    }
+    impls
+}
+
 pub fn impl_for_arrays(max_length: usize) -> TokenStream2 {
     let mut impls = TokenStream2::new();
     for length in 0..=max_length {
@@ -44,6 +52,27 @@ fn impl_for_tuple(arity: usize) -> TokenStream2 {
     };
 }
 
+fn impl_into_lisp_for_tuple(arity: usize) -> TokenStream2 {
+    let type_vars = (0..arity).map(|n| {
+        Ident::new(&format!("T{}", n + 1), Span::call_site())
+    });
+    let mut types = TokenStream2::new();
+    let mut constraints = TokenStream2::new();
+    for var in type_vars {
+        types.append_all(quote!(#var, ));
+        constraints.append_all(quote!(#var: IntoLisp<'e>, ));
+    }
+
+    quote! {
+        impl<'e, #types> IntoLisp<'e> for (#types) where #constraints {
+            #[inline]
+            fn into_lisp(self, env: &'e Env) -> Result<Value<'e>> {
+                env.list(self)
+            }
+        }
+    }
+}
+
 fn impl_for_array(length: usize) -> TokenStream2 {
     let mut values = TokenStream2::new();
     for i in 0..length {
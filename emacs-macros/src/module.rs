@@ -158,6 +158,7 @@ impl Module {
                 #configure_mod_in_name
                 #export_lisp_funcs
                 #hook(#env)?;
+                ::emacs::init::define_unload_function(#env, &feature)?;
                 #env.provide(&feature)
             }
         }
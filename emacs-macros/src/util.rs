@@ -32,7 +32,9 @@ pub fn doc(fn_item: &ItemFn) -> String {
         if let Ok(syn::Meta::NameValue(mnv)) = attr.parse_meta() {
             if &mnv.path.segments.last().unwrap().ident.to_string() == "doc" {
                 if let syn::Lit::Str(ls) = mnv.lit {
-                    doc.push(ls.value().trim_start_matches(' ').to_owned());
+                    // Strip only the space after `///`, to preserve indentation, e.g. of code.
+                    let line = ls.value();
+                    doc.push(line.strip_prefix(' ').unwrap_or(&line).to_owned());
                 }
             }
         }
@@ -22,6 +22,8 @@ pub struct emacs_runtime {
 }
 #[test]
 fn bindgen_test_layout_emacs_runtime() {
+    const UNINIT: ::std::mem::MaybeUninit<emacs_runtime> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
     assert_eq!(
         ::std::mem::size_of::<emacs_runtime>(),
         24usize,
@@ -33,17 +35,17 @@ fn bindgen_test_layout_emacs_runtime() {
         concat!("Alignment of ", stringify!(emacs_runtime))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_runtime>())).size as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).size) as usize - ptr as usize },
         0usize,
         concat!("Offset of field: ", stringify!(emacs_runtime), "::", stringify!(size))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_runtime>())).private_members as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).private_members) as usize - ptr as usize },
         8usize,
         concat!("Offset of field: ", stringify!(emacs_runtime), "::", stringify!(private_members))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_runtime>())).get_environment as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).get_environment) as usize - ptr as usize },
         16usize,
         concat!("Offset of field: ", stringify!(emacs_runtime), "::", stringify!(get_environment))
     );
@@ -98,14 +100,7 @@ pub struct emacs_env_25 {
             env: *mut emacs_env,
             min_arity: isize,
             max_arity: isize,
-            function: ::std::option::Option<
-                unsafe extern "C" fn(
-                    env: *mut emacs_env,
-                    nargs: isize,
-                    args: *mut emacs_value,
-                    arg1: *mut ::std::os::raw::c_void,
-                ) -> emacs_value,
-            >,
+            function: emacs_subr,
             documentation: *const ::std::os::raw::c_char,
             data: *mut ::std::os::raw::c_void,
         ) -> emacs_value,
@@ -192,6 +187,8 @@ pub struct emacs_env_25 {
 }
 #[test]
 fn bindgen_test_layout_emacs_env_25() {
+    const UNINIT: ::std::mem::MaybeUninit<emacs_env_25> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
     assert_eq!(
         ::std::mem::size_of::<emacs_env_25>(),
         232usize,
@@ -203,29 +200,27 @@ fn bindgen_test_layout_emacs_env_25() {
         concat!("Alignment of ", stringify!(emacs_env_25))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).size as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).size) as usize - ptr as usize },
         0usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(size))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).private_members as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).private_members) as usize - ptr as usize },
         8usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(private_members))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).make_global_ref as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).make_global_ref) as usize - ptr as usize },
         16usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(make_global_ref))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).free_global_ref as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).free_global_ref) as usize - ptr as usize },
         24usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(free_global_ref))
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<emacs_env_25>())).non_local_exit_check as *const _ as usize
-        },
+        unsafe { ::std::ptr::addr_of!((*ptr).non_local_exit_check) as usize - ptr as usize },
         32usize,
         concat!(
             "Offset of field: ",
@@ -235,9 +230,7 @@ fn bindgen_test_layout_emacs_env_25() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<emacs_env_25>())).non_local_exit_clear as *const _ as usize
-        },
+        unsafe { ::std::ptr::addr_of!((*ptr).non_local_exit_clear) as usize - ptr as usize },
         40usize,
         concat!(
             "Offset of field: ",
@@ -247,7 +240,7 @@ fn bindgen_test_layout_emacs_env_25() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).non_local_exit_get as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).non_local_exit_get) as usize - ptr as usize },
         48usize,
         concat!(
             "Offset of field: ",
@@ -257,9 +250,7 @@ fn bindgen_test_layout_emacs_env_25() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<emacs_env_25>())).non_local_exit_signal as *const _ as usize
-        },
+        unsafe { ::std::ptr::addr_of!((*ptr).non_local_exit_signal) as usize - ptr as usize },
         56usize,
         concat!(
             "Offset of field: ",
@@ -269,9 +260,7 @@ fn bindgen_test_layout_emacs_env_25() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<emacs_env_25>())).non_local_exit_throw as *const _ as usize
-        },
+        unsafe { ::std::ptr::addr_of!((*ptr).non_local_exit_throw) as usize - ptr as usize },
         64usize,
         concat!(
             "Offset of field: ",
@@ -281,59 +270,57 @@ fn bindgen_test_layout_emacs_env_25() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).make_function as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).make_function) as usize - ptr as usize },
         72usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(make_function))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).funcall as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).funcall) as usize - ptr as usize },
         80usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(funcall))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).intern as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).intern) as usize - ptr as usize },
         88usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(intern))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).type_of as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).type_of) as usize - ptr as usize },
         96usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(type_of))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).is_not_nil as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).is_not_nil) as usize - ptr as usize },
         104usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(is_not_nil))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).eq as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).eq) as usize - ptr as usize },
         112usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(eq))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).extract_integer as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).extract_integer) as usize - ptr as usize },
         120usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(extract_integer))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).make_integer as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).make_integer) as usize - ptr as usize },
         128usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(make_integer))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).extract_float as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).extract_float) as usize - ptr as usize },
         136usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(extract_float))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).make_float as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).make_float) as usize - ptr as usize },
         144usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(make_float))
     );
     assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<emacs_env_25>())).copy_string_contents as *const _ as usize
-        },
+        unsafe { ::std::ptr::addr_of!((*ptr).copy_string_contents) as usize - ptr as usize },
         152usize,
         concat!(
             "Offset of field: ",
@@ -343,27 +330,27 @@ fn bindgen_test_layout_emacs_env_25() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).make_string as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).make_string) as usize - ptr as usize },
         160usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(make_string))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).make_user_ptr as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).make_user_ptr) as usize - ptr as usize },
         168usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(make_user_ptr))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).get_user_ptr as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).get_user_ptr) as usize - ptr as usize },
         176usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(get_user_ptr))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).set_user_ptr as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).set_user_ptr) as usize - ptr as usize },
         184usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(set_user_ptr))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).get_user_finalizer as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).get_user_finalizer) as usize - ptr as usize },
         192usize,
         concat!(
             "Offset of field: ",
@@ -373,7 +360,7 @@ fn bindgen_test_layout_emacs_env_25() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).set_user_finalizer as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).set_user_finalizer) as usize - ptr as usize },
         200usize,
         concat!(
             "Offset of field: ",
@@ -383,21 +370,683 @@ fn bindgen_test_layout_emacs_env_25() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).vec_get as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).vec_get) as usize - ptr as usize },
         208usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(vec_get))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).vec_set as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).vec_set) as usize - ptr as usize },
         216usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(vec_set))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<emacs_env_25>())).vec_size as *const _ as usize },
+        unsafe { ::std::ptr::addr_of!((*ptr).vec_size) as usize - ptr as usize },
         224usize,
         concat!("Offset of field: ", stringify!(emacs_env_25), "::", stringify!(vec_size))
     );
 }
+pub type __time_t = ::std::os::raw::c_long;
+pub type __syscall_slong_t = ::std::os::raw::c_long;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct timespec {
+    pub tv_sec: __time_t,
+    pub tv_nsec: __syscall_slong_t,
+}
+#[test]
+fn bindgen_test_layout_timespec() {
+    const UNINIT: ::std::mem::MaybeUninit<timespec> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<timespec>(),
+        16usize,
+        concat!("Size of: ", stringify!(timespec))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<timespec>(),
+        8usize,
+        concat!("Alignment of ", stringify!(timespec))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).tv_sec) as usize - ptr as usize },
+        0usize,
+        concat!("Offset of field: ", stringify!(timespec), "::", stringify!(tv_sec))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).tv_nsec) as usize - ptr as usize },
+        8usize,
+        concat!("Offset of field: ", stringify!(timespec), "::", stringify!(tv_nsec))
+    );
+}
+pub type emacs_limb_t = usize;
+pub const emacs_process_input_continue: emacs_process_input_result = 0;
+pub const emacs_process_input_quit: emacs_process_input_result = 1;
+pub type emacs_process_input_result = ::std::os::raw::c_int;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct emacs_env_26 {
+    pub size: isize,
+    pub private_members: *mut emacs_env_private,
+    pub make_global_ref: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, any_reference: emacs_value) -> emacs_value,
+    >,
+    pub free_global_ref: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, global_reference: emacs_value),
+    >,
+    pub non_local_exit_check:
+        ::std::option::Option<unsafe extern "C" fn(env: *mut emacs_env) -> emacs_funcall_exit>,
+    pub non_local_exit_clear: ::std::option::Option<unsafe extern "C" fn(env: *mut emacs_env)>,
+    pub non_local_exit_get: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            non_local_exit_symbol_out: *mut emacs_value,
+            non_local_exit_data_out: *mut emacs_value,
+        ) -> emacs_funcall_exit,
+    >,
+    pub non_local_exit_signal: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            non_local_exit_symbol: emacs_value,
+            non_local_exit_data: emacs_value,
+        ),
+    >,
+    pub non_local_exit_throw: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, tag: emacs_value, value: emacs_value),
+    >,
+    pub make_function: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            min_arity: isize,
+            max_arity: isize,
+            function: emacs_subr,
+            documentation: *const ::std::os::raw::c_char,
+            data: *mut ::std::os::raw::c_void,
+        ) -> emacs_value,
+    >,
+    pub funcall: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            function: emacs_value,
+            nargs: isize,
+            args: *mut emacs_value,
+        ) -> emacs_value,
+    >,
+    pub intern: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            symbol_name: *const ::std::os::raw::c_char,
+        ) -> emacs_value,
+    >,
+    pub type_of: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, value: emacs_value) -> emacs_value,
+    >,
+    pub is_not_nil: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, value: emacs_value) -> bool,
+    >,
+    pub eq: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, a: emacs_value, b: emacs_value) -> bool,
+    >,
+    pub extract_integer: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, value: emacs_value) -> intmax_t,
+    >,
+    pub make_integer: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, value: intmax_t) -> emacs_value,
+    >,
+    pub extract_float:
+        ::std::option::Option<unsafe extern "C" fn(env: *mut emacs_env, value: emacs_value) -> f64>,
+    pub make_float:
+        ::std::option::Option<unsafe extern "C" fn(env: *mut emacs_env, value: f64) -> emacs_value>,
+    pub copy_string_contents: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            value: emacs_value,
+            buffer: *mut ::std::os::raw::c_char,
+            size_inout: *mut isize,
+        ) -> bool,
+    >,
+    pub make_string: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            contents: *const ::std::os::raw::c_char,
+            length: isize,
+        ) -> emacs_value,
+    >,
+    pub make_user_ptr: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            fin: emacs_finalizer_function,
+            ptr: *mut ::std::os::raw::c_void,
+        ) -> emacs_value,
+    >,
+    pub get_user_ptr: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, uptr: emacs_value) -> *mut ::std::os::raw::c_void,
+    >,
+    pub set_user_ptr: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            uptr: emacs_value,
+            ptr: *mut ::std::os::raw::c_void,
+        ),
+    >,
+    pub get_user_finalizer: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, uptr: emacs_value) -> emacs_finalizer_function,
+    >,
+    pub set_user_finalizer: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, uptr: emacs_value, fin: emacs_finalizer_function),
+    >,
+    pub vec_get: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, vec: emacs_value, i: isize) -> emacs_value,
+    >,
+    pub vec_set: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, vec: emacs_value, i: isize, val: emacs_value),
+    >,
+    pub vec_size:
+        ::std::option::Option<unsafe extern "C" fn(env: *mut emacs_env, vec: emacs_value) -> isize>,
+    pub should_quit: ::std::option::Option<unsafe extern "C" fn(env: *mut emacs_env) -> bool>,
+}
+#[test]
+fn bindgen_test_layout_emacs_env_26() {
+    const UNINIT: ::std::mem::MaybeUninit<emacs_env_26> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<emacs_env_26>(),
+        240usize,
+        concat!("Size of: ", stringify!(emacs_env_26))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<emacs_env_26>(),
+        8usize,
+        concat!("Alignment of ", stringify!(emacs_env_26))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).size) as usize - ptr as usize },
+        0usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(size))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).private_members) as usize - ptr as usize },
+        8usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(private_members))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).make_global_ref) as usize - ptr as usize },
+        16usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(make_global_ref))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).free_global_ref) as usize - ptr as usize },
+        24usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(free_global_ref))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).non_local_exit_check) as usize - ptr as usize },
+        32usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(non_local_exit_check))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).non_local_exit_clear) as usize - ptr as usize },
+        40usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(non_local_exit_clear))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).non_local_exit_get) as usize - ptr as usize },
+        48usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(non_local_exit_get))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).non_local_exit_signal) as usize - ptr as usize },
+        56usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(non_local_exit_signal))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).non_local_exit_throw) as usize - ptr as usize },
+        64usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(non_local_exit_throw))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).make_function) as usize - ptr as usize },
+        72usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(make_function))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).funcall) as usize - ptr as usize },
+        80usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(funcall))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).intern) as usize - ptr as usize },
+        88usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(intern))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).type_of) as usize - ptr as usize },
+        96usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(type_of))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).is_not_nil) as usize - ptr as usize },
+        104usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(is_not_nil))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).eq) as usize - ptr as usize },
+        112usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(eq))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).extract_integer) as usize - ptr as usize },
+        120usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(extract_integer))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).make_integer) as usize - ptr as usize },
+        128usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(make_integer))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).extract_float) as usize - ptr as usize },
+        136usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(extract_float))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).make_float) as usize - ptr as usize },
+        144usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(make_float))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).copy_string_contents) as usize - ptr as usize },
+        152usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(copy_string_contents))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).make_string) as usize - ptr as usize },
+        160usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(make_string))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).make_user_ptr) as usize - ptr as usize },
+        168usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(make_user_ptr))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).get_user_ptr) as usize - ptr as usize },
+        176usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(get_user_ptr))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).set_user_ptr) as usize - ptr as usize },
+        184usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(set_user_ptr))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).get_user_finalizer) as usize - ptr as usize },
+        192usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(get_user_finalizer))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).set_user_finalizer) as usize - ptr as usize },
+        200usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(set_user_finalizer))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).vec_get) as usize - ptr as usize },
+        208usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(vec_get))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).vec_set) as usize - ptr as usize },
+        216usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(vec_set))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).vec_size) as usize - ptr as usize },
+        224usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(vec_size))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).should_quit) as usize - ptr as usize },
+        232usize,
+        concat!("Offset of field: ", stringify!(emacs_env_26), "::", stringify!(should_quit))
+    );
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct emacs_env_27 {
+    pub size: isize,
+    pub private_members: *mut emacs_env_private,
+    pub make_global_ref: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, any_reference: emacs_value) -> emacs_value,
+    >,
+    pub free_global_ref: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, global_reference: emacs_value),
+    >,
+    pub non_local_exit_check:
+        ::std::option::Option<unsafe extern "C" fn(env: *mut emacs_env) -> emacs_funcall_exit>,
+    pub non_local_exit_clear: ::std::option::Option<unsafe extern "C" fn(env: *mut emacs_env)>,
+    pub non_local_exit_get: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            non_local_exit_symbol_out: *mut emacs_value,
+            non_local_exit_data_out: *mut emacs_value,
+        ) -> emacs_funcall_exit,
+    >,
+    pub non_local_exit_signal: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            non_local_exit_symbol: emacs_value,
+            non_local_exit_data: emacs_value,
+        ),
+    >,
+    pub non_local_exit_throw: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, tag: emacs_value, value: emacs_value),
+    >,
+    pub make_function: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            min_arity: isize,
+            max_arity: isize,
+            function: emacs_subr,
+            documentation: *const ::std::os::raw::c_char,
+            data: *mut ::std::os::raw::c_void,
+        ) -> emacs_value,
+    >,
+    pub funcall: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            function: emacs_value,
+            nargs: isize,
+            args: *mut emacs_value,
+        ) -> emacs_value,
+    >,
+    pub intern: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            symbol_name: *const ::std::os::raw::c_char,
+        ) -> emacs_value,
+    >,
+    pub type_of: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, value: emacs_value) -> emacs_value,
+    >,
+    pub is_not_nil: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, value: emacs_value) -> bool,
+    >,
+    pub eq: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, a: emacs_value, b: emacs_value) -> bool,
+    >,
+    pub extract_integer: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, value: emacs_value) -> intmax_t,
+    >,
+    pub make_integer: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, value: intmax_t) -> emacs_value,
+    >,
+    pub extract_float:
+        ::std::option::Option<unsafe extern "C" fn(env: *mut emacs_env, value: emacs_value) -> f64>,
+    pub make_float:
+        ::std::option::Option<unsafe extern "C" fn(env: *mut emacs_env, value: f64) -> emacs_value>,
+    pub copy_string_contents: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            value: emacs_value,
+            buffer: *mut ::std::os::raw::c_char,
+            size_inout: *mut isize,
+        ) -> bool,
+    >,
+    pub make_string: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            contents: *const ::std::os::raw::c_char,
+            length: isize,
+        ) -> emacs_value,
+    >,
+    pub make_user_ptr: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            fin: emacs_finalizer_function,
+            ptr: *mut ::std::os::raw::c_void,
+        ) -> emacs_value,
+    >,
+    pub get_user_ptr: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, uptr: emacs_value) -> *mut ::std::os::raw::c_void,
+    >,
+    pub set_user_ptr: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            uptr: emacs_value,
+            ptr: *mut ::std::os::raw::c_void,
+        ),
+    >,
+    pub get_user_finalizer: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, uptr: emacs_value) -> emacs_finalizer_function,
+    >,
+    pub set_user_finalizer: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, uptr: emacs_value, fin: emacs_finalizer_function),
+    >,
+    pub vec_get: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, vec: emacs_value, i: isize) -> emacs_value,
+    >,
+    pub vec_set: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, vec: emacs_value, i: isize, val: emacs_value),
+    >,
+    pub vec_size:
+        ::std::option::Option<unsafe extern "C" fn(env: *mut emacs_env, vec: emacs_value) -> isize>,
+    pub should_quit: ::std::option::Option<unsafe extern "C" fn(env: *mut emacs_env) -> bool>,
+    pub process_input: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env) -> emacs_process_input_result,
+    >,
+    pub extract_time: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, arg: emacs_value) -> timespec,
+    >,
+    pub make_time: ::std::option::Option<
+        unsafe extern "C" fn(env: *mut emacs_env, time: timespec) -> emacs_value,
+    >,
+    pub extract_big_integer: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            arg: emacs_value,
+            sign: *mut ::std::os::raw::c_int,
+            count: *mut isize,
+            magnitude: *mut emacs_limb_t,
+        ) -> bool,
+    >,
+    pub make_big_integer: ::std::option::Option<
+        unsafe extern "C" fn(
+            env: *mut emacs_env,
+            sign: ::std::os::raw::c_int,
+            count: isize,
+            magnitude: *const emacs_limb_t,
+        ) -> emacs_value,
+    >,
+}
+#[test]
+fn bindgen_test_layout_emacs_env_27() {
+    const UNINIT: ::std::mem::MaybeUninit<emacs_env_27> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<emacs_env_27>(),
+        280usize,
+        concat!("Size of: ", stringify!(emacs_env_27))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<emacs_env_27>(),
+        8usize,
+        concat!("Alignment of ", stringify!(emacs_env_27))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).size) as usize - ptr as usize },
+        0usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(size))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).private_members) as usize - ptr as usize },
+        8usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(private_members))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).make_global_ref) as usize - ptr as usize },
+        16usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(make_global_ref))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).free_global_ref) as usize - ptr as usize },
+        24usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(free_global_ref))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).non_local_exit_check) as usize - ptr as usize },
+        32usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(non_local_exit_check))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).non_local_exit_clear) as usize - ptr as usize },
+        40usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(non_local_exit_clear))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).non_local_exit_get) as usize - ptr as usize },
+        48usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(non_local_exit_get))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).non_local_exit_signal) as usize - ptr as usize },
+        56usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(non_local_exit_signal))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).non_local_exit_throw) as usize - ptr as usize },
+        64usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(non_local_exit_throw))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).make_function) as usize - ptr as usize },
+        72usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(make_function))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).funcall) as usize - ptr as usize },
+        80usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(funcall))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).intern) as usize - ptr as usize },
+        88usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(intern))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).type_of) as usize - ptr as usize },
+        96usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(type_of))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).is_not_nil) as usize - ptr as usize },
+        104usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(is_not_nil))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).eq) as usize - ptr as usize },
+        112usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(eq))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).extract_integer) as usize - ptr as usize },
+        120usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(extract_integer))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).make_integer) as usize - ptr as usize },
+        128usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(make_integer))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).extract_float) as usize - ptr as usize },
+        136usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(extract_float))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).make_float) as usize - ptr as usize },
+        144usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(make_float))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).copy_string_contents) as usize - ptr as usize },
+        152usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(copy_string_contents))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).make_string) as usize - ptr as usize },
+        160usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(make_string))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).make_user_ptr) as usize - ptr as usize },
+        168usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(make_user_ptr))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).get_user_ptr) as usize - ptr as usize },
+        176usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(get_user_ptr))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).set_user_ptr) as usize - ptr as usize },
+        184usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(set_user_ptr))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).get_user_finalizer) as usize - ptr as usize },
+        192usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(get_user_finalizer))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).set_user_finalizer) as usize - ptr as usize },
+        200usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(set_user_finalizer))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).vec_get) as usize - ptr as usize },
+        208usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(vec_get))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).vec_set) as usize - ptr as usize },
+        216usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(vec_set))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).vec_size) as usize - ptr as usize },
+        224usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(vec_size))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).should_quit) as usize - ptr as usize },
+        232usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(should_quit))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).process_input) as usize - ptr as usize },
+        240usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(process_input))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).extract_time) as usize - ptr as usize },
+        248usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(extract_time))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).make_time) as usize - ptr as usize },
+        256usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(make_time))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).extract_big_integer) as usize - ptr as usize },
+        264usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(extract_big_integer))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).make_big_integer) as usize - ptr as usize },
+        272usize,
+        concat!("Offset of field: ", stringify!(emacs_env_27), "::", stringify!(make_big_integer))
+    );
+}
 extern "C" {
     pub fn emacs_module_init(ert: *mut emacs_runtime) -> ::std::os::raw::c_int;
 }
@@ -0,0 +1,420 @@
+//! Helpers for working with buffers.
+
+use crate::{error::Restore, symbol, Env, FromLisp, IntoLisp, Result, Value};
+
+crate::use_symbols! {
+    bufferp buffer_live_p
+}
+
+/// A type that represents Lisp buffers. This is a wrapper around [`Value`] that provides
+/// buffer-specific methods.
+///
+/// Arguments to #[[`defun`]] having this type will be type-checked. If you want to omit, or delay
+/// this type checking, use [`Value`] instead. Note that a killed buffer is still a buffer, so its
+/// methods check that it is live, signaling `wrong-type-argument` if it is not.
+///
+/// ```
+/// use emacs::{defun, Buffer, Result};
+///
+/// #[defun]
+/// fn first_line(buffer: Buffer) -> Result<String> {
+///     let end = buffer.value().env.with_buffer(buffer.value(), |env| {
+///         env.line_end_position(None)
+///     })?;
+///     buffer.substring(1, end as usize)
+/// }
+/// ```
+///
+/// [`Value`]: struct.Value.html
+/// [`defun`]: attr.defun.html
+#[derive(Debug, Clone, Copy)]
+pub struct Buffer<'e> {
+    value: Value<'e>,
+}
+
+impl<'e> Buffer<'e> {
+    /// Returns the name of the buffer. This is the equivalent of the Lisp function
+    /// [`buffer-name`].
+    ///
+    /// [`buffer-name`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Buffer-Names.html
+    pub fn name(&self) -> Result<String> {
+        self.check_live()?;
+        self.value.env.call("buffer-name", [self.value])?.into_rust()
+    }
+
+    /// Returns whether the buffer has not been killed. This is the equivalent of the Lisp function
+    /// [`buffer-live-p`].
+    ///
+    /// [`buffer-live-p`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Killing-Buffers.html
+    pub fn is_live(&self) -> Result<bool> {
+        Ok(self.value.env.call(buffer_live_p, [self.value])?.is_not_nil())
+    }
+
+    /// Returns the text between positions `start` and `end` in the buffer, without text properties.
+    /// This is the equivalent of the Lisp function [`buffer-substring-no-properties`].
+    ///
+    /// The positions can be given in either order. An `args-out-of-range` error is signaled if
+    /// either is outside the accessible portion of the buffer.
+    ///
+    /// [`buffer-substring-no-properties`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Buffer-Contents.html
+    pub fn substring(&self, start: usize, end: usize) -> Result<String> {
+        self.substring_with("buffer-substring-no-properties", start, end)?.into_rust()
+    }
+
+    /// Like [`substring`], but keeps the text properties, returning a Lisp string. This is the
+    /// equivalent of the Lisp function [`buffer-substring`].
+    ///
+    /// [`substring`]: #method.substring
+    /// [`buffer-substring`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Buffer-Contents.html
+    pub fn substring_propertized(&self, start: usize, end: usize) -> Result<Value<'e>> {
+        self.substring_with("buffer-substring", start, end)
+    }
+
+    fn substring_with(&self, func: &str, start: usize, end: usize) -> Result<Value<'e>> {
+        self.check_live()?;
+        let env = self.value.env;
+        env.with_buffer(self.value, |_| {
+            env.check_position(start)?;
+            env.check_position(end)?;
+            env.call(func, (start, end))
+        })
+    }
+
+    /// Signals `wrong-type-argument` if the buffer has been killed.
+    pub(crate) fn check_live(&self) -> Result<()> {
+        if !self.is_live()? {
+            return self.value.env.signal(symbol::wrong_type_argument, (buffer_live_p, self.value));
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn value(&self) -> Value<'e> {
+        self.value
+    }
+}
+
+impl<'e> FromLisp<'e> for Buffer<'e> {
+    fn from_lisp(value: Value<'e>) -> Result<Buffer<'e>> {
+        let env = value.env;
+        if !env.call(bufferp, [value])?.is_not_nil() {
+            return env.signal(symbol::wrong_type_argument, (bufferp, value));
+        }
+        Ok(Buffer { value })
+    }
+}
+
+impl<'e> IntoLisp<'e> for Buffer<'e> {
+    #[inline(always)]
+    fn into_lisp(self, _: &'e Env) -> Result<Value<'e>> {
+        Ok(self.value)
+    }
+}
+
+impl Env {
+    /// Returns the current buffer. This is the equivalent of the Lisp function [`current-buffer`].
+    ///
+    /// [`current-buffer`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Current-Buffer.html
+    pub fn current_buffer(&self) -> Result<Buffer<'_>> {
+        Ok(Buffer { value: self.call("current-buffer", [])? })
+    }
+
+    /// Returns the buffer named `name`, or `None` if there is no such buffer. This is the
+    /// equivalent of the Lisp function [`get-buffer`].
+    ///
+    /// ```
+    /// # use emacs::{Env, Result};
+    /// fn log_prefix(env: &Env) -> Result<Option<String>> {
+    ///     match env.get_buffer("*my-module-log*")? {
+    ///         Some(buffer) => Ok(Some(buffer.substring(1, 11)?)),
+    ///         None => Ok(None),
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`get-buffer`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Buffer-Names.html
+    pub fn get_buffer(&self, name: &str) -> Result<Option<Buffer<'_>>> {
+        let value = self.call("get-buffer", (name,))?;
+        Ok(if value.is_not_nil() { Some(Buffer { value }) } else { None })
+    }
+
+    /// Returns the name of `buffer`. This is the equivalent of the Lisp function [`buffer-name`].
+    /// A `wrong-type-argument` error is signaled if `buffer` is not a live buffer.
+    ///
+    /// [`buffer-name`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Buffer-Names.html
+    pub fn buffer_name(&self, buffer: Value<'_>) -> Result<String> {
+        buffer.into_rust::<Buffer>()?.name()
+    }
+
+    /// Returns whether the current buffer is narrowed. This is the equivalent of the Lisp function
+    /// [`buffer-narrowed-p`].
+    ///
+    /// [`buffer-narrowed-p`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Narrowing.html
+    pub fn buffer_narrowed_p(&self) -> Result<bool> {
+        Ok(self.call("buffer-narrowed-p", [])?.is_not_nil())
+    }
+
+    /// Returns whether the region is active, i.e. Transient Mark mode is enabled, and the mark is
+    /// active. This is the equivalent of the Lisp function [`region-active-p`].
+    ///
+    /// [`region-active-p`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/The-Region.html
+    pub fn region_active_p(&self) -> Result<bool> {
+        Ok(self.call("region-active-p", [])?.is_not_nil())
+    }
+
+    /// Returns the position of point in the current buffer. This is the equivalent of the Lisp
+    /// function [`point`].
+    ///
+    /// [`point`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Point.html
+    pub fn point(&self) -> Result<usize> {
+        self.call("point", [])?.into_rust()
+    }
+
+    /// Moves point to `pos` in the current buffer. This is the equivalent of the Lisp command
+    /// [`goto-char`].
+    ///
+    /// Unlike `goto-char`, which silently moves to the nearest accessible position, this signals
+    /// `args-out-of-range` if `pos` is outside the accessible portion of the buffer.
+    ///
+    /// [`goto-char`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Character-Motion.html
+    pub fn goto_char(&self, pos: usize) -> Result<()> {
+        self.check_position(pos)?;
+        self.call("goto-char", (pos,))?;
+        Ok(())
+    }
+
+    /// Returns a new marker pointing to `pos` in the current buffer. This is the equivalent of the
+    /// Lisp function [`copy-marker`], called with a position.
+    ///
+    /// The marker does not advance when text is inserted at its position. An `args-out-of-range`
+    /// error is signaled if `pos` is outside the accessible portion of the buffer.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result};
+    /// fn insert_before_line(env: &Env, text: &str) -> Result<()> {
+    ///     let start = env.make_marker_at(env.line_beginning_position(None)? as usize)?;
+    ///     let origin = env.make_marker_at(env.point()?)?;
+    ///     env.goto_char(start.marker_position()?.unwrap_or(1))?;
+    ///     env.call("insert", (text,))?;
+    ///     env.goto_char(origin.marker_position()?.unwrap_or(1))
+    /// }
+    /// ```
+    ///
+    /// [`copy-marker`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Creating-Markers.html
+    pub fn make_marker_at(&self, pos: usize) -> Result<Value<'_>> {
+        self.check_position(pos)?;
+        self.call("copy-marker", (pos,))
+    }
+
+    /// Signals `args-out-of-range` if `pos` is outside the accessible portion of the current buffer.
+    pub(crate) fn check_position(&self, pos: usize) -> Result<()> {
+        let min: usize = self.call("point-min", [])?.into_rust()?;
+        let max: usize = self.call("point-max", [])?.into_rust()?;
+        if pos < min || pos > max {
+            return self.signal(symbol::args_out_of_range, (pos, min, max));
+        }
+        Ok(())
+    }
+
+    /// Returns the position of the beginning of the current line. If `n` is given and not 1, the
+    /// line `n - 1` lines away is used instead. This is the equivalent of the Lisp function
+    /// [`line-beginning-position`].
+    ///
+    /// [`line-beginning-position`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Text-Lines.html
+    pub fn line_beginning_position(&self, n: Option<i64>) -> Result<i64> {
+        self.call("line-beginning-position", (n,))?.into_rust()
+    }
+
+    /// Returns the position of the end of the current line. If `n` is given and not 1, the line
+    /// `n - 1` lines away is used instead. This is the equivalent of the Lisp function
+    /// [`line-end-position`].
+    ///
+    /// [`line-end-position`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Text-Lines.html
+    pub fn line_end_position(&self, n: Option<i64>) -> Result<i64> {
+        self.call("line-end-position", (n,))?.into_rust()
+    }
+
+    /// Returns a hash of the contents of `buffer`, or of the current buffer if `buffer` is `None`, as
+    /// a hex string. This is the equivalent of the Lisp function [`buffer-hash`], which requires
+    /// Emacs 26 or later.
+    ///
+    /// The hash covers the whole buffer, ignoring narrowing and text properties. It is cheaper than
+    /// hashing the result of `buffer-string`, and only changes when the text does, so it can be
+    /// used to key caches on a buffer's contents.
+    ///
+    /// [`buffer-hash`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Checksum_002fHash.html
+    pub fn buffer_hash(&self, buffer: Option<Value<'_>>) -> Result<String> {
+        self.call("buffer-hash", (buffer,))?.into_rust()
+    }
+
+    /// Inserts the most recent kill at point, leaving the mark at the other end of the inserted
+    /// text. This is the equivalent of the Lisp command [`yank`].
+    ///
+    /// [`yank`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Yank-Commands.html
+    pub fn yank(&self) -> Result<()> {
+        self.call("yank", [])?;
+        Ok(())
+    }
+
+    /// Replaces the just-yanked text with the previous kill in the kill ring. This is the
+    /// equivalent of the Lisp command [`yank-pop`].
+    ///
+    /// As with the command, this must directly follow a yank, i.e. `last-command` must be `yank`.
+    /// Otherwise, newer versions of Emacs prompt for the text to insert in the minibuffer.
+    ///
+    /// [`yank-pop`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Yank-Commands.html
+    pub fn yank_pop(&self) -> Result<()> {
+        self.call("yank-pop", [])?;
+        Ok(())
+    }
+
+    /// Rotates the kill ring by `n` places, and returns the kill it then points to. If
+    /// `do_not_move` is true, the kill ring is not rotated, and the kill `n` places away is
+    /// returned. This is the equivalent of the Lisp function [`current-kill`].
+    ///
+    /// [`current-kill`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Low_002dLevel-Kill-Ring.html
+    pub fn current_kill(&self, n: i64, do_not_move: bool) -> Result<String> {
+        self.call("current-kill", (n, do_not_move))?.into_rust()
+    }
+
+    /// Replaces the accessible portion of the current buffer with the accessible portion of
+    /// `source`, a buffer or a buffer name. This is the equivalent of the Lisp function
+    /// [`replace-buffer-contents`].
+    ///
+    /// Unlike erasing the buffer and inserting the new text, this only changes the parts that
+    /// differ, so point, markers, and text properties in the unchanged parts are preserved. If
+    /// computing the difference takes longer than `max_secs` seconds, the text is replaced
+    /// destructively instead, and `false` is returned. Passing `Some` for `max_secs` requires
+    /// Emacs 27 or later.
+    ///
+    /// [`replace-buffer-contents`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Replacing.html
+    pub fn replace_buffer_contents(&self, source: Value, max_secs: Option<f64>) -> Result<bool> {
+        let replaced = match max_secs {
+            // The optional argument was added in Emacs 27.
+            Some(max_secs) => self.call("replace-buffer-contents", (source, max_secs))?,
+            None => self.call("replace-buffer-contents", [source])?,
+        };
+        Ok(replaced.is_not_nil())
+    }
+
+    /// Calls `f` with `buffer`, a buffer or a buffer name, temporarily made the current buffer.
+    /// This is the equivalent of the Lisp macro [`with-current-buffer`].
+    ///
+    /// As with the Lisp macro, the previously current buffer is made current again afterward, even
+    /// if `f` returns an error or panics, unless it has been killed in the meantime. An error from
+    /// `f` takes precedence over one from switching back.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result, Value};
+    /// fn append_to<'e>(env: &'e Env, buffer: Value<'e>, text: &str) -> Result<()> {
+    ///     env.with_buffer(buffer, |env| {
+    ///         env.call("goto-char", [env.call("point-max", [])?])?;
+    ///         env.call("insert", (text,))?;
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// [`with-current-buffer`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Current-Buffer.html
+    pub fn with_buffer<T, F>(&self, buffer: Value<'_>, f: F) -> Result<T>
+    where
+        F: FnOnce(&Env) -> Result<T>,
+    {
+        let previous = self.call("current-buffer", [])?;
+        self.call("set-buffer", [buffer])?;
+        let restore = Restore::new(self, move |env| {
+            if env.call("buffer-live-p", [previous])?.is_not_nil() {
+                env.call("set-buffer", [previous])?;
+            }
+            Ok(())
+        });
+        restore.finish(f(self))
+    }
+
+    /// Calls `f`, combining all changes it makes to the current buffer into a single undo step.
+    /// This is the equivalent of the Lisp macro [`with-undo-amalgamate`].
+    ///
+    /// As with the Lisp macro, [`undo-outer-limit`] is bound to nil, and [`undo-limit`] and
+    /// [`undo-strong-limit`] to `most-positive-fixnum`, so that garbage collection does not truncate
+    /// the undo list in the middle of the changes. They are combined even if `f` returns an error
+    /// or panics. That error is returned in preference to one from combining the changes.
+    ///
+    /// [`with-undo-amalgamate`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Atomic-Changes.html
+    /// [`undo-outer-limit`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Maintaining-Undo.html
+    /// [`undo-limit`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Maintaining-Undo.html
+    /// [`undo-strong-limit`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Maintaining-Undo.html
+    pub fn with_undo_amalgamate<R, F: FnOnce() -> Result<R>>(&self, f: F) -> Result<R> {
+        let handle = self.call("prepare-change-group", [])?;
+        let nil = ().into_lisp(self)?;
+        let max = self.call("symbol-value", [self.intern("most-positive-fixnum")?])?;
+        let limits = [("undo-outer-limit", nil), ("undo-limit", max), ("undo-strong-limit", max)];
+        self.let_bind(&limits, || {
+            let restore = Restore::new(self, move |env| {
+                env.call("accept-change-group", [handle])?;
+                env.call("undo-amalgamate-change-group", [handle])?;
+                Ok(())
+            });
+            let result = self.call("activate-change-group", [handle]).and_then(|_| f());
+            restore.finish(result)
+        })
+    }
+
+    /// Calls `f` with undo recording disabled in the current buffer, by binding
+    /// [`buffer-undo-list`] to `t`, then restores the previous undo list. This makes large
+    /// programmatic edits faster, when they don't need to be undone.
+    ///
+    /// Note that changes made by `f` are not recorded at all, so undoing earlier changes afterward
+    /// may corrupt the buffer's text, if `f` has shifted their positions.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result};
+    /// fn insert_lines(env: &Env, lines: &[String]) -> Result<()> {
+    ///     env.with_undo_disabled(|| {
+    ///         for line in lines {
+    ///             env.call("insert", (line.as_str(), "\n"))?;
+    ///         }
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// [`buffer-undo-list`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Undo.html
+    pub fn with_undo_disabled<R, F: FnOnce() -> Result<R>>(&self, f: F) -> Result<R> {
+        self.let_bind(&[("buffer-undo-list", self.intern("t")?)], f)
+    }
+
+    /// Calls `f`, then restores the current buffer's modified flag to what it was before, as
+    /// returned by [`buffer-modified-p`]. This is useful for passes that temporarily change the
+    /// buffer's text, then revert it.
+    ///
+    /// Unlike [`with-silent-modifications`], this does not inhibit modification hooks, or undo
+    /// recording. The flag is restored even if `f` returns an error or panics, or switches to another
+    /// buffer.
+    /// An error from `f` takes precedence over one from restoring the flag.
+    ///
+    /// [`buffer-modified-p`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Buffer-Modification.html
+    /// [`with-silent-modifications`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Buffer-Modification.html
+    pub fn preserving_modified_flag<R, F: FnOnce() -> Result<R>>(&self, f: F) -> Result<R> {
+        let buffer = self.call("current-buffer", [])?;
+        let flag = self.call("buffer-modified-p", [])?;
+        let restore = Restore::new(self, move |env| {
+            let current = env.call("current-buffer", [])?;
+            env.call("set-buffer", [buffer])?;
+            env.call("restore-buffer-modified-p", [flag])?;
+            env.call("set-buffer", [current])?;
+            Ok(())
+        });
+        restore.finish(f())
+    }
+}
+
+impl<'e> Value<'e> {
+    /// Returns the position this marker points to, or `None` if it points nowhere. This is the
+    /// equivalent of the Lisp function [`marker-position`]. A `wrong-type-argument` error is
+    /// signaled if the value is not a marker.
+    ///
+    /// [`marker-position`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Information-from-Markers.html
+    pub fn marker_position(&self) -> Result<Option<usize>> {
+        self.env.call("marker-position", [*self])?.into_rust()
+    }
+}
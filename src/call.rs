@@ -2,8 +2,9 @@ use std::borrow::BorrowMut;
 
 use emacs_module::emacs_value;
 use emacs_macros;
+use once_cell::sync::OnceCell;
 
-use crate::{Env, Value, Result, IntoLisp, global::{GlobalRef, OnceGlobalRef}};
+use crate::{subr, Env, Value, Result, FromLisp, IntoLisp, global::{GlobalRef, OnceGlobalRef}};
 
 // TODO: Seal this trait, for safety reasons.
 pub unsafe trait IntoLispArgs<'e> {
@@ -91,9 +92,13 @@ impl Env {
     /// }
     /// ```
     ///
+    /// Since this is the equivalent of Lisp's `funcall`, `func` cannot be a macro or a special form,
+    /// e.g. `when`, `if`, `setq`. To use those, construct a form and evaluate it with [`eval_form`].
+    ///
     /// [`Value`]: struct.Value.html
     /// [`func.call`]: struct.Value.html#method.call
     /// [`IntoLisp`]: trait.IntoLisp.html
+    /// [`eval_form`]: #method.eval_form
     #[inline]
     pub fn call<'e, F, A>(&'e self, func: F, args: A) -> Result<Value<'_>>
         where
@@ -123,6 +128,151 @@ impl Env {
     {
         func.into_lisp_callable(self)?.call_unprotected(args)
     }
+
+    /// Like [`call`], except that the returned value is converted into a Rust value of the given
+    /// type, with [`FromLisp`].
+    ///
+    /// ```
+    /// # use emacs::{Env, Result};
+    /// fn add(env: &Env) -> Result<i64> {
+    ///     let n: i64 = env.funcall("+", (1, 2))?;
+    ///     Ok(n)
+    /// }
+    /// ```
+    ///
+    /// [`call`]: #method.call
+    /// [`FromLisp`]: trait.FromLisp.html
+    #[inline]
+    pub fn funcall<'e, T, F, A>(&'e self, func: F, args: A) -> Result<T>
+        where
+            T: FromLisp<'e>,
+            F: IntoLispCallable<'e>,
+            A: IntoLispArgs<'e>,
+    {
+        self.call(func, args)?.into_rust()
+    }
+
+    /// Calls a Lisp function, passing the elements of the Lisp list `args` as arguments, then
+    /// converts the result into a Rust value of the given type, with [`FromLisp`]. This is the
+    /// equivalent of the Lisp function [`apply`].
+    ///
+    /// Errors signaled by the function are propagated as usual. A `wrong-type-argument` error is
+    /// signaled if `args` is not a list.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result, Value};
+    /// fn sum<'e>(env: &'e Env, numbers: Value<'e>) -> Result<i64> {
+    ///     env.apply("+", numbers)
+    /// }
+    /// ```
+    ///
+    /// [`FromLisp`]: trait.FromLisp.html
+    /// [`apply`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Calling-Functions.html
+    pub fn apply<'e, T, F>(&'e self, func: F, args: Value<'e>) -> Result<T>
+        where
+            T: FromLisp<'e>,
+            F: IntoLispCallable<'e>,
+    {
+        self.call(subr::apply, (func.into_lisp_callable(self)?, args))?.into_rust()
+    }
+
+    /// Like [`apply`], except that `leading` is passed before the elements of the Lisp list
+    /// `trailing`, like the Lisp function [`apply`][lisp-apply] with several arguments.
+    ///
+    /// `leading` should be an array/slice of [`Value`], or a tuple of different types, each
+    /// implementing [`IntoLisp`].
+    ///
+    /// ```
+    /// # use emacs::{Env, Result, Value};
+    /// // (apply #'format "%s: %s" name rest)
+    /// fn describe<'e>(env: &'e Env, name: &str, rest: Value<'e>) -> Result<String> {
+    ///     env.apply_with("format", ("%s: %s", name), rest)
+    /// }
+    /// ```
+    ///
+    /// [`apply`]: #method.apply
+    /// [lisp-apply]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Calling-Functions.html
+    /// [`Value`]: struct.Value.html
+    /// [`IntoLisp`]: trait.IntoLisp.html
+    pub fn apply_with<'e, T, F, A>(&'e self, func: F, leading: A, trailing: Value<'e>) -> Result<T>
+        where
+            T: FromLisp<'e>,
+            F: IntoLispCallable<'e>,
+            A: IntoLispArgs<'e>,
+    {
+        let func = func.into_lisp_callable(self)?;
+        let mut leading = leading.into_lisp_args(self)?;
+        let leading: &mut [emacs_value] = leading.borrow_mut();
+        let mut args = Vec::with_capacity(leading.len() + 2);
+        args.push(func);
+        // Safety: The raw values were just created by this env, and are still alive.
+        args.extend(leading.iter().map(|raw| unsafe { Value::new(*raw, self) }));
+        args.push(trailing);
+        self.call(subr::apply, &args)?.into_rust()
+    }
+}
+
+impl Env {
+    /// Evaluates the given Lisp form, with lexical binding enabled. This is the equivalent of the
+    /// Lisp function [`eval`].
+    ///
+    /// Unlike [`call`], which can only call functions, this properly handles forms whose head is a
+    /// macro or a special form, e.g. `when`, `if`, `setq`. Use [`call`] when the function and its
+    /// arguments are already known, since it avoids constructing the form.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use emacs::{defun, Env, Value, Result};
+    /// #[defun]
+    /// fn when_positive(env: &Env, x: i64) -> Result<Value<'_>> {
+    ///     // (when (> x 0) x)
+    ///     let test = env.list((env.intern(">")?, x, 0))?;
+    ///     env.eval_form(env.list((env.intern("when")?, test, x))?)
+    /// }
+    /// ```
+    ///
+    /// [`eval`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Eval.html
+    /// [`call`]: #method.call
+    pub fn eval_form<'e>(&'e self, form: Value<'e>) -> Result<Value<'e>> {
+        self.call("eval", (form, true))
+    }
+
+    /// Reads a Lisp form from the string `form`, then evaluates it with lexical binding enabled.
+    /// See [`eval_in`].
+    ///
+    /// ```
+    /// # use emacs::{Env, Result};
+    /// fn emacs_build_time(env: &Env) -> Result<String> {
+    ///     env.eval("(format-time-string \"%F\" emacs-build-time)")?.into_rust()
+    /// }
+    /// ```
+    ///
+    /// [`eval_in`]: #method.eval_in
+    pub fn eval(&self, form: &str) -> Result<Value<'_>> {
+        self.eval_in(form, true)
+    }
+
+    /// Reads a Lisp form from the string `form` with [`read-from-string`], then evaluates it with
+    /// [`eval`], with lexical binding enabled if `lexical` is true.
+    ///
+    /// The string must contain exactly one form, optionally surrounded by whitespace and comments.
+    /// If it contains more, `invalid-read-syntax` is signaled, without evaluating anything. Errors
+    /// from reading, e.g. `end-of-file` for an empty or unbalanced string, are therefore signaled
+    /// before evaluation starts, and can be told apart from errors signaled by the form itself.
+    ///
+    /// [`read-from-string`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Input-Functions.html
+    /// [`eval`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Eval.html
+    pub fn eval_in(&self, form: &str, lexical: bool) -> Result<Value<'_>> {
+        // This returns (OBJECT . FINAL-STRING-INDEX).
+        let read = self.call("read-from-string", (form,))?;
+        let rest = self.call("substring", (form, read.cdr::<Value>()?))?;
+        if self.call("string-match-p", ("\\`\\(?:[ \t\n\r\x0c]\\|;.*\\)*\\'", rest))?.is_not_nil() {
+            return self.call("eval", (read.car::<Value>()?, lexical));
+        }
+        let invalid_read_syntax = self.intern("invalid-read-syntax")?;
+        self.signal(invalid_read_syntax, ("Trailing garbage following expression", rest))
+    }
 }
 
 impl GlobalRef {
@@ -160,6 +310,22 @@ impl GlobalRef {
     {
         self.bind(env).call_unprotected(args)
     }
+
+    /// Like [`call`], except that the returned value is converted into a Rust value of the given
+    /// type, with [`FromLisp`]. This is useful for calling a function symbol that is interned once,
+    /// e.g. by [`use_symbols!`], in a hot loop.
+    ///
+    /// [`call`]: #method.call
+    /// [`FromLisp`]: trait.FromLisp.html
+    /// [`use_symbols!`]: macro.use_symbols.html
+    #[inline]
+    pub fn funcall<'e, T, A>(&'e self, env: &'e Env, args: A) -> Result<T>
+        where
+            T: FromLisp<'e>,
+            A: IntoLispArgs<'e>,
+    {
+        self.call(env, args)?.into_rust()
+    }
 }
 
 // We can implement IntoLispArgs for IntoLisp types (after breaking up this implementation).
@@ -206,3 +372,88 @@ impl<'e> IntoLispCallable<'e> for &'e OnceGlobalRef {
         self.bind(env).into_lisp_callable(env)
     }
 }
+
+/// Declares a Lisp function symbol that is interned on first use, then cached in a [`LispFn`].
+/// Calling it through [`Env::call`] is faster than calling by name, which interns the symbol on
+/// every call.
+///
+/// ```
+/// # use emacs::{Env, Result};
+/// fn sum(env: &Env, n: i64) -> Result<i64> {
+///     let mut sum = 0;
+///     for i in 0..n {
+///         sum = env.funcall(emacs::lisp_fn!("+"), (sum, i))?;
+///     }
+///     Ok(sum)
+/// }
+/// ```
+///
+/// [`LispFn`]: struct.LispFn.html
+/// [`Env::call`]: struct.Env.html#method.call
+#[macro_export]
+macro_rules! lisp_fn {
+    ($name:expr) => {
+        {
+            static FUNC: $crate::LispFn = $crate::LispFn::new($name);
+            &FUNC
+        }
+    };
+}
+
+/// A Lisp function symbol that is interned on first use, then kept alive by a [`GlobalRef`]. It is
+/// usually declared with [`lisp_fn!`], or stored in a named `static`, to be shared by several
+/// functions.
+///
+/// ```
+/// # use emacs::{Env, LispFn, Result, Value};
+/// static HELPER: LispFn = LispFn::new("my-pkg-helper");
+///
+/// fn process<'e>(env: &'e Env, items: &[Value<'e>]) -> Result<()> {
+///     for item in items {
+///         env.call(&HELPER, [*item])?;
+///     }
+///     Ok(())
+/// }
+/// ```
+///
+/// Unlike the symbols declared by [`use_symbols!`], this does not need to be initialized when the
+/// module is loaded. Since the symbol, not its function definition, is cached, later
+/// redefinitions and advices are respected.
+///
+/// [`GlobalRef`]: struct.GlobalRef.html
+/// [`lisp_fn!`]: macro.lisp_fn.html
+/// [`use_symbols!`]: macro.use_symbols.html
+#[derive(Debug)]
+pub struct LispFn {
+    name: &'static str,
+    symbol: OnceCell<GlobalRef>,
+}
+
+impl LispFn {
+    /// Creates a cache for the function symbol with the given name. The symbol is interned when it
+    /// is first used.
+    pub const fn new(name: &'static str) -> Self {
+        Self { name, symbol: OnceCell::new() }
+    }
+
+    /// Returns the name of the function symbol.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the function symbol, interning it if this is the first use.
+    pub fn bind<'e>(&'e self, env: &'e Env) -> Result<Value<'e>> {
+        let symbol = self.symbol.get_or_try_init(|| {
+            env.intern(self.name).map(|symbol| symbol.make_global_ref())
+        })?;
+        Ok(symbol.bind(env))
+    }
+}
+
+impl<'e> IntoLispCallable<'e> for &'e LispFn {
+    #[inline(always)]
+    fn into_lisp_callable(self, env: &'e Env) -> Result<Value<'e>> {
+        self.bind(env)
+    }
+}
@@ -0,0 +1,51 @@
+//! Conversion between Rust bytes and Lisp strings through Emacs's coding systems.
+
+use crate::{Env, Result};
+
+impl Env {
+    /// Encodes the given string using the named coding system, returning the raw encoded bytes.
+    /// This is the equivalent of the Lisp function [`encode-coding-string`].
+    ///
+    /// A `coding-system-error` is signaled if `coding` does not name a valid coding system.
+    ///
+    /// [`encode-coding-string`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Explicit-Encoding.html
+    pub fn encode_coding_string(&self, s: &str, coding: &str) -> Result<Vec<u8>> {
+        let encoded = self.call("encode-coding-string", (s, self.intern(coding)?))?;
+        self.string_raw_bytes(encoded)
+    }
+
+    /// Decodes the given bytes using the named coding system. This is the equivalent of the Lisp
+    /// function [`decode-coding-string`].
+    ///
+    /// A `coding-system-error` is signaled if `coding` does not name a valid coding system.
+    ///
+    /// [`decode-coding-string`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Explicit-Encoding.html
+    pub fn decode_coding_string(&self, bytes: &[u8], coding: &str) -> Result<String> {
+        let encoded = self.make_unibyte_string(bytes)?;
+        self.call("decode-coding-string", (encoded, self.intern(coding)?))?.into_rust()
+    }
+
+    /// Calls `f` with the named coding system used to read and write files, and to communicate
+    /// with processes, i.e. with the variables [`coding-system-for-read`] and
+    /// [`coding-system-for-write`] bound to it. See [`let_bind`].
+    ///
+    /// A `coding-system-error` is signaled if `coding` does not name a valid coding system.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result, Value};
+    /// fn insert_latin_1_file<'e>(env: &'e Env, file: &str) -> Result<Value<'e>> {
+    ///     env.with_coding_system("latin-1", || env.call("insert-file-contents", (file,)))
+    /// }
+    /// ```
+    ///
+    /// [`coding-system-for-read`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Specifying-Coding-Systems.html
+    /// [`coding-system-for-write`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Specifying-Coding-Systems.html
+    /// [`let_bind`]: #method.let_bind
+    pub fn with_coding_system<R, F>(&self, coding: &str, f: F) -> Result<R>
+    where
+        F: FnOnce() -> Result<R>,
+    {
+        let coding = self.call("check-coding-system", [self.intern(coding)?])?;
+        self.let_bind(&[("coding-system-for-read", coding), ("coding-system-for-write", coding)], f)
+    }
+}
@@ -0,0 +1,452 @@
+//! Helpers for inspecting and customizing how text is displayed.
+
+use std::path::Path;
+
+use crate::{error::Restore, symbol, Buffer, Env, FromLisp, IntoLisp, Result, Value};
+
+crate::use_symbols! {
+    kw_family => ":family"
+    kw_foreground => ":foreground"
+    kw_background => ":background"
+    kw_weight => ":weight"
+    kw_slant => ":slant"
+    kw_height => ":height"
+    kw_underline => ":underline"
+    kw_inherit => ":inherit"
+    overlayp
+}
+
+/// An anonymous face, i.e. a set of [face attributes], which can be used as the value of a `face`
+/// text property. Attributes that are `None` are left unspecified, so they are taken from the
+/// underlying faces.
+///
+/// This is converted into a Lisp plist, e.g. `(:foreground "red" :weight bold)`.
+///
+/// ```
+/// # use emacs::{Env, FaceSpec, IntoLisp, Result, Value};
+/// fn warning_text<'e>(env: &'e Env, text: &str) -> Result<Value<'e>> {
+///     let face = FaceSpec {
+///         foreground: Some("red".into()),
+///         weight: Some("bold".into()),
+///         ..Default::default()
+///     };
+///     env.propertized(text).with_property_on_all("face", face.into_lisp(env)?).build()
+/// }
+/// ```
+///
+/// [face attributes]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Face-Attributes.html
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FaceSpec {
+    /// The font family name, e.g. `"Monospace"`.
+    pub family: Option<String>,
+    /// The foreground color, e.g. `"red"`, or `"#ff0000"`.
+    pub foreground: Option<String>,
+    /// The background color.
+    pub background: Option<String>,
+    /// The font weight, e.g. `"bold"`, `"normal"`, or `"light"`. It is converted into a symbol.
+    pub weight: Option<String>,
+    /// The font slant, e.g. `"italic"`, or `"normal"`. It is converted into a symbol.
+    pub slant: Option<String>,
+    /// The font height, relative to the underlying face's, e.g. `1.5`.
+    pub height: Option<f64>,
+    /// Whether the text is underlined.
+    pub underline: Option<bool>,
+    /// The name of a face to inherit unspecified attributes from.
+    pub inherit: Option<String>,
+}
+
+impl<'e> IntoLisp<'e> for FaceSpec {
+    fn into_lisp(self, env: &'e Env) -> Result<Value<'e>> {
+        let mut plist = vec![];
+        if let Some(family) = self.family {
+            plist.push(kw_family.bind(env));
+            plist.push(family.into_lisp(env)?);
+        }
+        if let Some(foreground) = self.foreground {
+            plist.push(kw_foreground.bind(env));
+            plist.push(foreground.into_lisp(env)?);
+        }
+        if let Some(background) = self.background {
+            plist.push(kw_background.bind(env));
+            plist.push(background.into_lisp(env)?);
+        }
+        if let Some(weight) = self.weight {
+            plist.push(kw_weight.bind(env));
+            plist.push(env.intern(&weight)?);
+        }
+        if let Some(slant) = self.slant {
+            plist.push(kw_slant.bind(env));
+            plist.push(env.intern(&slant)?);
+        }
+        if let Some(height) = self.height {
+            plist.push(kw_height.bind(env));
+            plist.push(height.into_lisp(env)?);
+        }
+        if let Some(underline) = self.underline {
+            plist.push(kw_underline.bind(env));
+            plist.push(underline.into_lisp(env)?);
+        }
+        if let Some(inherit) = self.inherit {
+            plist.push(kw_inherit.bind(env));
+            plist.push(env.intern(&inherit)?);
+        }
+        env.list(&plist)
+    }
+}
+
+/// Where [`Env::create_image`] gets an image from.
+///
+/// [`Env::create_image`]: struct.Env.html#method.create_image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageSource<'a> {
+    /// An image file. A relative path is looked up in `image-load-path`.
+    File(&'a Path),
+    /// The contents of an image file, e.g. a PNG produced in memory.
+    Data(&'a [u8]),
+}
+
+/// A type that represents Lisp overlays. This is a wrapper around [`Value`] that provides
+/// overlay-specific methods. It is usually created by [`Env::make_overlay`].
+///
+/// Arguments to #[[`defun`]] having this type will be type-checked. If you want to omit, or delay
+/// this type checking, use [`Value`] instead. Note that a deleted overlay is still an overlay, so
+/// methods that need its buffer signal an `error` if it has been deleted.
+///
+/// ```
+/// # use emacs::{Env, Result};
+/// fn highlight_matches(env: &Env, regexp: &str) -> Result<()> {
+///     env.goto_char(1)?;
+///     while env.call("re-search-forward", (regexp, (), true))?.is_not_nil() {
+///         let start: usize = env.call("match-beginning", (0,))?.into_rust()?;
+///         let end: usize = env.call("match-end", (0,))?.into_rust()?;
+///         env.make_overlay(start, end, None)?.put("face", env.intern("highlight")?)?;
+///     }
+///     Ok(())
+/// }
+/// ```
+///
+/// [`Value`]: struct.Value.html
+/// [`defun`]: attr.defun.html
+/// [`Env::make_overlay`]: struct.Env.html#method.make_overlay
+#[derive(Debug, Clone, Copy)]
+pub struct Overlay<'e> {
+    value: Value<'e>,
+}
+
+impl<'e> Overlay<'e> {
+    /// Returns the position at which the overlay starts. This is the equivalent of the Lisp
+    /// function [`overlay-start`].
+    ///
+    /// [`overlay-start`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Managing-Overlays.html
+    pub fn start(&self) -> Result<usize> {
+        self.check_live()?;
+        self.value.env.call("overlay-start", [self.value])?.into_rust()
+    }
+
+    /// Returns the position at which the overlay ends. This is the equivalent of the Lisp function
+    /// [`overlay-end`].
+    ///
+    /// [`overlay-end`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Managing-Overlays.html
+    pub fn end(&self) -> Result<usize> {
+        self.check_live()?;
+        self.value.env.call("overlay-end", [self.value])?.into_rust()
+    }
+
+    /// Moves the overlay to span the positions `start` to `end` in its buffer. This is the
+    /// equivalent of the Lisp function [`move-overlay`].
+    ///
+    /// An `args-out-of-range` error is signaled if either position is outside the buffer.
+    ///
+    /// [`move-overlay`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Managing-Overlays.html
+    pub fn move_to(&self, start: usize, end: usize) -> Result<()> {
+        self.check_live()?;
+        let env = self.value.env;
+        let buffer = env.call("overlay-buffer", [self.value])?;
+        check_overlay_positions(env, buffer, start, end)?;
+        env.call("move-overlay", (self.value, start, end))?;
+        Ok(())
+    }
+
+    /// Sets the overlay's property `prop` to `value`. This is the equivalent of the Lisp function
+    /// [`overlay-put`].
+    ///
+    /// [`overlay-put`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Overlay-Properties.html
+    pub fn put<V: IntoLisp<'e>>(&self, prop: &str, value: V) -> Result<()> {
+        let env = self.value.env;
+        env.call("overlay-put", (self.value, env.intern(prop)?, value))?;
+        Ok(())
+    }
+
+    /// Returns the value of the overlay's property `prop`, or `nil` if it has none. This is the
+    /// equivalent of the Lisp function [`overlay-get`].
+    ///
+    /// [`overlay-get`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Overlay-Properties.html
+    pub fn get(&self, prop: &str) -> Result<Value<'e>> {
+        let env = self.value.env;
+        env.call("overlay-get", (self.value, env.intern(prop)?))
+    }
+
+    /// Deletes the overlay from its buffer. This is the equivalent of the Lisp function
+    /// [`delete-overlay`]. Deleting an overlay that has already been deleted does nothing.
+    ///
+    /// [`delete-overlay`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Managing-Overlays.html
+    pub fn delete(&self) -> Result<()> {
+        self.value.env.call("delete-overlay", [self.value])?;
+        Ok(())
+    }
+
+    /// Returns whether the overlay has not been deleted, i.e. it still belongs to a buffer.
+    pub fn is_live(&self) -> Result<bool> {
+        Ok(self.value.env.call("overlay-buffer", [self.value])?.is_not_nil())
+    }
+
+    fn check_live(&self) -> Result<()> {
+        if !self.is_live()? {
+            return self.value.env.signal(symbol::error, ("Overlay has been deleted", self.value));
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn value(&self) -> Value<'e> {
+        self.value
+    }
+}
+
+impl<'e> FromLisp<'e> for Overlay<'e> {
+    fn from_lisp(value: Value<'e>) -> Result<Overlay<'e>> {
+        let env = value.env;
+        if !env.call(overlayp, [value])?.is_not_nil() {
+            return env.signal(symbol::wrong_type_argument, (overlayp, value));
+        }
+        Ok(Overlay { value })
+    }
+}
+
+impl<'e> IntoLisp<'e> for Overlay<'e> {
+    #[inline(always)]
+    fn into_lisp(self, _: &'e Env) -> Result<Value<'e>> {
+        Ok(self.value)
+    }
+}
+
+/// Signals `args-out-of-range` if `start` or `end` is outside `buffer`, ignoring narrowing, since
+/// overlays can be placed anywhere in their buffer.
+fn check_overlay_positions(env: &Env, buffer: Value<'_>, start: usize, end: usize) -> Result<()> {
+    let max = env.call("buffer-size", [buffer])?.into_rust::<usize>()? + 1;
+    for &pos in &[start, end] {
+        if pos < 1 || pos > max {
+            return env.signal(symbol::args_out_of_range, (pos, 1, max));
+        }
+    }
+    Ok(())
+}
+
+impl Env {
+    /// Creates an overlay spanning the positions `start` to `end` in `buffer`, or in the current
+    /// buffer if `buffer` is `None`. This is the equivalent of the Lisp function
+    /// [`make-overlay`].
+    ///
+    /// The positions can be given in either order. An `args-out-of-range` error is signaled if
+    /// either is outside the buffer, and a `wrong-type-argument` error if `buffer` is not a live
+    /// buffer.
+    ///
+    /// [`make-overlay`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Managing-Overlays.html
+    pub fn make_overlay<'e>(
+        &'e self,
+        start: usize,
+        end: usize,
+        buffer: Option<Value<'e>>,
+    ) -> Result<Overlay<'e>> {
+        let buffer = match buffer {
+            Some(buffer) => buffer.into_rust::<Buffer>()?,
+            None => self.current_buffer()?,
+        };
+        buffer.check_live()?;
+        check_overlay_positions(self, buffer.value(), start, end)?;
+        Ok(Overlay { value: self.call("make-overlay", (start, end, buffer))? })
+    }
+
+    /// Returns the size of the text between positions `from` and `to` in `window`, as a pair of
+    /// `(width, height)` in pixels. If `window` is `None`, the selected window is used. This is the
+    /// equivalent of the Lisp function [`window-text-pixel-size`].
+    ///
+    /// On text terminals, each character counts as 1 pixel.
+    ///
+    /// [`window-text-pixel-size`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Size-of-Displayed-Text.html
+    pub fn window_text_pixel_size(
+        &self,
+        window: Option<Value<'_>>,
+        from: i64,
+        to: i64,
+    ) -> Result<(i64, i64)> {
+        let size = self.call("window-text-pixel-size", (window, from, to))?;
+        Ok((size.car()?, size.cdr()?))
+    }
+
+    /// Calls `f`, then restores the selected frame's window configuration to what it was before.
+    /// This is the equivalent of the Lisp macro [`save-window-excursion`].
+    ///
+    /// As with the Lisp macro, the window configuration is restored even if `f` returns an error or
+    /// panics. That error is then returned, even if restoring the configuration also fails.
+    ///
+    /// [`save-window-excursion`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Window-Configurations.html
+    pub fn save_window_excursion<R, F: FnOnce() -> Result<R>>(&self, f: F) -> Result<R> {
+        let configuration = self.call("current-window-configuration", [])?;
+        let restore = Restore::new(self, move |env| {
+            env.call("set-window-configuration", [configuration])?;
+            Ok(())
+        });
+        restore.finish(f())
+    }
+
+    /// Returns the overlays that overlap the region between positions `start` and `end` in the
+    /// current buffer. This is the equivalent of the Lisp function [`overlays-in`].
+    ///
+    /// [`overlays-in`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Finding-Overlays.html
+    pub fn overlays_in(&self, start: i64, end: i64) -> Result<Vec<Value<'_>>> {
+        self.call("overlays-in", (start, end))?.into_rust()
+    }
+
+    /// Returns the overlays that contain the character at position `pos` in the current buffer.
+    /// This is the equivalent of the Lisp function [`overlays-at`].
+    ///
+    /// [`overlays-at`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Finding-Overlays.html
+    pub fn overlays_at(&self, pos: i64) -> Result<Vec<Value<'_>>> {
+        self.call("overlays-at", (pos,))?.into_rust()
+    }
+
+    /// Creates a new, empty display table. This is the equivalent of the Lisp function
+    /// [`make-display-table`].
+    ///
+    /// [`make-display-table`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Display-Tables.html
+    pub fn make_display_table(&self) -> Result<Value<'_>> {
+        self.call("make-display-table", [])
+    }
+
+    /// Sets the entry for the character `c` in the display table `table`, so that `c` is displayed
+    /// as the characters `glyphs`, in the default face. If `glyphs` is `None`, the entry is cleared,
+    /// so that `c` is displayed normally.
+    ///
+    /// See [Display Tables] for how display tables are used.
+    ///
+    /// [Display Tables]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Display-Tables.html
+    pub fn set_display_table_entry(
+        &self,
+        table: Value<'_>,
+        c: char,
+        glyphs: Option<&[char]>,
+    ) -> Result<()> {
+        let entry = match glyphs {
+            Some(glyphs) => {
+                let vector = self.make_vector(glyphs.len(), ())?;
+                for (i, glyph) in glyphs.iter().enumerate() {
+                    vector.set(i, *glyph as i64)?;
+                }
+                vector.value()
+            }
+            None => ().into_lisp(self)?,
+        };
+        self.call("aset", (table, c as i64, entry))?;
+        Ok(())
+    }
+
+    /// Inserts a button labeled `label` at point in the current buffer, returning the button. This
+    /// is the equivalent of the Lisp function [`insert-button`].
+    ///
+    /// When the button is activated (e.g. clicked), `action` is called with the button.
+    ///
+    /// Since `action` is turned into a Lisp function by [`make_closure`], it is dropped once the
+    /// button has been deleted, and is garbage-collected.
+    ///
+    /// [`insert-button`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Making-Buttons.html
+    /// [`make_closure`]: #method.make_closure
+    pub fn insert_button<F>(&self, label: &str, action: F) -> Result<Value<'_>>
+    where
+        F: Fn(&Env, Value<'_>) -> Result<()> + 'static,
+    {
+        let action = self.make_closure(1..1, "", move |env| {
+            action(env, env.get_arg(0))?;
+            ().into_lisp(env)
+        })?;
+        self.call("insert-button", (label, self.intern("action")?, action))
+    }
+
+    /// Adds highlighting `keywords` for `mode`, or for the current buffer if `mode` is `None`. This
+    /// is the equivalent of the Lisp function [`font-lock-add-keywords`].
+    ///
+    /// `keywords` is a list in the format of `font-lock-keywords`. `how` determines where they are
+    /// added: `nil` prepends them, `set` replaces the existing keywords, and any other non-`nil`
+    /// value appends them.
+    ///
+    /// [`font-lock-add-keywords`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Customizing-Keywords.html
+    pub fn font_lock_add_keywords<'e>(
+        &'e self,
+        mode: Option<&str>,
+        keywords: Value<'e>,
+        how: Value<'e>,
+    ) -> Result<Value<'e>> {
+        let mode = match mode {
+            Some(mode) => Some(self.intern(mode)?),
+            None => None,
+        };
+        self.call("font-lock-add-keywords", (mode, keywords, how))
+    }
+
+    /// Creates an image descriptor from `source`. This is the equivalent of the Lisp function
+    /// [`create-image`].
+    ///
+    /// `image_type` is the image format, e.g. `"png"`, or `"svg"`. Each property is given as a pair
+    /// of its name, which is interned as a symbol, e.g. `":scale"`, and its value.
+    ///
+    /// This returns `nil` if Emacs cannot display images of the given type, e.g. in a terminal.
+    ///
+    /// ```
+    /// # use emacs::{Env, ImageSource, IntoLisp, Result};
+    /// fn insert_png(env: &Env, png: &[u8]) -> Result<()> {
+    ///     let image = env.create_image(ImageSource::Data(png), "png", &[
+    ///         (":scale", 2.into_lisp(env)?),
+    ///     ])?;
+    ///     env.insert_image(image, Some("[image]"))
+    /// }
+    /// ```
+    ///
+    /// [`create-image`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Defining-Images.html
+    pub fn create_image<'e>(
+        &'e self,
+        source: ImageSource<'_>,
+        image_type: &str,
+        properties: &[(&str, Value<'e>)],
+    ) -> Result<Value<'e>> {
+        let mut args = Vec::with_capacity(3 + 2 * properties.len());
+        match source {
+            ImageSource::File(path) => {
+                args.push(path.into_lisp(self)?);
+                args.push(self.intern(image_type)?);
+                args.push(().into_lisp(self)?);
+            }
+            ImageSource::Data(data) => {
+                args.push(data.into_lisp(self)?);
+                args.push(self.intern(image_type)?);
+                args.push(true.into_lisp(self)?);
+            }
+        }
+        for (name, value) in properties {
+            args.push(self.intern(name)?);
+            args.push(*value);
+        }
+        self.call("create-image", &args)
+    }
+
+    /// Inserts `image`, an image descriptor, at point in the current buffer. This is the
+    /// equivalent of the Lisp function [`insert-image`].
+    ///
+    /// `string` is the text that the image is displayed in place of, which is used e.g. when the
+    /// buffer is copied. It defaults to a single space.
+    ///
+    /// [`insert-image`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Showing-Images.html
+    pub fn insert_image(&self, image: Value<'_>, string: Option<&str>) -> Result<()> {
+        self.call("insert-image", (image, string))?;
+        Ok(())
+    }
+}
@@ -1,14 +1,13 @@
 use std::{
     cell::RefCell,
     ffi::CString,
-    mem::MaybeUninit,
 };
 
 use once_cell::sync::OnceCell;
 
 use emacs_module::{emacs_env, emacs_runtime, emacs_value};
 
-use crate::{subr, error, Value, Result, IntoLisp, call::IntoLispArgs, GlobalRef};
+use crate::{subr, symbol, Value, Result, IntoLisp, call::IntoLispArgs, GlobalRef};
 
 /// Whether the Emacs process that loaded this module has fixed [bug #31238], which caused
 /// [issue #2]. If it has, the initialization logic will disable the [workaround] of protecting
@@ -20,6 +19,20 @@ use crate::{subr, error, Value, Result, IntoLisp, call::IntoLispArgs, GlobalRef}
 /// [`Value`]: struct.Value.html
 pub static HAS_FIXED_GC_BUG_31238: OnceCell<bool> = OnceCell::new();
 
+/// The major and minor version of the Emacs process that loaded this module. This is detected
+/// during module initialization.
+pub(crate) static EMACS_VERSION: OnceCell<(u32, u32)> = OnceCell::new();
+
+/// The minimum version of the module API this crate requires, i.e. the Emacs version whose
+/// `emacs_env` struct it uses by default. Functions added to the API in later versions, such as
+/// [`should_quit`] and [`process_input`], are only used after checking at runtime that the Emacs
+/// process that loaded the module provides them. See [`Env::module_abi_size`].
+///
+/// [`should_quit`]: struct.Env.html#method.should_quit
+/// [`process_input`]: struct.Env.html#method.process_input
+/// [`Env::module_abi_size`]: struct.Env.html#method.module_abi_size
+pub const MODULE_API_VERSION: u32 = 25;
+
 /// Main point of interaction with the Lisp runtime.
 #[derive(Debug)]
 pub struct Env {
@@ -66,7 +79,11 @@ impl Env {
         unsafe_raw_call_value!(self, intern, CString::new(name)?.as_ptr())
     }
 
-    // TODO: Return an enum?
+    /// Returns the type of the given value, as a Lisp symbol. This is the equivalent of the Lisp
+    /// function [`type-of`]. See also [`lisp_type_of`], which returns a Rust enum instead.
+    ///
+    /// [`type-of`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Type-Predicates.html
+    /// [`lisp_type_of`]: #method.lisp_type_of
     pub fn type_of<'e>(&'e self, value: Value<'e>) -> Result<Value<'_>> {
         // Safety: Same lifetimes in type signature.
         unsafe_raw_call_value!(self, type_of, value.raw)
@@ -100,6 +117,70 @@ impl Env {
     pub fn message<T: AsRef<str>>(&self, text: T) -> Result<Value<'_>> {
         self.call(subr::message, (text.as_ref(),))
     }
+
+    /// Returns the major version of the Emacs process that loaded this module, i.e. the value of
+    /// the Lisp variable [`emacs-major-version`].
+    ///
+    /// [`emacs-major-version`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Version-Info.html
+    pub fn emacs_major_version(&self) -> Result<u32> {
+        Ok(self.emacs_version()?.0)
+    }
+
+    /// Returns whether the version of the Emacs process that loaded this module is at least
+    /// `major.minor`. This is useful for using features only available in newer versions.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result};
+    /// fn insert_emoji(env: &Env) -> Result<()> {
+    ///     if env.emacs_version_at_least(29, 1)? {
+    ///         env.call("emoji-insert", [])?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn emacs_version_at_least(&self, major: u32, minor: u32) -> Result<bool> {
+        Ok(self.emacs_version()? >= (major, minor))
+    }
+
+    /// Returns the major and minor version of the Emacs process that loaded this module, i.e. the
+    /// values of the Lisp variables [`emacs-major-version`] and [`emacs-minor-version`]. They are
+    /// detected once, during module initialization. Before that, a `rust-error` is signaled.
+    ///
+    /// ```
+    /// # use emacs::{Env, IntoLisp, Result, Value};
+    /// fn parse_number<'e>(env: &'e Env, s: &str) -> Result<Value<'e>> {
+    ///     if env.emacs_version()? >= (27, 1) {
+    ///         // Bignums are supported.
+    ///         env.call("string-to-number", (s,))
+    ///     } else {
+    ///         s.parse::<i64>()?.into_lisp(env)
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`emacs-major-version`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Version-Info.html
+    /// [`emacs-minor-version`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Version-Info.html
+    pub fn emacs_version(&self) -> Result<(u32, u32)> {
+        match EMACS_VERSION.get() {
+            Some(version) => Ok(*version),
+            None => self.signal(symbol::rust_error, (
+                "The Emacs version is not known before the module is initialized",
+            )),
+        }
+    }
+}
+
+/// Lower-level information about the module API.
+impl Env {
+    /// Returns the size of the `emacs_env` struct provided by the Emacs process that loaded this
+    /// module. It grows with each version of the module API, so it is never smaller than the size
+    /// of the struct for [`MODULE_API_VERSION`].
+    ///
+    /// [`MODULE_API_VERSION`]: constant.MODULE_API_VERSION.html
+    pub fn module_abi_size(&self) -> usize {
+        // Safety: The raw pointer is valid for the lifetime of this env.
+        unsafe { (*self.raw).size as usize }
+    }
 }
 
 // TODO: Add tests to make sure the protected values are not leaked.
@@ -111,23 +192,13 @@ impl Drop for Env {
             // If the `defun` returned a non-local exit, we clear it so that `free_global_ref` doesn't
             // bail out early. Afterwards we restore the non-local exit status and associated data.
             // It's kind of like an `unwind-protect`.
-            let mut symbol = MaybeUninit::uninit();
-            let mut data = MaybeUninit::uninit();
-            // TODO: Check whether calling non_local_exit_check first makes a difference in performance.
-            let status = self.non_local_exit_get(&mut symbol, &mut data);
-            if status == error::SIGNAL || status == error::THROW {
-                self.non_local_exit_clear();
-            }
-            for raw in protected.borrow().iter() {
-                // TODO: Do we want to stop if `free_global_ref` returned a non-local exit?
-                // Safety: We assume user code doesn't directly call C function `free_global_ref`.
-                unsafe_raw_call_no_exit!(self, free_global_ref, *raw);
-            }
-            match status {
-                error::SIGNAL => unsafe { self.non_local_exit_signal(symbol.assume_init(), data.assume_init()); }
-                error::THROW => unsafe { self.non_local_exit_throw(symbol.assume_init(), data.assume_init()); }
-                _ => ()
-            }
+            self.preserving_non_local_exit(|| {
+                for raw in protected.borrow().iter() {
+                    // TODO: Do we want to stop if `free_global_ref` returned a non-local exit?
+                    // Safety: We assume user code doesn't directly call C function `free_global_ref`.
+                    unsafe_raw_call_no_exit!(self, free_global_ref, *raw);
+                }
+            });
         }
     }
 }
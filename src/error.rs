@@ -1,5 +1,5 @@
 #[doc(no_inline)]
-use std::{any::Any, fmt::Display, mem::MaybeUninit, result, thread};
+use std::{any::Any, cell::RefCell, fmt::Display, mem::MaybeUninit, result, thread};
 
 pub use anyhow::{self, Error};
 use thiserror::Error;
@@ -7,7 +7,7 @@ use thiserror::Error;
 use emacs_module::*;
 
 use crate::{
-    Env, Value, IntoLisp, GlobalRef,
+    Env, Value, IntoLisp, GlobalRef, RootedValue,
     symbol::{self, IntoLispSymbol},
     call::IntoLispArgs,
 };
@@ -18,19 +18,81 @@ pub(crate) const RETURN: emacs_funcall_exit = emacs_funcall_exit_return;
 pub(crate) const SIGNAL: emacs_funcall_exit = emacs_funcall_exit_signal;
 pub(crate) const THROW: emacs_funcall_exit = emacs_funcall_exit_throw;
 
+thread_local! {
+    /// The backtrace of the last panic on this thread, if it was captured by the panic hook
+    /// installed by [`install_panic_hook`].
+    ///
+    /// [`install_panic_hook`]: fn.install_panic_hook.html
+    // `const` initializers are not supported by our MSRV.
+    #[allow(clippy::missing_const_for_thread_local)]
+    static PANIC_BACKTRACE: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Installs a panic hook that captures a backtrace, which [`Env::handle_panic`] then includes in
+/// the data of the `rust-panic` signal. The previously installed hook is still called afterwards.
+///
+/// As with [`Backtrace::capture`], a backtrace is captured only if the environment variable
+/// `RUST_LIB_BACKTRACE` or `RUST_BACKTRACE` is set. This is done at most once, since each call
+/// would add another layer of hooks.
+///
+/// [`Env::handle_panic`]: struct.Env.html#method.handle_panic
+/// [`Backtrace::capture`]: https://doc.rust-lang.org/std/backtrace/struct.Backtrace.html#method.capture
+#[cfg(feature = "backtrace")]
+pub(crate) fn install_panic_hook() {
+    use std::{backtrace::{Backtrace, BacktraceStatus}, panic, sync::Once};
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            // Panics carrying an `ErrorKind` are used to propagate non-local exits.
+            if !info.payload().is::<ErrorKind>() {
+                let backtrace = Backtrace::capture();
+                let backtrace = match backtrace.status() {
+                    BacktraceStatus::Captured => Some(backtrace.to_string()),
+                    _ => None,
+                };
+                let _ = PANIC_BACKTRACE.try_with(|b| b.replace(backtrace));
+            }
+            previous(info)
+        }));
+    });
+}
+
+#[cfg(not(feature = "backtrace"))]
+pub(crate) fn install_panic_hook() {}
+
+/// A Lisp value associated with an error. It is kept alive by a global reference, which is freed
+/// when the error is dropped. Use [`rooted`] to access it as a [`RootedValue`], without `unsafe`.
+///
+/// [`rooted`]: #method.rooted
+/// [`RootedValue`]: struct.RootedValue.html
+#[deprecated(since = "0.19.0", note = "Use `RootedValue`, returned by `TempValue::rooted`, instead")]
 #[derive(Debug)]
 pub struct TempValue {
-    raw: emacs_value,
+    rooted: RootedValue,
 }
 
 /// Defines new error signals.
 ///
 /// TODO: Document this properly.
 ///
+/// The message is usually a string literal. To compute it when the module is loaded, e.g. to
+/// localize it, use a closure that takes an [`&Env`] and returns a `Result<String>`, wrapped in
+/// braces. It is called after the module's global references are initialized, so it can call Lisp.
+///
+/// ```
+/// emacs::define_errors! {
+///     parse_error "Parse error"
+///     io_error { |env| env.funcall("format", ("I/O error (%s)", "en")) } (file_error)
+/// }
+/// ```
+///
 /// This macro can be used only once per Rust `mod`.
+///
+/// [`&Env`]: struct.Env.html
 #[macro_export]
 macro_rules! define_errors {
-    ($( $name:ident $message:literal $( ( $( $parent:ident )+ ) )? )*) => {
+    ($( $name:ident $message:tt $( ( $( $parent:ident )+ ) )? )*) => {
         $crate::global_refs! {__emrs_init_global_refs_to_error_symbols__(init_to_symbol) =>
             $( $name )*
         }
@@ -41,7 +103,7 @@ macro_rules! define_errors {
                 .expect("Failed to acquire a write lock on the list of initializers for custom error signals")
                 .push(::std::boxed::Box::new(|env| {
                     $(
-                        env.define_error($name, $message, [
+                        env.define_error_checked($name, $crate::__error_message!(env, $message), [
                             $(
                                 $(
                                     env.intern($crate::deps::emacs_macros::lisp_name!($parent))?
@@ -55,10 +117,68 @@ macro_rules! define_errors {
     }
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __error_message {
+    ($env:ident, $message:literal) => {
+        $message
+    };
+    ($env:ident, { $($compute:tt)* }) => {
+        &$crate::init::__compute_error_message($env, $($compute)*)?
+    };
+}
+
+/// Evaluates a block, catching values thrown by Lisp's [`throw`] to the given tag. This is the
+/// equivalent of the Lisp special form [`catch`].
+///
+/// The block must evaluate to a `Result<Value>`. If it returns an [`ErrorKind::Throw`] error whose
+/// tag is `eq` to the given tag, the thrown value is returned as `Ok`. Other errors are propagated.
+/// Since the block is wrapped in a closure, `?` inside it is also caught, not returned from the
+/// enclosing function.
+///
+/// ```
+/// use emacs::{defun, Value, Result};
+///
+/// #[defun]
+/// fn call_with_catch<'e>(tag: Value<'e>, lambda: Value<'e>) -> Result<Value<'e>> {
+///     emacs::catch!(tag.env, tag, {
+///         lambda.call([])
+///     })
+/// }
+/// ```
+///
+/// [`throw`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Catch-and-Throw.html
+/// [`catch`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Catch-and-Throw.html
+/// [`ErrorKind::Throw`]: enum.ErrorKind.html#variant.Throw
+#[macro_export]
+macro_rules! catch {
+    ($env:expr, $tag:expr, $body:block) => {{
+        let env: &$crate::Env = $env;
+        let expected_tag: $crate::Value<'_> = $tag;
+        let result: $crate::Result<$crate::Value<'_>> = (|| $body)();
+        match result {
+            Err(error) => {
+                let caught = match error.downcast_ref::<$crate::ErrorKind>() {
+                    Some($crate::ErrorKind::Throw { tag, value }) if tag.rooted().bind(env).eq(expected_tag) => {
+                        Some(value.rooted().to_local(env))
+                    }
+                    _ => None,
+                };
+                match caught {
+                    Some(value) => value,
+                    None => Err(error),
+                }
+            }
+            v => v,
+        }
+    }};
+}
+
 /// Error types generic to all Rust dynamic modules.
 ///
 /// This list is intended to grow over time and it is not recommended to exhaustively match against
 /// it.
+#[allow(deprecated)]
 #[derive(Debug, Error)]
 pub enum ErrorKind {
     /// An [error] signaled by Lisp code.
@@ -99,10 +219,16 @@ pub enum ErrorKind {
     /// ```emacs-lisp
     /// (unwrap 7)          ; *** Eval error ***  Wrong type argument: user-ptrp, 7
     /// (unwrap (wrap 7))   ; 7
-    /// (unwrap (wrap-f 7)) ; *** Eval error ***  Wrong type user-ptr: "expected: RefCell"
+    /// (unwrap (wrap-f 7)) ; *** Eval error ***  Wrong type user-ptr: "expected: core::cell::RefCell<i64>, found: core::cell::RefCell<f64>"
     /// ```
-    #[error("expected: {expected}")]
-    WrongTypeUserPtr { expected: &'static str },
+    ///
+    /// `found` is the name of the type that was actually embedded, if the `user-ptr` was created by
+    /// this module. It is `None` for `user-ptr` objects created by other modules, or by
+    /// [`Env::make_user_ptr`].
+    ///
+    /// [`Env::make_user_ptr`]: struct.Env.html#method.make_user_ptr
+    #[error("expected: {expected}, found: {}", found.unwrap_or("an unknown user-ptr"))]
+    WrongTypeUserPtr { expected: &'static str, found: Option<&'static str> },
 }
 
 /// A specialized [`Result`] type for Emacs's dynamic modules.
@@ -110,31 +236,41 @@ pub enum ErrorKind {
 /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
 pub type Result<T> = result::Result<T, Error>;
 
-// FIX: Make this into RootedValue (or ProtectedValue), and make it safe. XXX: The problem is that
-// the raw value will be leaked when RootedValue is dropped, since `free_global_ref` requires an env
-// (thus cannot be called there). This is likely a mis-design in Emacs (In Erlang,
-// `enif_keep_resource` and `enif_release_resource` don't require an env).
+#[allow(deprecated)]
 impl TempValue {
-    unsafe fn new(raw: emacs_value) -> Self {
-        Self { raw }
+    fn new(rooted: RootedValue) -> Self {
+        Self { rooted }
+    }
+
+    /// Returns the underlying [`RootedValue`], which can be bound to any [`Env`].
+    ///
+    /// [`Env`]: struct.Env.html
+    /// [`RootedValue`]: struct.RootedValue.html
+    #[inline]
+    pub fn rooted(&self) -> &RootedValue {
+        &self.rooted
+    }
+
+    /// Converts this into the underlying [`RootedValue`], e.g. to keep it after the error is
+    /// dropped.
+    ///
+    /// [`RootedValue`]: struct.RootedValue.html
+    #[inline]
+    pub fn into_rooted(self) -> RootedValue {
+        self.rooted
     }
 
     /// # Safety
     ///
-    /// This must only be used with the [`Env`] from which the error originated.
+    /// The returned value must not outlive this `TempValue`. Prefer [`rooted`], which enforces
+    /// that.
     ///
-    /// [`Env`]: struct.Env.html
+    /// [`rooted`]: #method.rooted
     pub unsafe fn value<'e>(&self, env: &'e Env) -> Value<'e> {
-        Value::new(self.raw, env).protect()
+        Value::new(self.rooted.bind(env).raw, env).protect()
     }
 }
 
-// XXX: Technically these are unsound, but they are necessary to use the `Fail` trait. We ensure
-// safety by marking TempValue methods as unsafe.
-unsafe impl Send for TempValue {}
-
-unsafe impl Sync for TempValue {}
-
 impl Env {
     /// Handles possible non-local exit after calling Lisp code.
     #[inline]
@@ -148,16 +284,16 @@ impl Env {
             (SIGNAL, symbol, data) => {
                 self.non_local_exit_clear();
                 Err(ErrorKind::Signal {
-                    symbol: unsafe { TempValue::new(symbol.assume_init()) },
-                    data: unsafe { TempValue::new(data.assume_init()) },
+                    symbol: unsafe { self.root_raw(symbol.assume_init()) },
+                    data: unsafe { self.root_raw(data.assume_init()) },
                 }
                 .into())
             }
             (THROW, tag, value) => {
                 self.non_local_exit_clear();
                 Err(ErrorKind::Throw {
-                    tag: unsafe { TempValue::new(tag.assume_init()) },
-                    value: unsafe { TempValue::new(value.assume_init()) },
+                    tag: unsafe { self.root_raw(tag.assume_init()) },
+                    value: unsafe { self.root_raw(value.assume_init()) },
                 }
                 .into())
             }
@@ -165,6 +301,14 @@ impl Env {
         }
     }
 
+    /// # Safety
+    ///
+    /// The given raw value must come from this env.
+    #[allow(deprecated)]
+    unsafe fn root_raw(&self, raw: emacs_value) -> TempValue {
+        TempValue::new(RootedValue::new(Value::new(raw, self)))
+    }
+
     /// Converts a Rust's `Result` to either a normal value, or a non-local exit in Lisp.
     #[inline]
     pub(crate) unsafe fn maybe_exit(&self, result: Result<Value<'_>>) -> emacs_value {
@@ -172,9 +316,11 @@ impl Env {
             Ok(v) => v.raw,
             Err(error) => match error.downcast_ref::<ErrorKind>() {
                 Some(err) => self.handle_known(err),
+                // The alternate format includes the whole chain of contexts, outermost first,
+                // down to the root cause, e.g. "while loading config: invalid digit".
                 _ => self
-                    .signal_internal(symbol::rust_error, &format!("{}", error))
-                    .unwrap_or_else(|_| panic!("Failed to signal {}", error)),
+                    .signal_internal(symbol::rust_error, &format!("{:#}", error))
+                    .unwrap_or_else(|_| panic!("Failed to signal {:#}", error)),
             },
         }
     }
@@ -187,6 +333,7 @@ impl Env {
         match result {
             Ok(v) => v,
             Err(error) => {
+                let backtrace = PANIC_BACKTRACE.try_with(|b| b.take()).ok().flatten();
                 // TODO: Try to check for some common types to display?
                 let mut m: result::Result<String, Box<dyn Any>> = Err(error);
                 if let Err(error) = m {
@@ -205,7 +352,12 @@ impl Env {
                 if let Err(error) = m {
                     m = Ok(format!("{:#?}", error));
                 }
-                match self.signal_internal(symbol::rust_panic, &m.expect("Logic error")) {
+                let message = m.expect("Logic error");
+                let signaled = match backtrace {
+                    Some(backtrace) => self.signal_with_backtrace(&message, &backtrace),
+                    None => self.signal_internal(symbol::rust_panic, &message),
+                };
+                match signaled {
                     Ok(v) => v,
                     Err(err) => {
                         println!("error in handle_panic/signal_internal: {}", err);
@@ -229,16 +381,27 @@ impl Env {
         Ok(())
     }
 
+    /// The values of signals and throws are still rooted when this returns, since their global
+    /// references are only queued to be freed when the error is dropped.
     unsafe fn handle_known(&self, err: &ErrorKind) -> emacs_value {
         match err {
-            ErrorKind::Signal { symbol, data } => self.non_local_exit_signal(symbol.raw, data.raw),
-            ErrorKind::Throw { tag, value } => self.non_local_exit_throw(tag.raw, value.raw),
+            ErrorKind::Signal { symbol, data } => {
+                self.non_local_exit_signal(symbol.rooted().bind(self).raw, data.rooted().bind(self).raw)
+            }
+            ErrorKind::Throw { tag, value } => {
+                self.non_local_exit_throw(tag.rooted().bind(self).raw, value.rooted().bind(self).raw)
+            }
             ErrorKind::WrongTypeUserPtr { .. } => self
                 .signal_internal(symbol::rust_wrong_type_user_ptr, &format!("{}", err))
                 .unwrap_or_else(|_| panic!("Failed to signal {}", err)),
         }
     }
 
+    fn signal_with_backtrace(&self, message: &str, backtrace: &str) -> Result<emacs_value> {
+        let data = self.list((message, backtrace))?;
+        unsafe { Ok(self.non_local_exit_signal(symbol::rust_panic.bind(self).raw, data.raw)) }
+    }
+
     fn signal_internal(&self, symbol: &GlobalRef, message: &str) -> Result<emacs_value> {
         let message = message.into_lisp(&self)?;
         let data = self.list([message])?;
@@ -258,19 +421,98 @@ impl Env {
         self.call("define-error", (name.into_lisp_symbol(self)?, message, self.list(parents)?))
     }
 
+    /// Like [`define_error`], but first checks that each parent is a defined error signal, i.e. it
+    /// has an `error-conditions` property. If not, a `rust-error` naming the missing parent is
+    /// signaled, instead of a confusing failure when the new error is later signaled or handled.
+    ///
+    /// [`define_error`]: #method.define_error
+    pub fn define_error_checked<'e, N, P>(
+        &'e self,
+        name: N,
+        message: &str,
+        parents: P,
+    ) -> Result<Value<'e>>
+    where
+        N: IntoLispSymbol<'e>,
+        P: IntoLispArgs<'e>,
+    {
+        let name = name.into_lisp_symbol(self)?;
+        let parents = self.list(parents)?;
+        let error_conditions = self.intern("error-conditions")?;
+        for parent in parents.into_rust::<Vec<Value>>()? {
+            if !self.call("get", (parent, error_conditions))?.is_not_nil() {
+                let name: String = self.call("symbol-name", [name])?.into_rust()?;
+                let parent: String = self.call("prin1-to-string", [parent])?.into_rust()?;
+                return self.signal(symbol::rust_error, (format!(
+                    "Cannot define error {}: parent {} is not a defined error signal",
+                    name, parent,
+                ),));
+            }
+        }
+        self.call("define-error", (name, message, parents))
+    }
+
     /// Signals a Lisp error. This is the equivalent of the Lisp function's [`signal`].
     ///
     /// [`signal`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Signaling-Errors.html#index-signal
+    #[allow(deprecated)]
     pub fn signal<'e, S, D, T>(&'e self, symbol: S, data: D) -> Result<T>
     where
         S: IntoLispSymbol<'e>,
         D: IntoLispArgs<'e>,
     {
-        let symbol = TempValue { raw: symbol.into_lisp_symbol(self)?.raw };
-        let data = TempValue { raw: self.list(data)?.raw };
+        let symbol = TempValue::new(RootedValue::new(symbol.into_lisp_symbol(self)?));
+        let data = TempValue::new(RootedValue::new(self.list(data)?));
         Err(ErrorKind::Signal { symbol, data }.into())
     }
 
+    /// Calls `body`, then calls `cleanup`, even if `body` returns an error, or panics. This is
+    /// similar to the Lisp special form [`unwind-protect`].
+    ///
+    /// Signals and throws from Lisp code called by `body` are returned as [`ErrorKind`] errors, so
+    /// they are propagated after `cleanup` runs, just like other errors. If a non-local exit is
+    /// pending in the environment when `cleanup` runs, e.g. because `body` used the raw API, it is
+    /// cleared, so that `cleanup` can call Lisp, then restored afterward.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result, Value};
+    /// fn with_temp_file<'e>(env: &'e Env, f: Value<'e>) -> Result<Value<'e>> {
+    ///     let file: String = env.call("make-temp-file", ("emacs-",))?.into_rust()?;
+    ///     env.unwind_protect(|| f.call((file.as_str(),)), || {
+    ///         let _ = std::fs::remove_file(&file);
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// [`unwind-protect`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Cleanups.html
+    /// [`ErrorKind`]: enum.ErrorKind.html
+    pub fn unwind_protect<T, B, C>(&self, body: B, cleanup: C) -> Result<T>
+    where
+        B: FnOnce() -> Result<T>,
+        C: FnOnce(),
+    {
+        let _cleanup = Cleanup { env: self, cleanup: Some(cleanup) };
+        body()
+    }
+
+    /// Calls `f` with any pending non-local exit cleared, then restores it.
+    pub(crate) fn preserving_non_local_exit<T, F: FnOnce() -> T>(&self, f: F) -> T {
+        let mut symbol = MaybeUninit::uninit();
+        let mut data = MaybeUninit::uninit();
+        // TODO: Check whether calling non_local_exit_check first makes a difference in performance.
+        let status = self.non_local_exit_get(&mut symbol, &mut data);
+        if status == SIGNAL || status == THROW {
+            self.non_local_exit_clear();
+        }
+        let result = f();
+        match status {
+            SIGNAL => unsafe { self.non_local_exit_signal(symbol.assume_init(), data.assume_init()); }
+            THROW => unsafe { self.non_local_exit_throw(symbol.assume_init(), data.assume_init()); }
+            _ => ()
+        }
+        result
+    }
+
     pub(crate) fn non_local_exit_get(
         &self,
         symbol: &mut MaybeUninit<emacs_value>,
@@ -311,17 +553,91 @@ impl Env {
     }
 }
 
+/// Restores some state changed by a scope helper, such as [`Env::with_buffer`], either when
+/// [`finish`] is called, or when dropped, e.g. because the scope's closure panicked.
+///
+/// [`Env::with_buffer`]: struct.Env.html#method.with_buffer
+/// [`finish`]: #method.finish
+pub(crate) struct Restore<'e, C: FnOnce(&'e Env) -> Result<()>> {
+    env: &'e Env,
+    restore: Option<C>,
+}
+
+impl<'e, C: FnOnce(&'e Env) -> Result<()>> Restore<'e, C> {
+    pub(crate) fn new(env: &'e Env, restore: C) -> Self {
+        Self { env, restore: Some(restore) }
+    }
+
+    /// Restores the state, then returns `result`, which is the result of the scope's closure. If
+    /// that is an error, it takes precedence over an error from restoring.
+    pub(crate) fn finish<T>(mut self, result: Result<T>) -> Result<T> {
+        let restored = self.run();
+        let value = result?;
+        restored?;
+        Ok(value)
+    }
+
+    fn run(&mut self) -> Result<()> {
+        match self.restore.take() {
+            Some(restore) => self.env.preserving_non_local_exit(|| restore(self.env)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'e, C: FnOnce(&'e Env) -> Result<()>> Drop for Restore<'e, C> {
+    fn drop(&mut self) {
+        let _ = self.run();
+    }
+}
+
+/// Calls the cleanup function of [`Env::unwind_protect`] when dropped.
+///
+/// [`Env::unwind_protect`]: struct.Env.html#method.unwind_protect
+struct Cleanup<'e, C: FnOnce()> {
+    env: &'e Env,
+    cleanup: Option<C>,
+}
+
+impl<C: FnOnce()> Drop for Cleanup<'_, C> {
+    fn drop(&mut self) {
+        if let Some(cleanup) = self.cleanup.take() {
+            self.env.preserving_non_local_exit(cleanup);
+        }
+    }
+}
+
 /// Emacs-specific extension methods for the standard library's [`Result`].
 ///
 /// [`Result`]: result::Result
 pub trait ResultExt<T, E> {
     /// Converts the error into a Lisp signal if this result is an [`Err`]. The first element of the
-    /// associated signal data will be a string formatted with [`Display::fmt`].
+    /// associated signal data will be a string formatted with [`Display::fmt`], in the alternate
+    /// form, i.e. `{:#}`, which for an `anyhow::Error` includes its whole chain of contexts.
     ///
     /// If the result is an [`Ok`], it is returned unchanged.
     fn or_signal<'e, S>(self, env: &'e Env, symbol: S) -> Result<T>
     where
         S: IntoLispSymbol<'e>;
+
+    /// Converts the error into a Lisp signal if this result is an [`Err`]. The associated signal
+    /// data is the list of values returned by `data`, which is called with the error.
+    ///
+    /// If the result is an [`Ok`], it is returned unchanged.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result, ResultExt};
+    /// # emacs::define_errors! { parse_error "Parse error" }
+    /// fn parse_line(env: &Env, file: &str, line: usize, text: &str) -> Result<i64> {
+    ///     // The signal data is (FILE LINE MESSAGE).
+    ///     text.parse::<i64>().or_signal_with(env, parse_error, |e| (file, line, e.to_string()))
+    /// }
+    /// ```
+    fn or_signal_with<'e, S, D, F>(self, env: &'e Env, symbol: S, data: F) -> Result<T>
+    where
+        S: IntoLispSymbol<'e>,
+        D: IntoLispArgs<'e>,
+        F: FnOnce(E) -> D;
 }
 
 impl<T, E: Display> ResultExt<T, E> for result::Result<T, E> {
@@ -329,6 +645,158 @@ impl<T, E: Display> ResultExt<T, E> for result::Result<T, E> {
     where
         S: IntoLispSymbol<'e>,
     {
-        self.or_else(|err| env.signal(symbol, (format!("{}", err),)))
+        self.or_else(|err| env.signal(symbol, (format!("{:#}", err),)))
+    }
+
+    fn or_signal_with<'e, S, D, F>(self, env: &'e Env, symbol: S, data: F) -> Result<T>
+    where
+        S: IntoLispSymbol<'e>,
+        D: IntoLispArgs<'e>,
+        F: FnOnce(E) -> D,
+    {
+        self.or_else(|err| env.signal(symbol, data(err)))
+    }
+}
+
+/// Emacs-specific extension methods for [`Error`].
+///
+/// [`Error`]: struct.Error.html
+pub trait ErrorExt {
+    /// Returns whether this error is an [`ErrorKind::Signal`], i.e. a Lisp error.
+    ///
+    /// [`ErrorKind::Signal`]: enum.ErrorKind.html#variant.Signal
+    fn is_signal(&self) -> bool;
+
+    /// Returns whether this error is an [`ErrorKind::Throw`], i.e. a Lisp `throw`.
+    ///
+    /// [`ErrorKind::Throw`]: enum.ErrorKind.html#variant.Throw
+    fn is_throw(&self) -> bool;
+
+    /// Returns the error symbol of this error, if it is an [`ErrorKind::Signal`]. The returned
+    /// value is a new local reference, so it remains valid for the lifetime of `env`, even after
+    /// this error is dropped.
+    ///
+    /// `None` is returned for other errors.
+    ///
+    /// ```
+    /// # use emacs::{Env, ErrorExt, Result, Value};
+    /// fn insert_if_writable(env: &Env, text: &str) -> Result<bool> {
+    ///     match env.call("insert", (text,)) {
+    ///         Err(error) => match error.signal_symbol(env)? {
+    ///             Some(symbol) if symbol.eq(env.intern("buffer-read-only")?) => Ok(false),
+    ///             _ => Err(error),
+    ///         },
+    ///         Ok(_) => Ok(true),
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`ErrorKind::Signal`]: enum.ErrorKind.html#variant.Signal
+    fn signal_symbol<'e>(&self, env: &'e Env) -> Result<Option<Value<'e>>>;
+
+    /// Returns the data list associated with this error, if it is an [`ErrorKind::Signal`]. Like
+    /// [`signal_symbol`], the returned value is a new local reference.
+    ///
+    /// `None` is returned for other errors.
+    ///
+    /// [`ErrorKind::Signal`]: enum.ErrorKind.html#variant.Signal
+    /// [`signal_symbol`]: #tymethod.signal_symbol
+    fn signal_data<'e>(&self, env: &'e Env) -> Result<Option<Value<'e>>>;
+
+    /// Returns the catch tag of this error, if it is an [`ErrorKind::Throw`]. Like
+    /// [`signal_symbol`], the returned value is a new local reference.
+    ///
+    /// `None` is returned for other errors.
+    ///
+    /// [`ErrorKind::Throw`]: enum.ErrorKind.html#variant.Throw
+    /// [`signal_symbol`]: #tymethod.signal_symbol
+    fn throw_tag<'e>(&self, env: &'e Env) -> Result<Option<Value<'e>>>;
+
+    /// Returns the thrown value of this error, if it is an [`ErrorKind::Throw`]. Like
+    /// [`signal_symbol`], the returned value is a new local reference.
+    ///
+    /// `None` is returned for other errors.
+    ///
+    /// [`ErrorKind::Throw`]: enum.ErrorKind.html#variant.Throw
+    /// [`signal_symbol`]: #tymethod.signal_symbol
+    fn throw_value<'e>(&self, env: &'e Env) -> Result<Option<Value<'e>>>;
+
+    /// Returns whether this error is a `quit` signal, e.g. because the user typed `C-g` while Lisp
+    /// code was running. Signals whose error conditions include `quit`, such as `minibuffer-quit`,
+    /// also count.
+    ///
+    /// Such errors should usually be propagated back to Emacs as is, even by code that handles
+    /// other errors, so that the user can interrupt long-running commands.
+    ///
+    /// ```
+    /// # use emacs::{Env, ErrorExt, Result, Value};
+    /// fn try_each<'e>(env: &'e Env, functions: &[Value<'e>]) -> Result<usize> {
+    ///     let mut succeeded = 0;
+    ///     for f in functions {
+    ///         match f.call([]) {
+    ///             Ok(_) => succeeded += 1,
+    ///             Err(error) if error.is_quit(env) => return Err(error),
+    ///             Err(_) => {}
+    ///         }
+    ///     }
+    ///     Ok(succeeded)
+    /// }
+    /// ```
+    fn is_quit(&self, env: &Env) -> bool;
+}
+
+impl ErrorExt for Error {
+    fn is_signal(&self) -> bool {
+        matches!(self.downcast_ref::<ErrorKind>(), Some(ErrorKind::Signal { .. }))
+    }
+
+    fn is_throw(&self) -> bool {
+        matches!(self.downcast_ref::<ErrorKind>(), Some(ErrorKind::Throw { .. }))
+    }
+
+    fn signal_symbol<'e>(&self, env: &'e Env) -> Result<Option<Value<'e>>> {
+        match self.downcast_ref::<ErrorKind>() {
+            Some(ErrorKind::Signal { symbol, .. }) => Ok(Some(symbol.rooted().to_local(env)?)),
+            _ => Ok(None),
+        }
+    }
+
+    fn signal_data<'e>(&self, env: &'e Env) -> Result<Option<Value<'e>>> {
+        match self.downcast_ref::<ErrorKind>() {
+            Some(ErrorKind::Signal { data, .. }) => Ok(Some(data.rooted().to_local(env)?)),
+            _ => Ok(None),
+        }
+    }
+
+    fn throw_tag<'e>(&self, env: &'e Env) -> Result<Option<Value<'e>>> {
+        match self.downcast_ref::<ErrorKind>() {
+            Some(ErrorKind::Throw { tag, .. }) => Ok(Some(tag.rooted().to_local(env)?)),
+            _ => Ok(None),
+        }
+    }
+
+    fn throw_value<'e>(&self, env: &'e Env) -> Result<Option<Value<'e>>> {
+        match self.downcast_ref::<ErrorKind>() {
+            Some(ErrorKind::Throw { value, .. }) => Ok(Some(value.rooted().to_local(env)?)),
+            _ => Ok(None),
+        }
+    }
+
+    fn is_quit(&self, env: &Env) -> bool {
+        let symbol = match self.downcast_ref::<ErrorKind>() {
+            Some(ErrorKind::Signal { symbol, .. }) => symbol.rooted().bind(env),
+            _ => return false,
+        };
+        let quit = crate::quit::quit.bind(env);
+        if symbol.eq(quit) {
+            return true;
+        }
+        let found = env.intern("error-conditions")
+            .and_then(|property| env.call("get", (symbol, property)))
+            .and_then(|conditions| env.call("memq", (quit, conditions)));
+        match found {
+            Ok(found) => found.is_not_nil(),
+            Err(_) => false,
+        }
     }
 }
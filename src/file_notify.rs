@@ -0,0 +1,56 @@
+//! Watching files for changes, with Rust callbacks.
+
+use crate::{Env, IntoLisp, Result, Value};
+
+impl Env {
+    /// Starts watching `file` (a file or a directory) for changes, calling `callback` with each
+    /// change event. This is the equivalent of the Lisp function [`file-notify-add-watch`].
+    ///
+    /// `flags` are the kinds of changes to watch for, i.e. `"change"`, and `"attribute-change"`. An
+    /// event is a list of the form `(DESCRIPTOR ACTION FILE [FILE1])`.
+    ///
+    /// The returned descriptor can be passed to [`file_notify_rm_watch`] to stop watching. Since
+    /// `callback` is turned into a Lisp function by [`make_closure`], it is dropped once the watch
+    /// has been removed, and is garbage-collected.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result, Value};
+    /// fn watch_config<'e>(env: &'e Env, file: &str) -> Result<Value<'e>> {
+    ///     env.file_notify_add_watch(file, &["change"], |env, event| {
+    ///         env.call("message", ("Config changed: %S", event))?;
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// [`file-notify-add-watch`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/File-Notifications.html
+    /// [`file_notify_rm_watch`]: #method.file_notify_rm_watch
+    /// [`make_closure`]: #method.make_closure
+    pub fn file_notify_add_watch<F>(
+        &self,
+        file: &str,
+        flags: &[&str],
+        callback: F,
+    ) -> Result<Value<'_>>
+    where
+        F: Fn(&Env, Value<'_>) -> Result<()> + 'static,
+    {
+        let flags = flags.iter().map(|flag| self.intern(flag)).collect::<Result<Vec<_>>>()?;
+        let callback = self.make_closure(1..1, "", move |env| {
+            callback(env, env.get_arg(0))?;
+            ().into_lisp(env)
+        })?;
+        self.call("file-notify-add-watch", (file, self.list(&flags)?, callback))
+    }
+
+    /// Stops watching the file identified by `descriptor`, which was returned by
+    /// [`file_notify_add_watch`]. This is the equivalent of the Lisp function
+    /// [`file-notify-rm-watch`].
+    ///
+    /// [`file_notify_add_watch`]: #method.file_notify_add_watch
+    /// [`file-notify-rm-watch`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/File-Notifications.html
+    pub fn file_notify_rm_watch(&self, descriptor: Value<'_>) -> Result<()> {
+        self.call("file-notify-rm-watch", [descriptor])?;
+        Ok(())
+    }
+}
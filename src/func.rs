@@ -4,15 +4,19 @@
 //! [`defun`]: attr.defun.html
 
 use std::{
-    os, panic,
+    os, panic, ptr, mem,
+    cell::Cell,
     ffi::CString,
     ops::{Deref, Range},
+    rc::Rc,
     slice,
 };
 
-use emacs_module::{emacs_value, EmacsSubr};
+use once_cell::sync::OnceCell;
 
-use crate::{Env, Value, Result, FromLisp, IntoLisp};
+use emacs_module::{emacs_env, emacs_value, emacs_variadic_function, EmacsSubr};
+
+use crate::{Env, Value, Result, FromLisp, IntoLisp, GlobalRef, Transfer, global, symbol};
 
 #[doc(hidden)]
 #[macro_export]
@@ -134,6 +138,223 @@ impl Manage for Env {
     }
 }
 
+/// The type of Rust closures that can be turned into Lisp functions by [`Env::make_closure`].
+///
+/// [`Env::make_closure`]: struct.Env.html#method.make_closure
+type Closure = dyn Fn(&CallEnv) -> Result<Value<'_>> + 'static;
+
+/// A Rust closure behind a Lisp function created by [`Env::make_closure`]. It is embedded in a
+/// `user-ptr` object referenced by the function, so it is dropped when the function is
+/// garbage-collected.
+///
+/// [`Env::make_closure`]: struct.Env.html#method.make_closure
+struct ClosureData {
+    arities: Range<usize>,
+    closure: Box<Closure>,
+}
+
+impl Transfer for ClosureData {}
+
+/// The module function shared by all Lisp functions created from Rust closures. See
+/// [`call_closure`].
+///
+/// [`call_closure`]: fn.call_closure.html
+static CALL_CLOSURE: OnceCell<GlobalRef> = OnceCell::new();
+
+/// The single handler that dispatches calls to all Lisp functions created from Rust closures. The
+/// first argument is the `user-ptr` embedding the [`ClosureData`], and the rest are the arguments
+/// to the closure.
+///
+/// [`ClosureData`]: struct.ClosureData.html
+unsafe extern "C" fn call_closure(
+    env: *mut emacs_env,
+    nargs: isize,
+    args: *mut emacs_value,
+    _data: *mut os::raw::c_void,
+) -> emacs_value {
+    let data = *args;
+    let env = Env::new(env);
+    let env = CallEnv::new(env, nargs - 1, args.offset(1));
+    env.handle_call(|env| {
+        // Safety: Emacs assures *args are on the stack for the duration of the call.
+        let data: &ClosureData = Value::new(data, env).into_rust()?;
+        let Range { start, end } = data.arities;
+        if env.nargs < start || env.nargs > end {
+            return env.signal(symbol::wrong_number_of_arguments, (env.cons(start, end)?, env.nargs));
+        }
+        (data.closure)(env)
+    })
+}
+
+impl Env {
+    /// Creates a Lisp function that calls the given Rust closure. Unlike [`lambda!`], which only
+    /// accepts function items, this allows capturing state.
+    ///
+    /// As with [`lambda!`], `arities` gives the minimum and maximum number of arguments, both
+    /// inclusive. A `wrong-number-of-arguments` error is signaled if the function is called with
+    /// a different number of arguments.
+    ///
+    /// # Implementation
+    ///
+    /// The closure is embedded in a `user-ptr` object, and the function is a Lisp closure that
+    /// passes it, along with its arguments, to a module function shared by all such closures.
+    /// Therefore, the closure is dropped once the function is garbage-collected.
+    ///
+    /// [`lambda!`]: macro.lambda.html
+    pub fn make_closure<F>(&self, arities: Range<usize>, doc: &str, f: F) -> Result<Value<'_>>
+    where
+        F: Fn(&CallEnv) -> Result<Value<'_>> + 'static,
+    {
+        let call_closure = match CALL_CLOSURE.get() {
+            Some(call_closure) => call_closure,
+            None => {
+                // Safety: `call_closure` doesn't use the data pointer.
+                let function = unsafe_raw_call_value!(
+                    self,
+                    make_function,
+                    1,
+                    emacs_variadic_function as isize,
+                    Some(call_closure),
+                    CString::new("")?.as_ptr(),
+                    ptr::null_mut()
+                )?;
+                CALL_CLOSURE.get_or_init(|| function.make_global_ref())
+            }
+        };
+        let data = self.embed(ClosureData { arities, closure: Box::new(f) }, None)?;
+        // (lambda (&rest args) DOC (apply 'CALL-CLOSURE 'DATA args))
+        let quote = self.intern("quote")?;
+        let args = self.intern("args")?;
+        let body = self.list((
+            self.intern("apply")?,
+            self.list((quote, call_closure.bind(self)))?,
+            self.list((quote, data))?,
+            args,
+        ))?;
+        let arglist = self.list((self.intern("&rest")?, args))?;
+        let lambda = self.list((self.intern("lambda")?, arglist, doc, body))?;
+        self.call("eval", (self.list((self.intern("function")?, lambda))?, true))
+    }
+
+    /// Calls `wrap` with a Lisp function that takes no arguments, and calls `f`. Returns the result
+    /// of `f`, or the error returned by `wrap`, if `f` succeeded. This allows `f`, which can borrow
+    /// local state, unlike closures given to [`make_closure`], to be called from a Lisp form, e.g.
+    /// one that binds variables.
+    ///
+    /// The function calls `f` at most once, and only until `wrap` returns. If `f` panics, the panic
+    /// is resumed after `wrap` returns, so that Lisp code in between is unwound normally.
+    ///
+    /// [`make_closure`]: #method.make_closure
+    pub(crate) fn call_scoped<'e, R, F, W>(&'e self, f: F, wrap: W) -> Result<R>
+    where
+        F: FnOnce() -> Result<R>,
+        W: FnOnce(Value<'e>) -> Result<Value<'e>>,
+    {
+        let mut f = Some(f);
+        let mut outcome = None;
+        let mut body = || {
+            if let Some(f) = f.take() {
+                outcome = Some(panic::catch_unwind(panic::AssertUnwindSafe(f)));
+            }
+        };
+        let body: &mut dyn FnMut() = &mut body;
+        // Safety: The pointer is only called while it is in `slot`, and `clear` takes it out before
+        // `body` goes out of scope. Taking it out before calling it also prevents reentrant calls.
+        let body: *mut (dyn FnMut() + 'static) = unsafe { mem::transmute(body) };
+        let slot = Rc::new(Cell::new(Some(body)));
+        let clear = ClearOnDrop(slot.clone());
+        let function = self.make_closure(0..0, "", move |env| {
+            match slot.take() {
+                Some(body) => unsafe { (*body)() },
+                None => return env.signal(symbol::rust_error, ("Scoped function called out of scope",)),
+            }
+            ().into_lisp(env)
+        })?;
+        let wrapped = wrap(function);
+        drop(clear);
+        match outcome {
+            Some(Ok(result)) => {
+                let value = result?;
+                wrapped?;
+                Ok(value)
+            }
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            None => {
+                wrapped?;
+                self.signal(symbol::rust_error, ("Scoped function was not called",))
+            }
+        }
+    }
+
+    /// Returns a command, i.e. an interactive function, that calls `function` with the same
+    /// arguments, and has the same documentation. `spec` is the argument of the command's
+    /// [`interactive`] form, e.g. `"p"`, passed through verbatim. If it is `None`, the command
+    /// reads no arguments when called interactively.
+    ///
+    /// This is what [`#[defun(interactive)]`] uses, since module functions cannot be made
+    /// interactive directly. The command is a Lisp closure that applies `function`.
+    ///
+    /// ```
+    /// # use emacs::{Env, IntoLisp, Result, Value};
+    /// fn define_greet(env: &Env) -> Result<Value<'_>> {
+    ///     let greet = env.make_closure(0..0, "Say hello.", |env| env.message("Hello"))?;
+    ///     let command = env.make_command(greet, None)?;
+    ///     env.call("defalias", (env.intern("my-greet")?, command))
+    /// }
+    /// ```
+    ///
+    /// [`interactive`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Using-Interactive.html
+    /// [`#[defun(interactive)]`]: attr.defun.html
+    pub fn make_command<'e>(&'e self, function: Value<'e>, spec: Option<&str>) -> Result<Value<'e>> {
+        let doc = match self.call("documentation", (function, true))? {
+            doc if doc.is_not_nil() => doc,
+            _ => "".into_lisp(self)?,
+        };
+        let interactive = self.intern("interactive")?;
+        let interactive = match spec {
+            Some(spec) => self.list((interactive, spec))?,
+            None => self.list((interactive,))?,
+        };
+        let args = self.intern("args")?;
+        let quoted = self.list((self.intern("quote")?, function))?;
+        let body = self.list((self.intern("apply")?, quoted, args))?;
+        let arglist = self.list((self.intern("&rest")?, args))?;
+        let lambda = self.list((self.intern("lambda")?, arglist, doc, interactive, body))?;
+        self.call("eval", (self.list((self.intern("function")?, lambda))?, true))
+    }
+
+    /// Makes `obsolete` an alias of the function `current`, marking it obsolete since version
+    /// `since`. Returns the symbol `obsolete`. This is the equivalent of the Lisp macro
+    /// [`define-obsolete-function-alias`].
+    ///
+    /// Calling the alias works like calling `current`. As with Lisp code, the byte compiler warns
+    /// about calls to it, and `describe-function` shows it as obsolete.
+    ///
+    /// [`define-obsolete-function-alias`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Obsolete-Functions.html
+    pub fn define_obsolete_function_alias(
+        &self,
+        obsolete: &str,
+        current: &str,
+        since: &str,
+    ) -> Result<Value<'_>> {
+        let obsolete = self.intern(obsolete)?;
+        let current = self.intern(current)?;
+        self.call("defalias", (obsolete, current))?;
+        self.call("make-obsolete", (obsolete, current, since))
+    }
+}
+
+/// Takes the function out of the slot used by [`Env::call_scoped`] when dropped.
+///
+/// [`Env::call_scoped`]: struct.Env.html#method.call_scoped
+struct ClearOnDrop(Rc<Cell<Option<*mut dyn FnMut()>>>);
+
+impl Drop for ClearOnDrop {
+    fn drop(&mut self) {
+        self.0.set(None);
+    }
+}
+
 /// Like [`Env`], but is available only in exported functions. This has additional methods to handle
 /// arguments passed from Lisp code.
 ///
@@ -182,6 +403,31 @@ impl CallEnv {
     pub fn parse_arg<'e, T: FromLisp<'e>>(&'e self, i: usize) -> Result<T> {
         self.get_arg(i).into_rust()
     }
+
+    /// Treats the arguments from index `start` onward as a property list, e.g. `:width 10 :height
+    /// 5`, and returns the value following `keyword`, or `None` if it is absent. The keyword
+    /// should include its leading colon.
+    ///
+    /// A `wrong-type-argument` error is signaled if there is an odd number of such arguments.
+    pub fn parse_keyword<'e, T: FromLisp<'e>>(
+        &'e self,
+        start: usize,
+        keyword: &str,
+    ) -> Result<Option<T>> {
+        let args = self.args();
+        let plist = args.get(start..).unwrap_or(&[]);
+        if plist.len() % 2 != 0 {
+            let plistp = self.intern("plistp")?;
+            return self.signal(symbol::wrong_type_argument, (plistp, self.list(plist)?));
+        }
+        let keyword = self.intern(keyword)?;
+        for pair in plist.chunks(2) {
+            if pair[0].eq(keyword) {
+                return pair[1].into_rust().map(Some);
+            }
+        }
+        Ok(None)
+    }
 }
 
 /// This allows `Env`'s methods to be called on a `CallEnv`.
@@ -209,6 +455,7 @@ impl HandleCall for CallEnv {
         F: Fn(&'e CallEnv) -> Result<T> + panic::RefUnwindSafe,
         T: IntoLisp<'e>,
     {
+        global::free_pending(self);
         let env = panic::AssertUnwindSafe(self);
         let result = panic::catch_unwind(|| unsafe {
             let rust_result = f(&env);
@@ -1,6 +1,10 @@
-use std::ops::Deref;
+use std::{
+    mem::ManuallyDrop,
+    ops::Deref,
+    sync::Mutex,
+};
 
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
 
 use emacs_module::emacs_value;
 
@@ -106,6 +110,177 @@ impl<'e> Value<'e> {
     }
 }
 
+/// A Lisp value that is "rooted" by a global reference, so that it can be safely bound to any
+/// [`Env`], not just the one it came from. This is how [`ErrorKind::Signal`] and
+/// [`ErrorKind::Throw`] keep their associated Lisp values alive. [`TempValue::rooted`] returns them.
+///
+/// Like [`GlobalRef`], a bound [`Value`] borrows the `RootedValue`, so it cannot outlive it. Use
+/// [`to_local`] to get a [`Value`] that does.
+///
+/// # Implementation
+///
+/// Freeing a global reference requires an [`Env`], which is not available when this is dropped.
+/// Therefore, dropping it does not free the global reference, but queues it. The queue is drained
+/// the next time Lisp calls a Rust function of this module. If that never happens, e.g. because
+/// the module is not called again, the queued global references, and the Lisp values they keep
+/// alive, are never freed. [`free`] frees the global reference immediately instead, and should be
+/// preferred when an [`Env`] is available.
+///
+/// [`Env`]: struct.Env.html
+/// [`Value`]: struct.Value.html
+/// [`GlobalRef`]: struct.GlobalRef.html
+/// [`ErrorKind::Signal`]: enum.ErrorKind.html#variant.Signal
+/// [`ErrorKind::Throw`]: enum.ErrorKind.html#variant.Throw
+/// [`TempValue::rooted`]: struct.TempValue.html#method.rooted
+/// [`to_local`]: #method.to_local
+/// [`free`]: #method.free
+#[derive(Debug)]
+pub struct RootedValue {
+    global: GlobalRef,
+}
+
+impl RootedValue {
+    /// Creates a new rooted value for the given [`Value`].
+    ///
+    /// [`Value`]: struct.Value.html
+    pub fn new(value: Value) -> Self {
+        Self { global: value.make_global_ref() }
+    }
+
+    /// Returns the underlying [`Value`], scoping its lifetime to the given [`Env`].
+    ///
+    /// [`Env`]: struct.Env.html
+    /// [`Value`]: struct.Value.html
+    #[inline]
+    pub fn bind<'e, 'g: 'e>(&'g self, env: &'e Env) -> Value<'e> {
+        self.global.bind(env)
+    }
+
+    /// Returns a copy of the underlying [`Value`], as a new local reference of the given [`Env`].
+    /// Unlike [`bind`], the returned value remains valid after this is freed or dropped.
+    ///
+    /// [`Env`]: struct.Env.html
+    /// [`Value`]: struct.Value.html
+    /// [`bind`]: #method.bind
+    pub fn to_local<'e>(&self, env: &'e Env) -> Result<Value<'e>> {
+        env.call(subr::identity, [self.bind(env)])
+    }
+
+    /// Frees the underlying global reference immediately, instead of when it is dropped.
+    pub fn free(self, env: &Env) -> Result<()> {
+        let this = ManuallyDrop::new(self);
+        GlobalRef { raw: this.global.raw }.free(env)
+    }
+}
+
+impl Drop for RootedValue {
+    fn drop(&mut self) {
+        defer_free(self.global.raw);
+    }
+}
+
+/// A global reference that frees itself when dropped, unlike [`GlobalRef`]. This is useful for
+/// storing Lisp values in long-lived Rust data structures, such as caches, without leaking them.
+///
+/// # Implementation
+///
+/// Freeing a global reference requires an [`Env`], which is not available when this is dropped,
+/// e.g. on a background thread, or in a `user-ptr`'s finalizer during garbage collection.
+/// Therefore, dropping it queues the global reference, which is actually freed the next time Lisp
+/// calls a Rust function.
+///
+/// [`Env`]: struct.Env.html
+/// [`GlobalRef`]: struct.GlobalRef.html
+#[derive(Debug)]
+pub struct OwnedGlobalRef {
+    raw: emacs_value,
+}
+
+impl OwnedGlobalRef {
+    /// Creates a new owned global reference for the given [`Value`].
+    ///
+    /// [`Value`]: struct.Value.html
+    pub fn new(value: Value) -> Self {
+        let GlobalRef { raw } = value.make_global_ref();
+        Self { raw }
+    }
+
+    /// Returns the underlying [`Value`], scoping its lifetime to the given [`Env`].
+    ///
+    /// [`Env`]: struct.Env.html
+    /// [`Value`]: struct.Value.html
+    #[inline]
+    pub fn bind<'e, 'g: 'e>(&'g self, env: &'e Env) -> Value<'e> {
+        // Safety: This global ref keeps the underlying Lisp object alive.
+        unsafe { Value::new(self.raw, env) }
+    }
+
+    /// Returns a copy of this global reference.
+    pub fn clone(&self, env: &Env) -> Self {
+        Self::new(self.bind(env))
+    }
+
+    /// Returns the number of global references that were dropped, and have not been freed yet.
+    #[doc(hidden)]
+    pub fn pending_count() -> usize {
+        PENDING_FREES.lock().map_or(0, |pending| pending.len())
+    }
+}
+
+impl Drop for OwnedGlobalRef {
+    fn drop(&mut self) {
+        // No env can be used here, e.g. when this is dropped by a `user-ptr`'s finalizer during GC.
+        defer_free(self.raw);
+    }
+}
+
+// Safety: Doing anything useful with an OwnedGlobalRef requires an &Env, which means holding the
+// GIL. Dropping it without one only queues the raw value.
+unsafe impl Send for OwnedGlobalRef {}
+unsafe impl Sync for OwnedGlobalRef {}
+
+impl<'e> FromLisp<'e> for OwnedGlobalRef {
+    #[inline(always)]
+    fn from_lisp(value: Value<'e>) -> Result<Self> {
+        Ok(Self::new(value))
+    }
+}
+
+impl<'e> IntoLisp<'e> for &'e OwnedGlobalRef {
+    #[inline(always)]
+    fn into_lisp(self, env: &'e Env) -> Result<Value<'e>> {
+        Ok(self.bind(env))
+    }
+}
+
+/// A global reference waiting to be freed.
+struct PendingFree(emacs_value);
+
+// Safety: The raw value is only used to call `free_global_ref`, with a live env.
+unsafe impl Send for PendingFree {}
+
+static PENDING_FREES: Lazy<Mutex<Vec<PendingFree>>> = Lazy::new(|| Mutex::new(vec![]));
+
+/// Queues a global reference, to be freed the next time Lisp calls a Rust function.
+fn defer_free(raw: emacs_value) {
+    if let Ok(mut pending) = PENDING_FREES.lock() {
+        pending.push(PendingFree(raw));
+    }
+}
+
+/// Frees the global references queued by dropped [`OwnedGlobalRef`]s and [`RootedValue`]s. This is
+/// called whenever Lisp calls a Rust function.
+///
+/// [`OwnedGlobalRef`]: struct.OwnedGlobalRef.html
+/// [`RootedValue`]: struct.RootedValue.html
+pub(crate) fn free_pending(env: &Env) {
+    let pending = PENDING_FREES.lock().map(|mut pending| pending.split_off(0));
+    for PendingFree(raw) in pending.unwrap_or_default() {
+        // Safety: Each value was queued exactly once, when its owner was dropped.
+        unsafe_raw_call_no_exit!(env, free_global_ref, raw);
+    }
+}
+
 /// Declares global references. These will be initialized when the module is loaded.
 #[doc(hidden)]
 #[macro_export]
@@ -190,6 +365,15 @@ impl OnceGlobalRef {
             env.call("indirect-function", [symbol])
         })
     }
+
+    /// Points this global reference to a new uninterned Lisp symbol with the given name. Such a
+    /// symbol is not `eq` to any other object, which makes it useful as a sentinel value.
+    ///
+    /// This should be called once, during module initialization.
+    #[doc(hidden)]
+    pub fn init_to_uninterned_symbol(&self, env: &Env, name: &str) -> Result<&GlobalRef> {
+        self.init(env, |env| env.call("make-symbol", (name,)))
+    }
 }
 
 impl<'e> IntoLisp<'e> for &'e OnceGlobalRef {
@@ -0,0 +1,65 @@
+//! Helpers for working with hooks.
+
+use crate::{symbol::IntoLispSymbol, Env, Result};
+
+impl Env {
+    /// Adds `function` to the hook variable `hook`. This is the equivalent of the Lisp function
+    /// [`add-hook`].
+    ///
+    /// `function` can be a function name, a [`GlobalRef`] to a function, e.g. one defined by this
+    /// module, or any other callable [`Value`], such as a lambda. Nothing is done if it is already
+    /// on the hook.
+    ///
+    /// If `append` is true, `function` is added to the end of the hook, instead of the beginning. If
+    /// `local` is true, the buffer-local value of the hook is modified, making it buffer-local if
+    /// needed. Its list then contains `t`, which means running the global value too.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result};
+    /// fn enable_auto_format(env: &Env) -> Result<()> {
+    ///     env.add_hook("before-save-hook", "my-module-format-buffer", false, true)
+    /// }
+    /// ```
+    ///
+    /// [`add-hook`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Setting-Hooks.html
+    /// [`GlobalRef`]: struct.GlobalRef.html
+    /// [`Value`]: struct.Value.html
+    pub fn add_hook<'e, F: IntoLispSymbol<'e>>(
+        &'e self,
+        hook: &str,
+        function: F,
+        append: bool,
+        local: bool,
+    ) -> Result<()> {
+        let function = function.into_lisp_symbol(self)?;
+        self.call("add-hook", (self.intern(hook)?, function, append, local))?;
+        Ok(())
+    }
+
+    /// Removes `function` from the hook variable `hook`. This is the equivalent of the Lisp
+    /// function [`remove-hook`].
+    ///
+    /// Functions are compared with `equal`, so a lambda is only removed if it is the same as the
+    /// one that was added. If `local` is true, the buffer-local value of the hook is modified.
+    ///
+    /// [`remove-hook`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Setting-Hooks.html
+    pub fn remove_hook<'e, F: IntoLispSymbol<'e>>(
+        &'e self,
+        hook: &str,
+        function: F,
+        local: bool,
+    ) -> Result<()> {
+        let function = function.into_lisp_symbol(self)?;
+        self.call("remove-hook", (self.intern(hook)?, function, local))?;
+        Ok(())
+    }
+
+    /// Calls the functions on the hook variable `hook`, with no arguments. This is the equivalent
+    /// of the Lisp function [`run-hooks`].
+    ///
+    /// [`run-hooks`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Hooks.html
+    pub fn run_hooks(&self, hook: &str) -> Result<()> {
+        self.call("run-hooks", [self.intern(hook)?])?;
+        Ok(())
+    }
+}
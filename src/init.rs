@@ -10,7 +10,7 @@ use std::{
 
 use once_cell::sync::Lazy;
 
-use crate::{Env, Value, Result, ErrorKind};
+use crate::{Env, Value, Result, ErrorKind, IntoLisp};
 
 #[doc(hidden)]
 #[macro_export]
@@ -39,6 +39,8 @@ type InitFn = Box<dyn Fn(&Env) -> Result<()> + Send + 'static>;
 
 type FnMap = HashMap<String, InitFn>;
 
+type UnloadFn = Box<dyn FnOnce(&Env) -> Result<()> + Send + 'static>;
+
 // TODO: How about defining these in user crate, and requiring #[module] to be at the crate's root?
 // TODO: We probably don't need the mutexes.
 
@@ -63,6 +65,17 @@ pub static __GLOBAL_REFS__: Lazy<Mutex<Vec<InitFn>>> = Lazy::new(|| Mutex::new(v
 /// [`emacs_module_init`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Dynamic-Modules.html
 pub static __CUSTOM_ERRORS__: Lazy<Mutex<Vec<InitFn>>> = Lazy::new(|| Mutex::new(vec![]));
 
+/// Calls the closure given to [`define_errors!`] to compute an error message.
+///
+/// [`define_errors!`]: ../macro.define_errors.html
+#[doc(hidden)]
+pub fn __compute_error_message<F>(env: &Env, compute: F) -> Result<String>
+where
+    F: FnOnce(&Env) -> Result<String>,
+{
+    compute(env)
+}
+
 /// Functions that will be called by [`emacs_module_init`] to define the module functions.
 ///
 /// They are called after loading module metadata, e.g. module name, function prefix.
@@ -81,10 +94,101 @@ pub static __PREFIX__: Lazy<Mutex<[String; 2]>> = Lazy::new(|| Mutex::new(["".to
 
 pub static __MOD_IN_NAME__: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(true));
 
+/// Cleanup functions registered through [`on_unload`]. They are called by the module's
+/// `<feature>-unload-function`.
+///
+/// [`on_unload`]: fn.on_unload.html
+static UNLOAD_FNS: Lazy<Mutex<Vec<UnloadFn>>> = Lazy::new(|| Mutex::new(vec![]));
+
+/// Registers a function to be called when the module's feature is unloaded with [`unload-feature`].
+/// This can be used to stop background threads, cancel timers, or free global references.
+///
+/// Cleanup functions are called in the reverse order of registration, each at most once. If one of
+/// them fails, the rest are still called, and the first error is signaled. Note that Emacs cannot
+/// unload the dynamic library itself, so its static data stays around.
+///
+/// # Examples
+///
+/// ```
+/// use emacs::{Env, Result};
+///
+/// #[emacs::module]
+/// fn init(env: &Env) -> Result<()> {
+///     emacs::on_unload(|env| {
+///         env.message("Unloading")?;
+///         Ok(())
+///     });
+///     Ok(())
+/// }
+/// ```
+///
+/// [`unload-feature`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Unloading.html
+pub fn on_unload<F>(f: F)
+    where
+        F: FnOnce(&Env) -> Result<()> + Send + 'static,
+{
+    UNLOAD_FNS.lock()
+        .expect("Failed to acquire a write lock on the list of cleanup functions")
+        .push(Box::new(f));
+}
+
+fn run_unload_fns(env: &Env) -> Result<()> {
+    // Take the functions out first, so that they can call `on_unload` without deadlocking.
+    let fns = std::mem::take(&mut *UNLOAD_FNS.lock()
+        .expect("Failed to acquire a write lock on the list of cleanup functions"));
+    let mut result = Ok(());
+    for f in fns.into_iter().rev() {
+        let r = f(env);
+        if result.is_ok() {
+            result = r;
+        }
+    }
+    result
+}
+
+/// Defines `<feature>-unload-function`, which [`unload-feature`] calls before unloading the
+/// feature. It runs the cleanup functions registered through [`on_unload`], then returns nil, so
+/// that the normal unloading still happens.
+///
+/// If the function is already defined, e.g. by the module's init function, it is called first, and
+/// whether it returns non-nil is preserved, so that it can still skip the normal unloading. The cleanup
+/// functions are run even if it fails.
+///
+/// [`unload-feature`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Unloading.html
+/// [`on_unload`]: fn.on_unload.html
+pub fn define_unload_function(env: &Env, feature: &str) -> Result<()> {
+    let name = env.intern(&format!("{}-unload-function", feature))?;
+    let previous = if env.call("fboundp", [name])?.is_not_nil() {
+        Some(env.call("symbol-function", [name])?.make_global_ref())
+    } else {
+        None
+    };
+    let func = env.make_closure(0..0, "Run the cleanup functions registered by the dynamic module.", move |env| {
+        // `unload-feature` only checks whether the result is non-nil.
+        let skip_unloading = match &previous {
+            Some(previous) => previous.call(env, []).map(|v| v.is_not_nil()),
+            None => Ok(false),
+        };
+        let cleanup = run_unload_fns(env);
+        let skip_unloading = skip_unloading?;
+        cleanup?;
+        skip_unloading.into_lisp(env)
+    })?;
+    env.call("fset", (name, func))?;
+    Ok(())
+}
+
 fn debugging() -> bool {
     std::env::var("EMACS_MODULE_RS_DEBUG").unwrap_or_default() == "1"
 }
 
+fn detect_emacs_version(env: &Env) -> Result<()> {
+    let major = env.call("default-value", [env.intern("emacs-major-version")?])?.into_rust()?;
+    let minor = env.call("default-value", [env.intern("emacs-minor-version")?])?.into_rust()?;
+    crate::env::EMACS_VERSION.get_or_init(|| (major, minor));
+    Ok(())
+}
+
 fn check_gc_bug_31238(env: &Env) -> Result<()> {
     let version = env.call("default-value", [env.intern("emacs-version")?])?;
     let fixed = env.call("version<=", ("27", version))?.is_not_nil();
@@ -109,20 +213,24 @@ pub fn initialize<F>(env: &Env, init: F) -> os::raw::c_int
             init_global_ref(&env)?;
         }
         env.define_core_errors()?;
+        detect_emacs_version(&env)?;
         check_gc_bug_31238(&env)?;
         for define_error in __CUSTOM_ERRORS__.try_lock()
             .expect("Failed to acquire a read lock on the list of initializers for custom error signals").iter() {
             define_error(&env)?;
         }
-        init(&env)
+        let result = init(&env);
+        // After `init`, so that a panic hook set by it is kept, and called by ours.
+        crate::error::install_panic_hook();
+        result
     })() {
         Ok(_) => 0,
         Err(e) => {
             if let Some(ErrorKind::Signal { symbol, data }) = e.downcast_ref::<ErrorKind>() {
                 env.call("message", (
                     "Error during initialization: symbol: %s data: %s",
-                    unsafe { symbol.value(&env) },
-                    unsafe { data.value(&env) },
+                    symbol.rooted().bind(&env),
+                    data.rooted().bind(&env),
                 ))
             } else {
                 env.message(format!("Error during initialization: {:#?}", e))
@@ -32,14 +32,29 @@ pub use emacs_macros::{defun, module};
 
 #[doc(inline)]
 pub use self::{
-    env::Env,
+    env::{Env, MODULE_API_VERSION},
     value::Value,
-    global::{GlobalRef, OnceGlobalRef},
-    types::{FromLisp, IntoLisp, Transfer, Vector},
+    global::{GlobalRef, OnceGlobalRef, OwnedGlobalRef, RootedValue},
+    types::{FromLisp, IntoLisp, Transfer, Vector, VecIter, ListIter, SeqIter, HashTable, HashTableTest, LispType},
     func::CallEnv,
-    error::{ErrorKind, Result, ResultExt, Error},
+    call::LispFn,
+    display::{FaceSpec, ImageSource, Overlay},
+    buffer::Buffer,
+    timer::Timer,
+    thread::MainThreadSender,
+    progress::ProgressReporter,
+    text::PropertizedString,
+    quit::ProcessInputResult,
+    error::{ErrorKind, Result, ResultExt, ErrorExt, Error},
 };
 
+#[doc(inline)]
+pub use self::init::on_unload;
+
+#[doc(inline)]
+#[allow(deprecated)]
+pub use self::error::TempValue;
+
 #[macro_use] mod macros;
 
 #[doc(hidden)]
@@ -56,6 +71,24 @@ mod call;
 mod global;
 mod symbol;
 mod subr;
+mod coding;
+mod display;
+mod timer;
+mod buffer;
+mod list;
+mod minibuffer;
+mod progress;
+mod text;
+mod quit;
+mod variable;
+mod math;
+mod file_notify;
+mod process;
+mod hook;
+mod thread;
+
+#[cfg(feature = "serde")]
+pub mod serde;
 
 /// This exposes some raw types for module to use (e.g. in `emacs_module_init`) without having to
 /// declare the raw `emacs_module` as a dependency.
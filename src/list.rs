@@ -0,0 +1,169 @@
+//! Helpers for working with lists and other sequences.
+
+use crate::{symbol, Env, FromLisp, HashTableTest, IntoLisp, Result, Value};
+
+crate::use_symbols! {
+    kw_test => ":test"
+    kw_from_end => ":from-end"
+}
+
+impl Env {
+    /// Returns the first element of `alist` whose `car` matches `key`, or `None` if there is no
+    /// such element. This is the equivalent of the Lisp function [`assoc`].
+    ///
+    /// Keys are compared using `testfn` if given, or `equal` otherwise.
+    ///
+    /// [`assoc`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Association-Lists.html
+    pub fn assoc<'e>(
+        &'e self,
+        key: Value<'e>,
+        alist: Value<'e>,
+        testfn: Option<Value<'e>>,
+    ) -> Result<Option<Value<'e>>> {
+        self.call("assoc", (key, alist, testfn))?.into_rust()
+    }
+
+    /// Returns the first element of `alist` whose `cdr` is `equal` to `value`, or `None` if there is
+    /// no such element. This is the equivalent of the Lisp function [`rassoc`].
+    ///
+    /// [`rassoc`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Association-Lists.html
+    pub fn rassoc<'e>(&'e self, value: Value<'e>, alist: Value<'e>) -> Result<Option<Value<'e>>> {
+        self.call("rassoc", (value, alist))?.into_rust()
+    }
+
+    /// Returns the number of elements in `seq` that are `eql` to `elem`. This is the equivalent of
+    /// the Lisp function [`cl-count`].
+    ///
+    /// [`cl-count`]: https://www.gnu.org/software/emacs/manual/html_node/cl/Searching-Sequences.html
+    pub fn count<'e>(&'e self, elem: Value<'e>, seq: Value<'e>) -> Result<usize> {
+        self.require_cl_lib()?;
+        self.call("cl-count", (elem, seq))?.into_rust()
+    }
+
+    /// Returns the number of elements in `seq` that satisfy the predicate `pred`. This is the
+    /// equivalent of the Lisp function [`cl-count-if`].
+    ///
+    /// [`cl-count-if`]: https://www.gnu.org/software/emacs/manual/html_node/cl/Searching-Sequences.html
+    pub fn count_if<'e>(&'e self, pred: Value<'e>, seq: Value<'e>) -> Result<usize> {
+        self.require_cl_lib()?;
+        self.call("cl-count-if", (pred, seq))?.into_rust()
+    }
+
+    /// Returns a copy of `seq` with duplicate elements removed, comparing elements with `test`. This
+    /// is the equivalent of the Lisp function [`cl-remove-duplicates`], with `:from-end` set, so
+    /// that the first occurrence of each element is kept, and the order is preserved.
+    ///
+    /// [`cl-remove-duplicates`]: https://www.gnu.org/software/emacs/manual/html_node/cl/Sequence-Functions.html
+    pub fn remove_duplicates<'e>(&'e self, seq: Value<'e>, test: HashTableTest) -> Result<Value<'e>> {
+        self.require_cl_lib()?;
+        self.call("cl-remove-duplicates", (seq, kw_test, test.symbol(), kw_from_end, true))
+    }
+
+    /// Returns the first `n` elements of `seq`, as a sequence of the same type. This is the
+    /// equivalent of the Lisp function [`seq-take`].
+    ///
+    /// If `n` is larger than the length of `seq`, the whole sequence is returned. If `n` is zero or
+    /// negative, an empty sequence is returned.
+    ///
+    /// [`seq-take`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Sequence-Functions.html
+    pub fn seq_take<'e>(&'e self, seq: Value<'e>, n: i64) -> Result<Value<'e>> {
+        self.require_seq()?;
+        self.call("seq-take", (seq, n))
+    }
+
+    /// Returns all but the first `n` elements of `seq`, as a sequence of the same type. This is the
+    /// equivalent of the Lisp function [`seq-drop`].
+    ///
+    /// If `n` is larger than the length of `seq`, an empty sequence is returned. If `n` is zero or
+    /// negative, `seq` itself is returned.
+    ///
+    /// [`seq-drop`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Sequence-Functions.html
+    pub fn seq_drop<'e>(&'e self, seq: Value<'e>, n: i64) -> Result<Value<'e>> {
+        self.require_seq()?;
+        self.call("seq-drop", (seq, n))
+    }
+
+    /// Returns a list of the integers from `from` to `to`, inclusive, in increments of `step`, which
+    /// defaults to 1. This is the equivalent of the Lisp function [`number-sequence`].
+    ///
+    /// If `step` is negative, the sequence is descending. An empty list is returned if `to` cannot
+    /// be reached from `from` in the direction of `step`. An error is signaled if `step` is zero,
+    /// unless `from` and `to` are equal.
+    ///
+    /// [`number-sequence`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Building-Lists.html
+    pub fn number_sequence(&self, from: i64, to: i64, step: Option<i64>) -> Result<Value<'_>> {
+        self.call("number-sequence", (from, to, step))
+    }
+
+    /// Ensures `seq` is loaded, since it is not preloaded in older versions of Emacs.
+    fn require_seq(&self) -> Result<()> {
+        self.call("require", [self.intern("seq")?])?;
+        Ok(())
+    }
+
+    /// Ensures `cl-lib` is loaded, since its sequence functions are only autoloaded after that.
+    pub(crate) fn require_cl_lib(&self) -> Result<()> {
+        self.call("require", [self.intern("cl-lib")?])?;
+        Ok(())
+    }
+}
+
+impl<'e> Value<'e> {
+    /// Returns the value of the property `key` in this property list, converted with [`FromLisp`],
+    /// or `None` if the property is absent. `key` is interned as is, so a keyword should include
+    /// its leading colon, e.g. `":width"`.
+    ///
+    /// Unlike the Lisp function [`plist-get`], this distinguishes an absent property from one
+    /// whose value is `nil`, which is converted like other values.
+    ///
+    /// ```
+    /// # use emacs::{defun, Result, Value};
+    /// /// Return the area described by SHAPE, a plist like (:width 2 :height 3).
+    /// #[defun]
+    /// fn area(shape: Value) -> Result<i64> {
+    ///     let width: i64 = shape.plist_get(":width")?.unwrap_or(1);
+    ///     let height: i64 = shape.plist_get(":height")?.unwrap_or(1);
+    ///     Ok(width * height)
+    /// }
+    /// ```
+    ///
+    /// [`FromLisp`]: trait.FromLisp.html
+    /// [`plist-get`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Plist-Access.html
+    pub fn plist_get<T: FromLisp<'e>>(&self, key: &str) -> Result<Option<T>> {
+        let env = self.env;
+        let tail = env.call("plist-member", (*self, env.intern(key)?))?;
+        if !tail.is_not_nil() {
+            return Ok(None);
+        }
+        env.call("cadr", [tail])?.into_rust().map(Some)
+    }
+
+    /// Returns the value associated with `key` in this association list, converted with
+    /// [`FromLisp`], or `None` if there is no such association. Keys are compared with `equal`.
+    ///
+    /// This is similar to the Lisp function [`alist-get`], except that an association whose value
+    /// is `nil` is distinguished from an absent one.
+    ///
+    /// [`FromLisp`]: trait.FromLisp.html
+    /// [`alist-get`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Association-Lists.html
+    pub fn alist_get<T: FromLisp<'e>, K: IntoLisp<'e>>(&self, key: K) -> Result<Option<T>> {
+        let env = self.env;
+        match env.assoc(key.into_lisp(env)?, *self, None)? {
+            Some(pair) => pair.cdr().map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the pairs of properties and values in this property list.
+    ///
+    /// A `wrong-type-argument` error is signaled if this value is not a proper list with an even
+    /// number of elements. A `circular-list` error is signaled if it is circular.
+    pub fn plist_to_vec(&self) -> Result<Vec<(Value<'e>, Value<'e>)>> {
+        let env = self.env;
+        let elements: Vec<Value> = self.into_rust()?;
+        if elements.len() % 2 == 1 {
+            return env.signal(symbol::wrong_type_argument, (env.intern("plistp")?, *self));
+        }
+        Ok(elements.chunks(2).map(|pair| (pair[0], pair[1])).collect())
+    }
+}
@@ -0,0 +1,94 @@
+//! Helpers for working with numbers, with Lisp semantics.
+
+use crate::{Env, Result, Value};
+
+impl Env {
+    /// Returns the largest integer not greater than `x`, or than `x` divided by `divisor`, if given.
+    /// This is the equivalent of the Lisp function [`floor`].
+    ///
+    /// Unlike converting to Rust numbers first, this works with bignums, and divides integers
+    /// exactly. An `arith-error` is signaled if `divisor` is zero.
+    ///
+    /// [`floor`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Numeric-Conversions.html
+    pub fn floor<'e>(&'e self, x: Value<'e>, divisor: Option<Value<'e>>) -> Result<Value<'e>> {
+        self.call("floor", (x, divisor))
+    }
+
+    /// Returns the smallest integer not less than `x`, or than `x` divided by `divisor`, if given.
+    /// This is the equivalent of the Lisp function [`ceiling`].
+    ///
+    /// [`ceiling`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Numeric-Conversions.html
+    pub fn ceiling<'e>(&'e self, x: Value<'e>, divisor: Option<Value<'e>>) -> Result<Value<'e>> {
+        self.call("ceiling", (x, divisor))
+    }
+
+    /// Returns the integer nearest to `x`, or to `x` divided by `divisor`, if given. This is the
+    /// equivalent of the Lisp function [`round`].
+    ///
+    /// Halfway cases are rounded to the nearest even integer, e.g. 2.5 is rounded to 2. This is
+    /// unlike Rust's [`f64::round`], which rounds them away from zero.
+    ///
+    /// [`round`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Numeric-Conversions.html
+    /// [`f64::round`]: https://doc.rust-lang.org/std/primitive.f64.html#method.round
+    pub fn round<'e>(&'e self, x: Value<'e>, divisor: Option<Value<'e>>) -> Result<Value<'e>> {
+        self.call("round", (x, divisor))
+    }
+
+    /// Returns the integer part of `x`, or of `x` divided by `divisor`, if given, by rounding
+    /// toward zero. This is the equivalent of the Lisp function [`truncate`].
+    ///
+    /// [`truncate`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Numeric-Conversions.html
+    pub fn truncate<'e>(&'e self, x: Value<'e>, divisor: Option<Value<'e>>) -> Result<Value<'e>> {
+        self.call("truncate", (x, divisor))
+    }
+
+    /// Returns the greatest common divisor of the integers `a` and `b`, which is never negative.
+    /// This is the equivalent of the Lisp function [`cl-gcd`].
+    ///
+    /// Like the other functions here, this works with bignums, so the result is exact even when
+    /// the arguments don't fit in `i64`.
+    ///
+    /// [`cl-gcd`]: https://www.gnu.org/software/emacs/manual/html_node/cl/Numerical-Functions.html
+    pub fn gcd<'e>(&'e self, a: Value<'e>, b: Value<'e>) -> Result<Value<'e>> {
+        self.require_cl_lib()?;
+        self.call("cl-gcd", (a, b))
+    }
+
+    /// Returns the least common multiple of the integers `a` and `b`, which is never negative.
+    /// This is the equivalent of the Lisp function [`cl-lcm`].
+    ///
+    /// [`cl-lcm`]: https://www.gnu.org/software/emacs/manual/html_node/cl/Numerical-Functions.html
+    pub fn lcm<'e>(&'e self, a: Value<'e>, b: Value<'e>) -> Result<Value<'e>> {
+        self.require_cl_lib()?;
+        self.call("cl-lcm", (a, b))
+    }
+
+    /// Returns `base` raised to the power `power`. This is the equivalent of the Lisp function
+    /// [`expt`].
+    ///
+    /// If both are integers, and `power` is not negative, the result is an exact integer, which
+    /// becomes a bignum if it is too large for a fixnum. Otherwise, it is a float.
+    ///
+    /// ```
+    /// # use emacs::{Env, IntoLisp, Result, Value};
+    /// fn mersenne<'e>(env: &'e Env, p: u32) -> Result<Value<'e>> {
+    ///     let power = env.expt(2.into_lisp(env)?, p.into_lisp(env)?)?;
+    ///     env.call("1-", [power])
+    /// }
+    /// ```
+    ///
+    /// [`expt`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Math-Functions.html
+    pub fn expt<'e>(&'e self, base: Value<'e>, power: Value<'e>) -> Result<Value<'e>> {
+        self.call("expt", (base, power))
+    }
+
+    /// Returns the integer square root of the non-negative integer `x`, i.e. the largest integer
+    /// whose square is not greater than `x`. This is the equivalent of the Lisp function
+    /// [`cl-isqrt`]. An `arith-error` is signaled if `x` is negative.
+    ///
+    /// [`cl-isqrt`]: https://www.gnu.org/software/emacs/manual/html_node/cl/Numerical-Functions.html
+    pub fn isqrt<'e>(&'e self, x: Value<'e>) -> Result<Value<'e>> {
+        self.require_cl_lib()?;
+        self.call("cl-isqrt", [x])
+    }
+}
@@ -0,0 +1,122 @@
+//! Helpers for interactive flows involving the minibuffer and recursive editing.
+
+use crate::{Env, IntoLisp, Result, Value};
+
+impl Env {
+    /// Returns whether the current buffer is a minibuffer. This is the equivalent of the Lisp
+    /// function [`minibufferp`].
+    ///
+    /// [`minibufferp`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Minibuffer-Misc.html
+    pub fn minibufferp(&self) -> Result<bool> {
+        Ok(self.call("minibufferp", [])?.is_not_nil())
+    }
+
+    /// Aborts the innermost recursive edit, such as an active minibuffer. This is the equivalent
+    /// of the Lisp function [`abort-recursive-edit`].
+    ///
+    /// This function never returns normally. When a recursive edit is in progress, the result is an
+    /// [`ErrorKind::Throw`] to the tag `exit`, which should be propagated back to Emacs, so that
+    /// the recursive edit can be exited. Otherwise, a `user-error` is signaled.
+    ///
+    /// [`abort-recursive-edit`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Recursive-Editing.html
+    /// [`ErrorKind::Throw`]: enum.ErrorKind.html#variant.Throw
+    pub fn abort_recursive_edit(&self) -> Result<()> {
+        self.call("abort-recursive-edit", [])?;
+        Ok(())
+    }
+
+    /// Reads a string from the minibuffer, prompting with `prompt`. This is the equivalent of the
+    /// Lisp function [`read-string`].
+    ///
+    /// `initial` is inserted into the minibuffer before reading. `history` names the variable
+    /// holding the history list, defaulting to `minibuffer-history`. If the user enters an empty
+    /// string, `default` is returned instead, if given.
+    ///
+    /// This waits for user input, so it should only be used in interactive contexts, e.g. in
+    /// functions called by commands. In batch mode, the input is read from the standard input.
+    ///
+    /// [`read-string`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Text-from-Minibuffer.html
+    pub fn read_string(
+        &self,
+        prompt: &str,
+        initial: Option<&str>,
+        history: Option<&str>,
+        default: Option<&str>,
+    ) -> Result<String> {
+        let history = match history {
+            Some(history) => Some(self.intern(history)?),
+            None => None,
+        };
+        let input: String =
+            self.call("read-string", (prompt, initial, history, default))?.into_rust()?;
+        match default {
+            Some(default) if input.is_empty() => Ok(default.to_owned()),
+            _ => Ok(input),
+        }
+    }
+
+    /// Reads a number from the minibuffer, prompting with `prompt`, and asking again until the user
+    /// enters a valid number. This is the equivalent of the Lisp function [`read-number`].
+    ///
+    /// If the user enters an empty string, `default` is returned instead, if given. Integers are
+    /// returned as floats.
+    ///
+    /// As with [`read_string`], this waits for user input. If the user quits with `C-g`, a `quit`
+    /// signal is returned as an error, which should be propagated back to Emacs.
+    ///
+    /// [`read-number`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Object-from-Minibuffer.html
+    /// [`read_string`]: #method.read_string
+    pub fn read_number(&self, prompt: &str, default: Option<f64>) -> Result<f64> {
+        let number = self.call("read-number", (prompt, default))?;
+        self.call("float", [number])?.into_rust()
+    }
+
+    /// Reads a string from the minibuffer, prompting with `prompt`, and offering completion from
+    /// `collection`. This is the equivalent of the Lisp function [`completing-read`].
+    ///
+    /// `collection` is usually a Lisp list or vector of candidate strings, or a tuple of Rust
+    /// strings, which is converted into a list. Any other completion table accepted by
+    /// `completing-read`, e.g. an alist, or a function, also works. If `require_match` is true,
+    /// the user must enter one of the candidates, or an empty string.
+    ///
+    /// As with [`read_string`], this waits for user input. If the user quits with `C-g`, a `quit`
+    /// signal is returned as an error, which should be propagated back to Emacs.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result};
+    /// fn ask_fruit(env: &Env) -> Result<String> {
+    ///     env.completing_read("Fruit: ", ("apple", "banana", "cherry"), true)
+    /// }
+    /// ```
+    ///
+    /// [`completing-read`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Minibuffer-Completion.html
+    /// [`read_string`]: #method.read_string
+    pub fn completing_read<'e, C: IntoLisp<'e>>(
+        &'e self,
+        prompt: &str,
+        collection: C,
+        require_match: bool,
+    ) -> Result<String> {
+        self.call("completing-read", (prompt, collection, (), require_match))?.into_rust()
+    }
+
+    /// Adds `value` to the front of the history list held in the variable `history_var`, then
+    /// returns the new list. This is the equivalent of the Lisp function [`add-to-history`]. It is
+    /// useful for commands that maintain their own history lists, for use with [`read_string`].
+    ///
+    /// As with `add-to-history`, `value` is not added if it is empty, or equal to the most recent
+    /// element. If `history-delete-duplicates` is non-nil, other occurrences of `value` are
+    /// removed. The list is then trimmed to `maxelt` elements, defaulting to the `history-length`
+    /// property of `history_var`, or the value of the variable `history-length`.
+    ///
+    /// [`add-to-history`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Minibuffer-History.html
+    /// [`read_string`]: #method.read_string
+    pub fn add_to_history(
+        &self,
+        history_var: &str,
+        value: &str,
+        maxelt: Option<i64>,
+    ) -> Result<Value<'_>> {
+        self.call("add-to-history", (self.intern(history_var)?, value, maxelt))
+    }
+}
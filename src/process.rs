@@ -0,0 +1,36 @@
+//! Helpers for communicating with subprocesses.
+
+use crate::{Env, Result, Value};
+
+impl Env {
+    /// Sends `text` to the standard input of `process`, a process or a process name. This is the
+    /// equivalent of the Lisp function [`process-send-string`].
+    ///
+    /// [`process-send-string`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Input-to-Processes.html
+    pub fn process_send_string(&self, process: Value<'_>, text: &str) -> Result<()> {
+        self.call("process-send-string", (process, text))?;
+        Ok(())
+    }
+
+    /// Sends an end-of-file to `process`, a process or a process name, returning the process. This
+    /// is the equivalent of the Lisp function [`process-send-eof`].
+    ///
+    /// For a process communicating through a pipe, this closes its standard input.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result, Value};
+    /// fn send_all<'e>(env: &'e Env, process: Value<'e>, lines: &[&str]) -> Result<()> {
+    ///     for line in lines {
+    ///         env.process_send_string(process, line)?;
+    ///         env.process_send_string(process, "\n")?;
+    ///     }
+    ///     env.process_send_eof(process)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`process-send-eof`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Input-to-Processes.html
+    pub fn process_send_eof<'e>(&'e self, process: Value<'e>) -> Result<Value<'e>> {
+        self.call("process-send-eof", [process])
+    }
+}
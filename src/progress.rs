@@ -0,0 +1,60 @@
+//! Reporting the progress of long-running operations.
+
+use crate::{Env, Result, Value};
+
+/// A Lisp [progress reporter], which displays the progress of a long-running operation in the echo
+/// area. It is created by [`Env::make_progress_reporter`].
+///
+/// [progress reporter]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Progress.html
+/// [`Env::make_progress_reporter`]: struct.Env.html#method.make_progress_reporter
+#[derive(Debug, Clone, Copy)]
+pub struct ProgressReporter<'e> {
+    reporter: Value<'e>,
+}
+
+impl<'e> ProgressReporter<'e> {
+    /// Returns the underlying Lisp progress reporter object.
+    #[inline]
+    pub fn value(&self) -> Value<'e> {
+        self.reporter
+    }
+
+    /// Reports that the operation has reached `value`, which should be between the reporter's
+    /// minimum and maximum values. This is the equivalent of the Lisp function
+    /// [`progress-reporter-update`].
+    ///
+    /// To avoid slowing down the operation, the message is only updated occasionally, so this can
+    /// be called frequently.
+    ///
+    /// [`progress-reporter-update`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Progress.html
+    pub fn update(&self, value: i64) -> Result<()> {
+        self.reporter.env.call("progress-reporter-update", (self.reporter, value))?;
+        Ok(())
+    }
+
+    /// Reports that the operation has finished. This is the equivalent of the Lisp function
+    /// [`progress-reporter-done`].
+    ///
+    /// [`progress-reporter-done`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Progress.html
+    pub fn done(self) -> Result<()> {
+        self.reporter.env.call("progress-reporter-done", [self.reporter])?;
+        Ok(())
+    }
+}
+
+impl Env {
+    /// Creates a progress reporter that displays `message`, followed by the percentage of the
+    /// operation that has been completed, as values between `min` and `max` are reported. This is
+    /// the equivalent of the Lisp function [`make-progress-reporter`].
+    ///
+    /// [`make-progress-reporter`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Progress.html
+    pub fn make_progress_reporter(
+        &self,
+        message: &str,
+        min: i64,
+        max: i64,
+    ) -> Result<ProgressReporter<'_>> {
+        let reporter = self.call("make-progress-reporter", (message, min, max))?;
+        Ok(ProgressReporter { reporter })
+    }
+}
@@ -0,0 +1,136 @@
+//! Cooperative quitting, for long-running Rust code.
+
+use std::mem;
+
+use emacs_module::{
+    emacs_env_26, emacs_env_27, emacs_process_input_result, emacs_process_input_continue,
+    emacs_process_input_quit,
+};
+
+use crate::{symbol, Env, Result};
+
+// We use const instead of enum, in case Emacs add more results in the future.
+const CONTINUE: emacs_process_input_result = emacs_process_input_continue;
+const QUIT: emacs_process_input_result = emacs_process_input_quit;
+
+crate::use_symbols! {
+    quit quit_flag
+}
+
+/// The result of [`Env::process_input`].
+///
+/// [`Env::process_input`]: struct.Env.html#method.process_input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessInputResult {
+    /// The Rust code may continue.
+    Continue,
+    /// The user has requested a quit. The Rust code should return to Emacs as soon as possible,
+    /// e.g. by returning [`Env::signal_quit`].
+    ///
+    /// [`Env::signal_quit`]: struct.Env.html#method.signal_quit
+    Quit,
+}
+
+impl Env {
+    /// Returns whether the user has requested a quit, e.g. by typing `C-g`. When this returns true,
+    /// the Rust code should return to Emacs as soon as possible, e.g. by returning
+    /// [`signal_quit`].
+    ///
+    /// This requires Emacs 26 or later. On older versions, it always returns false.
+    ///
+    /// [`signal_quit`]: #method.signal_quit
+    pub fn should_quit(&self) -> bool {
+        match self.raw_26() {
+            // Safety: The function comes from the env, which has the right size.
+            Some(raw) => unsafe {
+                let should_quit = raw.should_quit.expect("Required module function does not exist: should_quit");
+                should_quit(self.raw)
+            },
+            None => false,
+        }
+    }
+
+    /// Processes pending input events, returning whether the user has requested a quit. Unlike
+    /// [`should_quit`], this lets Emacs notice `C-g` even on platforms where keyboard input is not
+    /// otherwise read during Rust code, so it is the better choice for long-running loops.
+    ///
+    /// This requires Emacs 27 or later. On older versions, it falls back to [`should_quit`].
+    ///
+    /// ```
+    /// # use emacs::{defun, Env, ProcessInputResult, Result};
+    /// #[defun]
+    /// fn count_primes(env: &Env, limit: u64) -> Result<u64> {
+    ///     let mut count = 0;
+    ///     for n in 2..limit {
+    ///         if n % 10_000 == 0 && env.process_input()? == ProcessInputResult::Quit {
+    ///             return env.signal_quit();
+    ///         }
+    ///         if (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0) {
+    ///             count += 1;
+    ///         }
+    ///     }
+    ///     Ok(count)
+    /// }
+    /// ```
+    ///
+    /// [`should_quit`]: #method.should_quit
+    pub fn process_input(&self) -> Result<ProcessInputResult> {
+        let raw = match self.raw_27() {
+            Some(raw) => raw,
+            None => return Ok(if self.should_quit() {
+                ProcessInputResult::Quit
+            } else {
+                ProcessInputResult::Continue
+            }),
+        };
+        // Safety: The function comes from the env, which has the right size.
+        let result = unsafe {
+            let process_input = raw.process_input.expect("Required module function does not exist: process_input");
+            process_input(self.raw)
+        };
+        match result {
+            CONTINUE => Ok(ProcessInputResult::Continue),
+            QUIT => {
+                // Emacs signals `quit` by setting a pending non-local exit, which we clear, since
+                // the caller decides how to return. The resulting error is dropped, which queues
+                // its values to be freed.
+                let _ = self.handle_exit(());
+                Ok(ProcessInputResult::Quit)
+            }
+            _ => {
+                let message = format!("Unexpected process_input result {}", result);
+                self.signal(symbol::rust_error, (message,))
+            }
+        }
+    }
+
+    /// Signals `quit`, the same error signaled when the user types `C-g`. Like Emacs does when
+    /// processing `C-g`, this clears `quit-flag` first, so that the quit is not handled again.
+    pub fn signal_quit<T>(&self) -> Result<T> {
+        // If a quit is pending, Emacs signals it when this calls back into Lisp, and clears the flag.
+        self.call("set", (quit_flag, ()))?;
+        self.signal(quit, [])
+    }
+
+    fn raw_26(&self) -> Option<&emacs_env_26> {
+        // Safety: Emacs sets `size` to the size of the env struct it actually provides.
+        unsafe {
+            if (*self.raw).size as usize >= mem::size_of::<emacs_env_26>() {
+                Some(&*(self.raw as *const emacs_env_26))
+            } else {
+                None
+            }
+        }
+    }
+
+    fn raw_27(&self) -> Option<&emacs_env_27> {
+        // Safety: Emacs sets `size` to the size of the env struct it actually provides.
+        unsafe {
+            if (*self.raw).size as usize >= mem::size_of::<emacs_env_27>() {
+                Some(&*(self.raw as *const emacs_env_27))
+            } else {
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,516 @@
+//! Converting Lisp values into Rust values.
+
+use std::fmt::{self, Display};
+
+use ::serde::de::{self, DeserializeOwned, DeserializeSeed, Visitor};
+
+use crate::{FromLisp, HashTable, Result, Value};
+
+/// Converts a Lisp value into a Rust value of type `T`:
+/// - `bool` is `false` for `nil`, and `true` for any other value.
+/// - Numbers, strings, and byte arrays are converted with [`FromLisp`]. Integers are also accepted
+///   where floats are expected.
+/// - `char` is converted from a Lisp character, i.e. an integer.
+/// - `Option` is `None` for `nil`. `()` and unit structs accept only `nil`.
+/// - Sequences, tuples, and tuple structs are converted from lists or vectors.
+/// - Maps and structs are converted from hash tables, plists, or alists. A list whose first
+///   element is a keyword is a plist. Keywords used as keys, e.g. `:width`, are converted without
+///   the leading colon. `nil` is an empty map.
+/// - Enum variants are converted from the externally-tagged form: a symbol or a string for a
+///   unit variant, and otherwise a hash table, or an alist, with a single entry keyed by it.
+///
+/// The returned error's message describes where in `value` a conversion failed, e.g. `expected
+/// integer, found string at :shapes[2] :width`.
+///
+/// [`FromLisp`]: ../trait.FromLisp.html
+pub fn from_lisp<T: DeserializeOwned>(value: Value<'_>) -> Result<T> {
+    T::deserialize(Deserializer::new(value)).map_err(DeError::into_error)
+}
+
+#[derive(Debug)]
+enum DeError {
+    /// A value could not be converted. The path to it is stored innermost segment first.
+    Invalid { message: String, path: Vec<String> },
+    /// An error signaled by Lisp code.
+    Lisp(crate::Error),
+}
+
+impl DeError {
+    fn invalid(message: String) -> Self {
+        DeError::Invalid { message, path: vec![] }
+    }
+
+    /// Records that this error happened at the given segment of the path, i.e. inside the value
+    /// of a map entry, or an element of a sequence.
+    fn at<F: FnOnce() -> String>(self, segment: F) -> Self {
+        match self {
+            DeError::Invalid { message, mut path } => {
+                path.push(segment());
+                DeError::Invalid { message, path }
+            }
+            error => error,
+        }
+    }
+
+    fn into_error(self) -> crate::Error {
+        match self {
+            DeError::Invalid { message, path } if path.is_empty() => anyhow::anyhow!("{}", message),
+            DeError::Invalid { message, path } => {
+                let path: String = path.into_iter().rev().collect();
+                anyhow::anyhow!("{} at {}", message, path.trim_start())
+            }
+            DeError::Lisp(error) => error,
+        }
+    }
+}
+
+impl Display for DeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeError::Invalid { message, .. } => Display::fmt(message, f),
+            DeError::Lisp(error) => Display::fmt(error, f),
+        }
+    }
+}
+
+impl std::error::Error for DeError {}
+
+impl de::Error for DeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        DeError::invalid(msg.to_string())
+    }
+}
+
+impl From<crate::Error> for DeError {
+    fn from(error: crate::Error) -> Self {
+        DeError::Lisp(error)
+    }
+}
+
+type DeResult<T> = std::result::Result<T, DeError>;
+
+/// Returns the path segment for the value of the map entry with the given key, e.g. ` :width`.
+fn key_segment(key: Value<'_>) -> String {
+    let printed = key.env.call("format", ("%S", key)).and_then(|s| s.into_rust::<String>());
+    format!(" {}", printed.unwrap_or_else(|_| "?".to_owned()))
+}
+
+fn index_segment(index: usize) -> String {
+    format!("[{}]", index)
+}
+
+#[derive(Clone, Copy)]
+struct Deserializer<'e> {
+    value: Value<'e>,
+    /// Whether this is a map key, in which case symbols can be converted into strings.
+    key: bool,
+}
+
+impl<'e> Deserializer<'e> {
+    fn new(value: Value<'e>) -> Self {
+        Deserializer { value, key: false }
+    }
+
+    fn key(value: Value<'e>) -> Self {
+        Deserializer { value, key: true }
+    }
+
+    fn is(&self, predicate: &str) -> DeResult<bool> {
+        Ok(self.value.env.call(predicate, [self.value])?.is_not_nil())
+    }
+
+    fn expected(&self, what: &str) -> DeError {
+        let found = if self.value.is_not_nil() {
+            self.value.env.type_of(self.value)
+                .and_then(|t| t.env.call("symbol-name", [t])?.into_rust::<String>())
+                .unwrap_or_else(|_| "unknown".to_owned())
+        } else {
+            "nil".to_owned()
+        };
+        DeError::invalid(format!("expected {}, found {}", what, found))
+    }
+
+    /// Returns the name of a symbol, without the leading colon if it is a keyword.
+    fn symbol_name(&self) -> DeResult<String> {
+        let name: String = self.value.env.call("symbol-name", [self.value])?.into_rust()?;
+        if self.is("keywordp")? {
+            return Ok(name[1..].to_owned());
+        }
+        Ok(name)
+    }
+
+    fn integer<T: FromLisp<'e>>(self, name: &str) -> DeResult<T> {
+        if !self.value.is_integer() {
+            return Err(self.expected("integer"));
+        }
+        self.value.into_rust().map_err(|_| DeError::invalid(format!("integer out of range for {}", name)))
+    }
+
+    fn float(self) -> DeResult<f64> {
+        if self.value.is_float() {
+            return Ok(self.value.into_rust()?);
+        }
+        if self.value.is_integer() {
+            return Ok(self.integer::<i64>("f64")? as f64);
+        }
+        Err(self.expected("number"))
+    }
+
+    fn string(self) -> DeResult<String> {
+        if self.value.is_string() {
+            return Ok(self.value.into_rust()?);
+        }
+        if self.key && self.is("symbolp")? {
+            return self.symbol_name();
+        }
+        Err(self.expected("string"))
+    }
+
+    /// Returns the elements of a list or a vector.
+    fn elements(self) -> DeResult<Vec<Value<'e>>> {
+        let env = self.value.env;
+        if self.value.is_vector() {
+            let len = self.value.vec_len()?;
+            return (0..len).map(|i| Ok(self.value.vec_get(i)?)).collect();
+        }
+        if self.value.is_not_nil() && !self.value.is_cons() {
+            return Err(self.expected("list or vector"));
+        }
+        env.list_iter(self.value)?
+            .map(|item| item.map_err(|_| self.expected("proper list")))
+            .collect()
+    }
+
+    /// Returns the entries of a hash table, a plist, or an alist.
+    fn entries(self) -> DeResult<Vec<(Value<'e>, Value<'e>)>> {
+        let env = self.value.env;
+        if self.is("hash-table-p")? {
+            let table: HashTable = self.value.into_rust()?;
+            return Ok(table.iter()?.collect::<Result<_>>()?);
+        }
+        if self.value.is_not_nil() && !self.value.is_cons() {
+            return Err(self.expected("hash table, plist, or alist"));
+        }
+        let items = self.elements()?;
+        match items.first() {
+            Some(first) if Deserializer::new(*first).is("keywordp")? => {
+                if items.len() % 2 != 0 {
+                    return Err(DeError::invalid("expected plist with an even number of elements".to_owned()));
+                }
+                Ok(items.chunks(2).map(|pair| (pair[0], pair[1])).collect())
+            }
+            _ => items
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    if !item.is_cons() {
+                        return Err(Deserializer::new(item).expected("cons").at(|| index_segment(i)));
+                    }
+                    Ok((env.call("car", [item])?, env.call("cdr", [item])?))
+                })
+                .collect(),
+        }
+    }
+}
+
+macro_rules! deserialize_integer {
+    ($($method:ident => $visit:ident($t:ty))*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+                visitor.$visit(self.integer::<$t>(stringify!($t))?)
+            }
+        )*
+    };
+}
+
+impl<'de, 'e> de::Deserializer<'de> for Deserializer<'e> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        let value = self.value;
+        if !value.is_not_nil() {
+            visitor.visit_unit()
+        } else if value.is_integer() {
+            match value.into_rust::<i64>() {
+                Ok(i) => visitor.visit_i64(i),
+                Err(_) => self.deserialize_i128(visitor),
+            }
+        } else if value.is_float() {
+            visitor.visit_f64(value.into_rust()?)
+        } else if value.is_string() {
+            visitor.visit_string(value.into_rust()?)
+        } else if value.eq(value.env.intern("t")?) {
+            visitor.visit_bool(true)
+        } else if self.is("symbolp")? {
+            visitor.visit_string(self.symbol_name()?)
+        } else if value.is_vector() {
+            self.deserialize_seq(visitor)
+        } else if self.is("hash-table-p")? {
+            self.deserialize_map(visitor)
+        } else if value.is_cons() {
+            if Deserializer::new(value.car()?).is("keywordp")? {
+                self.deserialize_map(visitor)
+            } else {
+                self.deserialize_seq(visitor)
+            }
+        } else {
+            Err(self.expected("convertible value"))
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_bool(self.value.is_not_nil())
+    }
+
+    deserialize_integer! {
+        deserialize_i8 => visit_i8(i8)
+        deserialize_i16 => visit_i16(i16)
+        deserialize_i32 => visit_i32(i32)
+        deserialize_i64 => visit_i64(i64)
+        deserialize_i128 => visit_i128(i128)
+        deserialize_u8 => visit_u8(u8)
+        deserialize_u16 => visit_u16(u16)
+        deserialize_u32 => visit_u32(u32)
+        deserialize_u64 => visit_u64(u64)
+        deserialize_u128 => visit_u128(u128)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_f32(self.float()? as f32)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_f64(self.float()?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        let code = self.integer::<u32>("char")?;
+        match std::char::from_u32(code) {
+            Some(c) => visitor.visit_char(c),
+            None => Err(DeError::invalid(format!("invalid character {}", code))),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_string(self.string()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_string(self.string()?)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        if !self.value.is_string() {
+            return Err(self.expected("string"));
+        }
+        visitor.visit_byte_buf(self.value.as_bytes()?)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        if self.value.is_not_nil() {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        if self.value.is_not_nil() {
+            return Err(self.expected("nil"));
+        }
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> DeResult<V::Value> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> DeResult<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_seq(SeqAccess { items: self.elements()?.into_iter(), index: 0 })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> DeResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> DeResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_map(MapAccess { entries: self.entries()?.into_iter(), value: None })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> DeResult<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> DeResult<V::Value> {
+        if self.value.is_string() || self.is("symbolp")? {
+            return visitor.visit_enum(EnumAccess { variant: self.value, content: None });
+        }
+        if self.is("hash-table-p")? || self.value.is_cons() {
+            let mut entries = self.entries()?;
+            if entries.len() == 1 {
+                let (variant, content) = entries.remove(0);
+                return visitor.visit_enum(EnumAccess { variant, content: Some(content) });
+            }
+        }
+        Err(self.expected("symbol, or single-entry map"))
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        if self.value.is_string() {
+            return visitor.visit_string(self.value.into_rust()?);
+        }
+        if self.is("symbolp")? {
+            return visitor.visit_string(self.symbol_name()?);
+        }
+        Err(self.expected("symbol or string"))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> DeResult<V::Value> {
+        visitor.visit_unit()
+    }
+}
+
+struct SeqAccess<'e> {
+    items: std::vec::IntoIter<Value<'e>>,
+    index: usize,
+}
+
+impl<'de, 'e> de::SeqAccess<'de> for SeqAccess<'e> {
+    type Error = DeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> DeResult<Option<T::Value>> {
+        let item = match self.items.next() {
+            Some(item) => item,
+            None => return Ok(None),
+        };
+        let index = self.index;
+        self.index += 1;
+        seed.deserialize(Deserializer::new(item)).map(Some).map_err(|e| e.at(|| index_segment(index)))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+struct MapAccess<'e> {
+    entries: std::vec::IntoIter<(Value<'e>, Value<'e>)>,
+    /// The key and value of the entry whose key was just deserialized.
+    value: Option<(Value<'e>, Value<'e>)>,
+}
+
+impl<'de, 'e> de::MapAccess<'de> for MapAccess<'e> {
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> DeResult<Option<K::Value>> {
+        let (key, value) = match self.entries.next() {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        self.value = Some((key, value));
+        seed.deserialize(Deserializer::key(key)).map(Some).map_err(|e| e.at(|| key_segment(key)))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> DeResult<V::Value> {
+        let (key, value) = self.value.take()
+            .ok_or_else(|| DeError::invalid("next_value_seed called before next_key_seed".to_owned()))?;
+        seed.deserialize(Deserializer::new(value)).map_err(|e| e.at(|| key_segment(key)))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+/// An enum variant, in the externally-tagged form. `content` is `None` for a bare symbol or string.
+struct EnumAccess<'e> {
+    variant: Value<'e>,
+    content: Option<Value<'e>>,
+}
+
+impl<'e> EnumAccess<'e> {
+    fn content(&self) -> DeResult<Deserializer<'e>> {
+        match self.content {
+            Some(content) => Ok(Deserializer::new(content)),
+            None => Err(Deserializer::new(self.variant).expected("single-entry map")),
+        }
+    }
+}
+
+impl<'de, 'e> de::EnumAccess<'de> for EnumAccess<'e> {
+    type Error = DeError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> DeResult<(V::Value, Self)> {
+        let variant = seed.deserialize(Deserializer::key(self.variant))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, 'e> de::VariantAccess<'de> for EnumAccess<'e> {
+    type Error = DeError;
+
+    fn unit_variant(self) -> DeResult<()> {
+        match self.content {
+            Some(content) => de::Deserialize::deserialize(Deserializer::new(content))
+                .map_err(|e: DeError| e.at(|| key_segment(self.variant))),
+            None => Ok(()),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> DeResult<T::Value> {
+        let variant = self.variant;
+        seed.deserialize(self.content()?).map_err(|e| e.at(|| key_segment(variant)))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> DeResult<V::Value> {
+        let variant = self.variant;
+        de::Deserializer::deserialize_seq(self.content()?, visitor)
+            .map_err(|e| e.at(|| key_segment(variant)))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> DeResult<V::Value> {
+        let variant = self.variant;
+        de::Deserializer::deserialize_map(self.content()?, visitor)
+            .map_err(|e| e.at(|| key_segment(variant)))
+    }
+}
@@ -0,0 +1,31 @@
+//! Converting between Rust values and Lisp values with [`serde`]. This requires the `serde`
+//! feature.
+//!
+//! ```
+//! use emacs::{defun, Result, Value};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Position {
+//!     line: u32,
+//!     column: u32,
+//! }
+//!
+//! // Accepts a plist, e.g. (:line 1 :column 0), or an alist, e.g. ((line . 1) (column . 0)).
+//! // Returns a plist.
+//! #[defun]
+//! fn next_line(position: Value) -> Result<Value> {
+//!     let Position { line, column } = emacs::serde::from_lisp(position)?;
+//!     emacs::serde::to_lisp(position.env, &Position { line: line + 1, column })
+//! }
+//! ```
+//!
+//! [`serde`]: https://serde.rs/
+
+mod ser;
+mod de;
+
+pub use self::{
+    ser::{to_lisp, to_lisp_with, MapRepr, SerOptions, SeqRepr},
+    de::from_lisp,
+};
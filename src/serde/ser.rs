@@ -0,0 +1,475 @@
+//! Converting Rust values into Lisp values.
+
+use std::fmt::{self, Display};
+
+use ::serde::ser::{self, Serialize};
+
+use crate::{subr, Env, HashTable, HashTableTest, IntoLisp, Result, Value};
+
+/// How Rust maps are converted by [`to_lisp_with`].
+///
+/// [`to_lisp_with`]: fn.to_lisp_with.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapRepr {
+    /// A hash table that uses `equal` to compare keys.
+    HashTable,
+    /// An alist, i.e. a list of `(KEY . VALUE)` pairs, in the order the map yields them.
+    Alist,
+}
+
+/// How Rust sequences (including tuples) are converted by [`to_lisp_with`].
+///
+/// [`to_lisp_with`]: fn.to_lisp_with.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeqRepr {
+    List,
+    Vector,
+}
+
+/// Options for [`to_lisp_with`]. The default converts maps into hash tables, and sequences into
+/// lists.
+///
+/// [`to_lisp_with`]: fn.to_lisp_with.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerOptions {
+    pub maps: MapRepr,
+    pub sequences: SeqRepr,
+}
+
+impl Default for SerOptions {
+    fn default() -> Self {
+        SerOptions { maps: MapRepr::HashTable, sequences: SeqRepr::List }
+    }
+}
+
+/// Converts `value` into a Lisp value, using the default [`SerOptions`]. See [`to_lisp_with`].
+///
+/// [`SerOptions`]: struct.SerOptions.html
+/// [`to_lisp_with`]: fn.to_lisp_with.html
+pub fn to_lisp<'e, T: Serialize + ?Sized>(env: &'e Env, value: &T) -> Result<Value<'e>> {
+    to_lisp_with(env, value, &SerOptions::default())
+}
+
+/// Converts `value` into a Lisp value:
+/// - `bool`, numbers, strings, and byte arrays are converted with [`IntoLisp`]. Notably, integer
+///   conversion is lossless by default.
+/// - `char` becomes a Lisp character, i.e. an integer.
+/// - `None`, `()`, and unit structs become `nil`. `Some(x)` and newtype structs become `x`.
+/// - Sequences, tuples, and tuple structs become lists or vectors, according to `options`.
+/// - Maps become hash tables or alists, according to `options`.
+/// - Structs become plists keyed by keywords, e.g. `(:line 1 :column 0)`.
+/// - Enum variants are externally tagged: a unit variant becomes a symbol named after it, and other
+///   variants become a map with a single entry, whose key is that symbol.
+///
+/// The elements of a sequence are converted one after the other, so only nesting, not length,
+/// consumes stack space.
+///
+/// [`IntoLisp`]: ../trait.IntoLisp.html
+pub fn to_lisp_with<'e, T: Serialize + ?Sized>(
+    env: &'e Env,
+    value: &T,
+    options: &SerOptions,
+) -> Result<Value<'e>> {
+    value.serialize(Serializer { env, options }).map_err(|e| e.0)
+}
+
+/// Wraps [`Error`] to implement [`ser::Error`], which requires `std::error::Error`.
+///
+/// [`Error`]: ../struct.Error.html
+/// [`ser::Error`]: https://docs.rs/serde/1/serde/ser/trait.Error.html
+#[derive(Debug)]
+struct SerError(crate::Error);
+
+impl Display for SerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl std::error::Error for SerError {}
+
+impl ser::Error for SerError {
+    fn custom<T: Display>(msg: T) -> Self {
+        SerError(anyhow::anyhow!("{}", msg))
+    }
+}
+
+impl From<crate::Error> for SerError {
+    fn from(error: crate::Error) -> Self {
+        SerError(error)
+    }
+}
+
+type SerResult<T> = std::result::Result<T, SerError>;
+
+#[derive(Clone, Copy)]
+struct Serializer<'o, 'e> {
+    env: &'e Env,
+    options: &'o SerOptions,
+}
+
+impl<'o, 'e> Serializer<'o, 'e> {
+    fn convert<T: IntoLisp<'e>>(self, value: T) -> SerResult<Value<'e>> {
+        Ok(value.into_lisp(self.env)?)
+    }
+
+    fn sequence(self, items: &[Value<'e>]) -> SerResult<Value<'e>> {
+        let sequence = match self.options.sequences {
+            SeqRepr::List => self.env.call(subr::list, items)?,
+            SeqRepr::Vector => self.env.call(subr::vector, items)?,
+        };
+        Ok(sequence)
+    }
+
+    fn map(self, entries: &[(Value<'e>, Value<'e>)]) -> SerResult<Value<'e>> {
+        match self.options.maps {
+            MapRepr::HashTable => {
+                let table: HashTable = self.env.make_hash_table(HashTableTest::Equal)?;
+                for (key, value) in entries {
+                    table.insert(*key, *value)?;
+                }
+                Ok(table.value())
+            }
+            MapRepr::Alist => {
+                let pairs = entries
+                    .iter()
+                    .map(|(key, value)| self.env.cons(*key, *value))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(self.env.call(subr::list, pairs.as_slice())?)
+            }
+        }
+    }
+
+    /// Wraps the content of a non-unit enum variant in a single-entry map, keyed by its name.
+    fn tagged(self, variant: &'static str, content: Value<'e>) -> SerResult<Value<'e>> {
+        self.map(&[(self.env.intern(variant)?, content)])
+    }
+}
+
+impl<'o, 'e> ser::Serializer for Serializer<'o, 'e> {
+    type Ok = Value<'e>;
+    type Error = SerError;
+    type SerializeSeq = SerializeSeq<'o, 'e>;
+    type SerializeTuple = SerializeSeq<'o, 'e>;
+    type SerializeTupleStruct = SerializeSeq<'o, 'e>;
+    type SerializeTupleVariant = SerializeSeq<'o, 'e>;
+    type SerializeMap = SerializeMap<'o, 'e>;
+    type SerializeStruct = SerializeStruct<'o, 'e>;
+    type SerializeStructVariant = SerializeStruct<'o, 'e>;
+
+    fn serialize_bool(self, v: bool) -> SerResult<Value<'e>> {
+        self.convert(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> SerResult<Value<'e>> {
+        self.convert(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> SerResult<Value<'e>> {
+        self.convert(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> SerResult<Value<'e>> {
+        self.convert(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> SerResult<Value<'e>> {
+        self.convert(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> SerResult<Value<'e>> {
+        self.convert(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> SerResult<Value<'e>> {
+        self.convert(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> SerResult<Value<'e>> {
+        self.convert(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> SerResult<Value<'e>> {
+        self.convert(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> SerResult<Value<'e>> {
+        self.convert(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> SerResult<Value<'e>> {
+        self.convert(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> SerResult<Value<'e>> {
+        self.convert(f64::from(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> SerResult<Value<'e>> {
+        self.convert(v)
+    }
+
+    fn serialize_char(self, v: char) -> SerResult<Value<'e>> {
+        self.convert(i64::from(u32::from(v)))
+    }
+
+    fn serialize_str(self, v: &str) -> SerResult<Value<'e>> {
+        self.convert(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> SerResult<Value<'e>> {
+        self.convert(v)
+    }
+
+    fn serialize_none(self) -> SerResult<Value<'e>> {
+        self.convert(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> SerResult<Value<'e>> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> SerResult<Value<'e>> {
+        self.convert(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> SerResult<Value<'e>> {
+        self.convert(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> SerResult<Value<'e>> {
+        Ok(self.env.intern(variant)?)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> SerResult<Value<'e>> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> SerResult<Value<'e>> {
+        self.tagged(variant, value.serialize(self)?)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> SerResult<SerializeSeq<'o, 'e>> {
+        Ok(SerializeSeq { ser: self, items: Vec::with_capacity(len.unwrap_or(0)), variant: None })
+    }
+
+    fn serialize_tuple(self, len: usize) -> SerResult<SerializeSeq<'o, 'e>> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> SerResult<SerializeSeq<'o, 'e>> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> SerResult<SerializeSeq<'o, 'e>> {
+        Ok(SerializeSeq { ser: self, items: Vec::with_capacity(len), variant: Some(variant) })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> SerResult<SerializeMap<'o, 'e>> {
+        Ok(SerializeMap { ser: self, entries: Vec::with_capacity(len.unwrap_or(0)), key: None })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> SerResult<SerializeStruct<'o, 'e>> {
+        Ok(SerializeStruct { ser: self, plist: Vec::with_capacity(2 * len), variant: None })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> SerResult<SerializeStruct<'o, 'e>> {
+        Ok(SerializeStruct { ser: self, plist: Vec::with_capacity(2 * len), variant: Some(variant) })
+    }
+}
+
+/// Collects the elements of a sequence, tuple, tuple struct, or tuple variant.
+struct SerializeSeq<'o, 'e> {
+    ser: Serializer<'o, 'e>,
+    items: Vec<Value<'e>>,
+    variant: Option<&'static str>,
+}
+
+impl<'o, 'e> SerializeSeq<'o, 'e> {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> SerResult<()> {
+        self.items.push(value.serialize(self.ser)?);
+        Ok(())
+    }
+
+    fn finish(self) -> SerResult<Value<'e>> {
+        let sequence = self.ser.sequence(&self.items)?;
+        match self.variant {
+            Some(variant) => self.ser.tagged(variant, sequence),
+            None => Ok(sequence),
+        }
+    }
+}
+
+impl<'o, 'e> ser::SerializeSeq for SerializeSeq<'o, 'e> {
+    type Ok = Value<'e>;
+    type Error = SerError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> SerResult<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> SerResult<Value<'e>> {
+        self.finish()
+    }
+}
+
+impl<'o, 'e> ser::SerializeTuple for SerializeSeq<'o, 'e> {
+    type Ok = Value<'e>;
+    type Error = SerError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> SerResult<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> SerResult<Value<'e>> {
+        self.finish()
+    }
+}
+
+impl<'o, 'e> ser::SerializeTupleStruct for SerializeSeq<'o, 'e> {
+    type Ok = Value<'e>;
+    type Error = SerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> SerResult<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> SerResult<Value<'e>> {
+        self.finish()
+    }
+}
+
+impl<'o, 'e> ser::SerializeTupleVariant for SerializeSeq<'o, 'e> {
+    type Ok = Value<'e>;
+    type Error = SerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> SerResult<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> SerResult<Value<'e>> {
+        self.finish()
+    }
+}
+
+/// Collects the entries of a map.
+struct SerializeMap<'o, 'e> {
+    ser: Serializer<'o, 'e>,
+    entries: Vec<(Value<'e>, Value<'e>)>,
+    /// The key whose value is to be serialized next.
+    key: Option<Value<'e>>,
+}
+
+impl<'o, 'e> ser::SerializeMap for SerializeMap<'o, 'e> {
+    type Ok = Value<'e>;
+    type Error = SerError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> SerResult<()> {
+        self.key = Some(key.serialize(self.ser)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> SerResult<()> {
+        let key = self.key.take().ok_or_else(|| {
+            <SerError as ser::Error>::custom("serialize_value called before serialize_key")
+        })?;
+        self.entries.push((key, value.serialize(self.ser)?));
+        Ok(())
+    }
+
+    fn end(self) -> SerResult<Value<'e>> {
+        self.ser.map(&self.entries)
+    }
+}
+
+/// Collects the fields of a struct, or a struct variant, into a plist.
+struct SerializeStruct<'o, 'e> {
+    ser: Serializer<'o, 'e>,
+    plist: Vec<Value<'e>>,
+    variant: Option<&'static str>,
+}
+
+impl<'o, 'e> SerializeStruct<'o, 'e> {
+    fn push<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> SerResult<()> {
+        self.plist.push(self.ser.env.intern(&format!(":{}", key))?);
+        self.plist.push(value.serialize(self.ser)?);
+        Ok(())
+    }
+
+    fn finish(self) -> SerResult<Value<'e>> {
+        let plist = self.ser.env.call(subr::list, self.plist.as_slice())?;
+        match self.variant {
+            Some(variant) => self.ser.tagged(variant, plist),
+            None => Ok(plist),
+        }
+    }
+}
+
+impl<'o, 'e> ser::SerializeStruct for SerializeStruct<'o, 'e> {
+    type Ok = Value<'e>;
+    type Error = SerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> SerResult<()> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> SerResult<Value<'e>> {
+        self.finish()
+    }
+}
+
+impl<'o, 'e> ser::SerializeStructVariant for SerializeStruct<'o, 'e> {
+    type Ok = Value<'e>;
+    type Error = SerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> SerResult<()> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> SerResult<Value<'e>> {
+        self.finish()
+    }
+}
@@ -1,5 +1,8 @@
 global_refs! {common(init_to_function) =>
-    cons car cdr
+    cons car cdr listp
+    identity
+    eql equal
+    functionp apply
     vector make_vector
     list
     message
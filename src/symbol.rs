@@ -1,3 +1,7 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use once_cell::sync::Lazy;
+
 use crate::{Env, Result, Value, global::{GlobalRef, OnceGlobalRef}};
 
 /// Defines static [`&OnceGlobalRef`] variables that point to corresponding Lisp symbols.
@@ -23,8 +27,19 @@ use_symbols! {
     rust_error
     rust_panic
     rust_wrong_type_user_ptr
+    wrong_type_argument
+    args_out_of_range
+    circular_list
+    wrong_number_of_arguments
 }
 
+/// Symbols interned by [`Env::intern_cached`]. Their global references are never freed, so they can
+/// be bound to any env.
+///
+/// [`Env::intern_cached`]: struct.Env.html#method.intern_cached
+static INTERNED: Lazy<Mutex<HashMap<String, &'static GlobalRef>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
 pub trait IntoLispSymbol<'e> {
     fn into_lisp_symbol(self, env: &'e Env) -> Result<Value<'e>>;
 }
@@ -56,3 +71,74 @@ impl<'e> IntoLispSymbol<'e> for &'e OnceGlobalRef {
         self.bind(env).into_lisp_symbol(env)
     }
 }
+
+impl Env {
+    /// Returns the symbol `nil`, without interning it.
+    #[inline]
+    pub fn nil(&self) -> Value<'_> {
+        nil.bind(self)
+    }
+
+    /// Returns the symbol `t`, without interning it.
+    #[inline]
+    pub fn t(&self) -> Value<'_> {
+        t.bind(self)
+    }
+
+    /// Returns the interned symbol with the given name, like [`intern`]. The symbol is kept alive by
+    /// a [`GlobalRef`], so later calls with the same name only need a hash table lookup.
+    ///
+    /// This is useful for symbols whose names are only known at runtime, but are used repeatedly.
+    /// For names known at compile time, [`use_symbols!`] and [`lisp_fn!`] are cheaper. Since the
+    /// cache is never cleared, this should not be used with an unbounded set of names.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result, Value};
+    /// fn face_property<'e>(env: &'e Env, face: Value<'e>, property: &str) -> Result<Value<'e>> {
+    ///     env.call("face-attribute", (face, env.intern_cached(property)?))
+    /// }
+    /// ```
+    ///
+    /// [`intern`]: #method.intern
+    /// [`GlobalRef`]: struct.GlobalRef.html
+    /// [`use_symbols!`]: macro.use_symbols.html
+    /// [`lisp_fn!`]: macro.lisp_fn.html
+    pub fn intern_cached(&self, name: &str) -> Result<Value<'_>> {
+        let mut interned = INTERNED.lock().expect("Symbol cache should not be poisoned");
+        if let Some(symbol) = interned.get(name) {
+            return Ok(symbol.bind(self));
+        }
+        let symbol: &'static GlobalRef = Box::leak(Box::new(self.intern(name)?.make_global_ref()));
+        interned.insert(name.to_owned(), symbol);
+        Ok(symbol.bind(self))
+    }
+
+    /// Returns the function definition of `symbol`, or `nil` if it has none. This is the equivalent
+    /// of the Lisp function [`symbol-function`].
+    ///
+    /// The definition is returned as is, i.e. aliases are not followed, and autoload objects are not
+    /// loaded. To call the function, it is usually simpler to call the symbol itself.
+    ///
+    /// [`symbol-function`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Function-Cells.html
+    pub fn symbol_function<'e, S: IntoLispSymbol<'e>>(&'e self, symbol: S) -> Result<Value<'e>> {
+        self.call("symbol-function", [symbol.into_lisp_symbol(self)?])
+    }
+
+    /// Returns whether `symbol` has a function definition, i.e. calling it does not signal
+    /// `void-function`. This is the equivalent of the Lisp function [`fboundp`].
+    ///
+    /// ```
+    /// # use emacs::{Env, Result, Value};
+    /// fn call_if_defined<'e>(env: &'e Env, name: &str, arg: Value<'e>) -> Result<Option<Value<'e>>> {
+    ///     if !env.fboundp(name)? {
+    ///         return Ok(None);
+    ///     }
+    ///     Ok(Some(env.call(name, [arg])?))
+    /// }
+    /// ```
+    ///
+    /// [`fboundp`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Function-Cells.html
+    pub fn fboundp<'e, S: IntoLispSymbol<'e>>(&'e self, symbol: S) -> Result<bool> {
+        Ok(self.call("fboundp", [symbol.into_lisp_symbol(self)?])?.is_not_nil())
+    }
+}
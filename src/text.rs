@@ -0,0 +1,138 @@
+//! Helpers for working with strings.
+
+use std::ops::Range;
+
+use crate::{symbol, Env, IntoLisp, Result, Value};
+
+/// A builder for a Lisp string with text properties on different parts of it. It is created by
+/// [`Env::propertized`].
+///
+/// Ranges are given in characters, not bytes, with 0 being the start of the string, as in Lisp. The
+/// properties are applied in the order they were added, so where ranges with the same property
+/// overlap, the one added last wins. Different properties on overlapping ranges are all kept.
+///
+/// ```
+/// # use emacs::{Env, Result, Value};
+/// fn status<'e>(env: &'e Env, name: &str, ok: bool) -> Result<Value<'e>> {
+///     let text = format!("{}: {}", name, if ok { "ok" } else { "failed" });
+///     let len = text.chars().count();
+///     let face = env.intern(if ok { "success" } else { "error" })?;
+///     env.propertized(&text)
+///         .with_property(0..len, "help-echo", env.intern("my-status-help")?)
+///         .with_property(name.chars().count() + 2..len, "face", face)
+///         .build()
+/// }
+/// ```
+///
+/// [`Env::propertized`]: struct.Env.html#method.propertized
+#[derive(Debug, Clone)]
+pub struct PropertizedString<'e> {
+    env: &'e Env,
+    text: String,
+    properties: Vec<(Range<usize>, String, Value<'e>)>,
+}
+
+impl<'e> PropertizedString<'e> {
+    /// Adds the text property `name`, which is interned as a symbol, with the given value, to the
+    /// characters in `range`.
+    pub fn with_property(mut self, range: Range<usize>, name: &str, value: Value<'e>) -> Self {
+        self.properties.push((range, name.to_owned(), value));
+        self
+    }
+
+    /// Adds the text property `name`, which is interned as a symbol, with the given value, to all
+    /// characters of the string, like the Lisp function [`propertize`].
+    ///
+    /// ```
+    /// # use emacs::{Env, IntoLisp, Result, Value};
+    /// fn link<'e>(env: &'e Env, text: &str, url: &str) -> Result<Value<'e>> {
+    ///     env.propertized(text)
+    ///         .with_property_on_all("face", env.intern("link")?)
+    ///         .with_property_on_all("help-echo", url.into_lisp(env)?)
+    ///         .build()
+    /// }
+    /// ```
+    ///
+    /// [`propertize`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Changing-Properties.html
+    pub fn with_property_on_all(self, name: &str, value: Value<'e>) -> Self {
+        let len = self.text.chars().count();
+        self.with_property(0..len, name, value)
+    }
+
+    /// Creates the Lisp string, then sets its text properties with [`put-text-property`].
+    ///
+    /// An `args-out-of-range` error is signaled, before any property is set, if a range does not
+    /// fit in the string, or its start is after its end.
+    ///
+    /// [`put-text-property`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Changing-Properties.html
+    pub fn build(&self) -> Result<Value<'e>> {
+        let env = self.env;
+        let string = self.text.as_str().into_lisp(env)?;
+        let len = self.text.chars().count();
+        for (range, _, _) in &self.properties {
+            if range.start > range.end || range.end > len {
+                return env.signal(symbol::args_out_of_range, (string, range.start, range.end));
+            }
+        }
+        for (range, name, value) in &self.properties {
+            env.call("put-text-property", (
+                range.start, range.end, env.intern(name)?, *value, string,
+            ))?;
+        }
+        Ok(string)
+    }
+}
+
+impl Env {
+    /// Returns a builder for a Lisp string with the content `text`, and text properties on
+    /// different parts of it. See [`PropertizedString`].
+    ///
+    /// [`PropertizedString`]: struct.PropertizedString.html
+    pub fn propertized(&self, text: &str) -> PropertizedString<'_> {
+        PropertizedString { env: self, text: text.to_owned(), properties: vec![] }
+    }
+
+    /// Returns `s` with leading and trailing whitespace removed. This is the equivalent of the Lisp
+    /// function [`string-trim`].
+    ///
+    /// [`string-trim`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Creating-Strings.html
+    pub fn string_trim(&self, s: &str) -> Result<String> {
+        // `string-trim` is defined in `subr-x` before Emacs 29.
+        self.call("require", [self.intern("subr-x")?])?;
+        self.call("string-trim", (s,))?.into_rust()
+    }
+
+    /// Splits `s` into substrings, at matches of the regular expression `separators`. This is the
+    /// equivalent of the Lisp function [`split-string`].
+    ///
+    /// If `separators` is `None`, the string is split at whitespace, and empty substrings are
+    /// always omitted. Otherwise, empty substrings are omitted only if `omit_nulls` is true.
+    ///
+    /// [`split-string`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Creating-Strings.html
+    pub fn split_string(
+        &self,
+        s: &str,
+        separators: Option<&str>,
+        omit_nulls: bool,
+    ) -> Result<Vec<String>> {
+        self.call("split-string", (s, separators, omit_nulls))?.into_rust()
+    }
+}
+
+impl<'e> Value<'e> {
+    /// Returns the text properties of the character at position `pos` of this Lisp string, as a
+    /// property list. This is the equivalent of the Lisp function [`text-properties-at`].
+    ///
+    /// Positions are counted in characters, starting from 0. An `args-out-of-range` error is
+    /// signaled if `pos` is not the position of a character in the string.
+    ///
+    /// [`text-properties-at`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Examining-Properties.html
+    pub fn text_properties_at(&self, pos: usize) -> Result<Value<'e>> {
+        let env = self.env;
+        let len: usize = env.call("length", [*self])?.into_rust()?;
+        if pos >= len {
+            return env.signal(symbol::args_out_of_range, (*self, pos));
+        }
+        env.call("text-properties-at", (pos, *self))
+    }
+}
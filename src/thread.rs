@@ -0,0 +1,135 @@
+//! Scheduling work on the Lisp thread from other threads.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use once_cell::sync::{Lazy, OnceCell};
+
+use crate::{Env, GlobalRef, IntoLisp, Result, Timer};
+
+/// A closure sent by another thread, to be called on the Lisp thread.
+type Job = Box<dyn FnOnce(&Env) -> Result<()> + Send>;
+
+/// How often the queue of jobs is checked, in seconds.
+const POLL_INTERVAL: f64 = 0.05;
+
+/// Jobs waiting to be called on the Lisp thread. Each [`MainThreadSender`] holds a clone of this,
+/// which tells the polling timer whether more jobs may arrive.
+static QUEUE: Lazy<Arc<Mutex<VecDeque<Job>>>> =
+    Lazy::new(|| Arc::new(Mutex::new(VecDeque::new())));
+
+/// The timer that calls the queued jobs, if it is running.
+static POLLER: Lazy<Mutex<Option<Timer>>> = Lazy::new(|| Mutex::new(None));
+
+/// The Lisp function called by the polling timer. It is created once, and reused each time the
+/// timer is restarted.
+static DRAIN: OnceCell<GlobalRef> = OnceCell::new();
+
+/// A handle for sending closures from other threads to the Lisp thread, where they are called
+/// with a valid [`Env`]. It is created by [`Env::main_thread_sender`].
+///
+/// Unlike [`Env`] and [`Value`], this can be sent to, and shared between, other threads. This is
+/// useful for reporting the results of background work, such as I/O done by worker threads.
+///
+/// # Implementation
+///
+/// Emacs provides no way for other threads to wake up the Lisp thread, so the closures are called
+/// from a timer, which checks for them every 50 milliseconds, while there is any sender alive, or
+/// any closure waiting to be called. Therefore, they are only called when Emacs runs timers, e.g.
+/// while waiting for user input, not while Lisp code is busy.
+///
+/// [`Env`]: struct.Env.html
+/// [`Value`]: struct.Value.html
+/// [`Env::main_thread_sender`]: struct.Env.html#method.main_thread_sender
+#[derive(Clone)]
+pub struct MainThreadSender {
+    queue: Arc<Mutex<VecDeque<Job>>>,
+}
+
+impl fmt::Debug for MainThreadSender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MainThreadSender").finish()
+    }
+}
+
+impl MainThreadSender {
+    /// Schedules `f` to be called on the Lisp thread. Closures are called in the order they were
+    /// sent.
+    ///
+    /// Since they are called from a timer, closures should return quickly, and must not block,
+    /// e.g. waiting for other threads, since that would freeze Emacs. Errors returned by them are
+    /// signaled in Lisp, which Emacs reports as timer errors. The remaining closures are then
+    /// called the next time the queue is checked.
+    pub fn send<F>(&self, f: F)
+    where
+        F: FnOnce(&Env) -> Result<()> + Send + 'static,
+    {
+        self.queue.lock().expect("Job queue should not be poisoned").push_back(Box::new(f));
+    }
+}
+
+impl Env {
+    /// Returns a [`MainThreadSender`], which other threads can use to schedule closures to be
+    /// called on the Lisp thread. This starts the timer that calls them, if it is not running.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result};
+    /// fn fetch_in_background(env: &Env, url: String) -> Result<()> {
+    ///     let sender = env.main_thread_sender()?;
+    ///     std::thread::spawn(move || {
+    ///         let body = format!("Fetched {}", url); // Some slow I/O.
+    ///         sender.send(move |env| {
+    ///             env.message(&body)?;
+    ///             Ok(())
+    ///         });
+    ///     });
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`MainThreadSender`]: struct.MainThreadSender.html
+    pub fn main_thread_sender(&self) -> Result<MainThreadSender> {
+        let mut poller = POLLER.lock().expect("Poller should not be poisoned");
+        if poller.is_none() {
+            let drain = match DRAIN.get() {
+                Some(drain) => drain,
+                None => {
+                    let drain = self.make_closure(0..0, "", |env| {
+                        drain_queue(env)?;
+                        ().into_lisp(env)
+                    })?;
+                    DRAIN.get_or_init(|| drain.make_global_ref())
+                }
+            };
+            let timer = self.call("run-with-timer", (0, POLL_INTERVAL, drain))?;
+            *poller = Some(Timer::new(timer));
+        }
+        Ok(MainThreadSender { queue: QUEUE.clone() })
+    }
+}
+
+/// Calls the queued jobs, then stops the polling timer if no more jobs can arrive.
+fn drain_queue(env: &Env) -> Result<()> {
+    loop {
+        // Don't hold the lock while running the job, which may send more jobs.
+        let job = QUEUE.lock().expect("Job queue should not be poisoned").pop_front();
+        match job {
+            Some(job) => job(env)?,
+            None => break,
+        }
+    }
+    // If only the static holds the queue, there is no sender left, and new ones can only be created
+    // on this thread, which restarts the timer. A sender may have sent a job just before being
+    // dropped, though, so the queue must also be empty.
+    let queue = QUEUE.lock().expect("Job queue should not be poisoned");
+    if Arc::strong_count(&QUEUE) == 1 && queue.is_empty() {
+        drop(queue);
+        if let Some(timer) = POLLER.lock().expect("Poller should not be poisoned").take() {
+            timer.cancel(env)?;
+        }
+    }
+    Ok(())
+}
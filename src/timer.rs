@@ -0,0 +1,71 @@
+//! Running Rust closures from Lisp timers.
+
+use crate::{Env, GlobalRef, IntoLisp, Result, Value};
+
+/// A Lisp [timer] that calls a Rust closure. It can be used to cancel the timer later.
+///
+/// Like [`GlobalRef`], this should be disposed of with [`cancel`], or [`free`], instead of [`drop`],
+/// to avoid leaking the underlying Lisp object.
+///
+/// [timer]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Timers.html
+/// [`GlobalRef`]: struct.GlobalRef.html
+/// [`cancel`]: #method.cancel
+/// [`free`]: #method.free
+/// [`drop`]: https://doc.rust-lang.org/std/mem/fn.drop.html
+#[derive(Debug)]
+pub struct Timer {
+    timer: GlobalRef,
+}
+
+impl Timer {
+    pub(crate) fn new(timer: Value<'_>) -> Self {
+        Timer { timer: timer.make_global_ref() }
+    }
+
+    /// Returns the underlying Lisp timer object, scoping its lifetime to the given [`Env`].
+    ///
+    /// [`Env`]: struct.Env.html
+    #[inline]
+    pub fn bind<'e, 'g: 'e>(&'g self, env: &'e Env) -> Value<'e> {
+        self.timer.bind(env)
+    }
+
+    /// Cancels this timer, so that its closure is no longer called. This is the equivalent of the
+    /// Lisp function [`cancel-timer`].
+    ///
+    /// [`cancel-timer`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Timers.html
+    pub fn cancel(self, env: &Env) -> Result<()> {
+        env.call("cancel-timer", [self.bind(env)])?;
+        self.free(env)
+    }
+
+    /// Frees the reference to the underlying Lisp timer object, without cancelling it.
+    pub fn free(self, env: &Env) -> Result<()> {
+        self.timer.free(env)
+    }
+}
+
+impl Env {
+    /// Calls the given closure the next time Emacs has been idle for `secs` seconds. If `repeat` is
+    /// true, the closure is called again each time Emacs becomes idle for that long. This is the
+    /// equivalent of the Lisp function [`run-with-idle-timer`].
+    ///
+    /// Errors returned by the closure are signaled in Lisp, which Emacs reports as timer errors.
+    ///
+    /// The closure is turned into a Lisp function by [`make_closure`], so it is dropped once the
+    /// timer has been cancelled, or has run for the last time, and is garbage-collected.
+    ///
+    /// [`run-with-idle-timer`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Idle-Timers.html
+    /// [`make_closure`]: #method.make_closure
+    pub fn run_with_idle_timer<F>(&self, secs: f64, repeat: bool, f: F) -> Result<Timer>
+    where
+        F: Fn(&Env) -> Result<()> + 'static,
+    {
+        let function = self.make_closure(0..0, "", move |env| {
+            f(env)?;
+            ().into_lisp(env)
+        })?;
+        let timer = self.call("run-with-idle-timer", (secs, repeat, function))?;
+        Ok(Timer::new(timer))
+    }
+}
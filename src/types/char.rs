@@ -0,0 +1,39 @@
+//! Conversions between Rust `char`s and Lisp characters.
+//!
+//! Lisp characters are integers from 0 to `#x3FFFFF`. Those up to `#x10FFFF` are Unicode code
+//! points, while those from `#x3FFF80` are raw bytes, which appear in multibyte text that could not
+//! be decoded. Only characters that are Unicode scalar values can be converted into `char`.
+
+use std::convert::TryFrom;
+
+use super::*;
+
+/// The largest Lisp character, i.e. the value of `(max-char)`.
+const MAX_CHAR: i64 = 0x3F_FFFF;
+
+/// Converts a Lisp character into a `char`.
+///
+/// A `wrong-type-argument` error is signaled if the value is not a character, i.e. not an integer
+/// between 0 and `#x3FFFFF`. A `rust-error` is signaled if it is a character that is not a Unicode
+/// scalar value, i.e. a surrogate code point, or a raw byte. To handle raw bytes, convert the value
+/// into `u32` instead, then use the Lisp function `multibyte-char-to-unibyte` to get the byte.
+impl FromLisp<'_> for char {
+    fn from_lisp(value: Value<'_>) -> Result<Self> {
+        let env = value.env;
+        let i: i64 = value.into_rust()?;
+        if !(0..=MAX_CHAR).contains(&i) {
+            return env.signal(symbol::wrong_type_argument, (env.intern("characterp")?, value));
+        }
+        match char::try_from(i as u32) {
+            Ok(c) => Ok(c),
+            Err(_) => env.signal(symbol::rust_error, ("Not a Unicode scalar value", value)),
+        }
+    }
+}
+
+/// Converts a `char` into a Lisp character, i.e. the integer of its code point.
+impl IntoLisp<'_> for char {
+    fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
+        (self as u32 as i64).into_lisp(env)
+    }
+}
@@ -11,3 +11,22 @@ impl IntoLisp<'_> for f64 {
         unsafe_raw_call_value_unprotected!(env, make_float, self)
     }
 }
+
+/// Converts a Lisp float, which is always 64-bit, into the nearest `f32`, as with `as f32`.
+///
+/// This never fails because of narrowing: precision may be lost, and values outside the range of
+/// `f32` become infinite. Like for `f64`, a `wrong-type-argument` error is signaled if the value is
+/// not a float, including integers.
+impl FromLisp<'_> for f32 {
+    fn from_lisp(value: Value<'_>) -> Result<Self> {
+        Ok(f64::from_lisp(value)? as f32)
+    }
+}
+
+/// Converts an `f32` into a Lisp float. This is lossless, so converting the result back gives the
+/// same `f32`.
+impl IntoLisp<'_> for f32 {
+    fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
+        f64::from(self).into_lisp(env)
+    }
+}
@@ -0,0 +1,69 @@
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+};
+
+use super::*;
+
+crate::use_symbols! {
+    equal hash_table_p
+    test => ":test"
+    size => ":size"
+}
+
+/// Converts a Lisp hash table into a `HashMap`, converting each key and value with [`FromLisp`].
+///
+/// A `wrong-type-argument` error is signaled if the value is not a hash table.
+///
+/// Since entries are compared by Rust's [`Eq`] instead of the table's `:test`, distinct keys of an
+/// `eq` or `eql` table that convert to equal Rust values (e.g. two different strings with the same
+/// contents) are merged, and only one of them is kept. Tables using `equal` convert losslessly.
+///
+/// [`FromLisp`]: trait.FromLisp.html
+/// [`Eq`]: https://doc.rust-lang.org/std/cmp/trait.Eq.html
+impl<'e, K, V, S> FromLisp<'e> for HashMap<K, V, S>
+where
+    K: FromLisp<'e> + Eq + Hash,
+    V: FromLisp<'e>,
+    S: BuildHasher + Default,
+{
+    fn from_lisp(value: Value<'e>) -> Result<Self> {
+        let env = value.env;
+        if !env.call(hash_table_p, [value])?.is_not_nil() {
+            return env.signal(symbol::wrong_type_argument, (hash_table_p, value));
+        }
+        let capacity = env.call("hash-table-count", [value])?.into_rust()?;
+        let mut map = HashMap::with_capacity_and_hasher(capacity, S::default());
+        // `hash-table-keys` is defined in `subr-x`.
+        env.call("require", [env.intern("subr-x")?])?;
+        let keys = env.call("hash-table-keys", [value])?;
+        for key in env.list_iter(keys)? {
+            let key = key?;
+            let val = env.call("gethash", (key, value))?;
+            map.insert(key.into_rust()?, val.into_rust()?);
+        }
+        Ok(map)
+    }
+}
+
+/// Converts a `HashMap` into a Lisp hash table that uses `equal` to compare keys, converting each
+/// key and value with [`IntoLisp`].
+///
+/// Keys that are strings, numbers, or lists and vectors of those are compared by contents, like
+/// Rust does, so they round-trip safely through [`FromLisp`].
+///
+/// [`IntoLisp`]: trait.IntoLisp.html
+/// [`FromLisp`]: trait.FromLisp.html
+impl<'e, K, V, S> IntoLisp<'e> for HashMap<K, V, S>
+where
+    K: IntoLisp<'e>,
+    V: IntoLisp<'e>,
+{
+    fn into_lisp(self, env: &'e Env) -> Result<Value<'e>> {
+        let table = env.call("make-hash-table", (test, equal, size, self.len()))?;
+        for (key, val) in self {
+            env.call("puthash", (key.into_lisp(env)?, val.into_lisp(env)?, table))?;
+        }
+        Ok(table)
+    }
+}
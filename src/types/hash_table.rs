@@ -0,0 +1,171 @@
+use super::*;
+use crate::OnceGlobalRef;
+
+crate::use_symbols! {
+    eq eql equal hash_table_p
+    kw_test => ":test"
+}
+
+crate::global_refs! {__emrs_init_hash_table_sentinel__(init_to_uninterned_symbol) =>
+    missing => "emacs-module-rs--missing"
+}
+
+/// The function used by a Lisp hash table to compare keys. See [`make-hash-table`].
+///
+/// [`make-hash-table`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Creating-Hash.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashTableTest {
+    Eq,
+    Eql,
+    Equal,
+}
+
+impl HashTableTest {
+    /// Returns the Lisp symbol naming the comparison function.
+    pub(crate) fn symbol(self) -> &'static OnceGlobalRef {
+        match self {
+            HashTableTest::Eq => eq,
+            HashTableTest::Eql => eql,
+            HashTableTest::Equal => equal,
+        }
+    }
+}
+
+/// A type that represents Lisp hash tables. This is a wrapper around [`Value`] that provides
+/// hash-table-specific methods.
+///
+/// Arguments to #[[`defun`]] having this type will be type-checked. If you want to omit, or delay
+/// this type checking, use [`Value`] instead.
+///
+/// ```
+/// use emacs::{defun, HashTable, Result};
+///
+/// #[defun]
+/// fn count_or_zero(table: HashTable, key: String) -> Result<i64> {
+///     match table.get(key)? {
+///         Some(count) => count.into_rust(),
+///         None => Ok(0),
+///     }
+/// }
+/// ```
+///
+/// [`Value`]: struct.Value.html
+/// [`defun`]: attr.defun.html
+#[derive(Debug, Clone, Copy)]
+pub struct HashTable<'e> {
+    value: Value<'e>,
+}
+
+impl<'e> HashTable<'e> {
+    /// Returns the value associated with `key`, or `None` if there is no such entry. Unlike the Lisp
+    /// function [`gethash`], this distinguishes a missing entry from an entry whose value is nil.
+    ///
+    /// [`gethash`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Hash-Access.html
+    pub fn get<K: IntoLisp<'e>>(&self, key: K) -> Result<Option<Value<'e>>> {
+        let env = self.value.env;
+        let val = env.call("gethash", (key, self.value, missing))?;
+        if val.eq(missing.bind(env)) {
+            Ok(None)
+        } else {
+            Ok(Some(val))
+        }
+    }
+
+    /// Associates `key` with `value`, replacing any existing entry. This is the equivalent of the
+    /// Lisp function [`puthash`].
+    ///
+    /// [`puthash`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Hash-Access.html
+    pub fn insert<K: IntoLisp<'e>, V: IntoLisp<'e>>(&self, key: K, value: V) -> Result<()> {
+        self.value.env.call("puthash", (key, value, self.value))?;
+        Ok(())
+    }
+
+    /// Removes the entry for `key`, if any. This is the equivalent of the Lisp function
+    /// [`remhash`].
+    ///
+    /// [`remhash`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Hash-Access.html
+    pub fn remove<K: IntoLisp<'e>>(&self, key: K) -> Result<()> {
+        self.value.env.call("remhash", (key, self.value))?;
+        Ok(())
+    }
+
+    /// Returns the number of entries. This is the equivalent of the Lisp function
+    /// [`hash-table-count`].
+    ///
+    /// [`hash-table-count`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Other-Hash.html
+    pub fn len(&self) -> Result<usize> {
+        self.value.env.call("hash-table-count", [self.value])?.into_rust()
+    }
+
+    /// Returns whether the table has no entries.
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Returns an iterator over the `(key, value)` pairs of the table.
+    ///
+    /// The entries are collected when this is called, so changes made to the table afterward are
+    /// not reflected by the iterator.
+    pub fn iter(&self) -> Result<Iter<'e>> {
+        let env = self.value.env;
+        // `hash-table-keys` and `hash-table-values` are defined in `subr-x`. Both go through
+        // `maphash`, so they return entries in the same order.
+        env.call("require", [env.intern("subr-x")?])?;
+        let keys = env.call("hash-table-keys", [self.value])?;
+        let values = env.call("hash-table-values", [self.value])?;
+        Ok(Iter { keys: env.list_iter(keys)?, values: env.list_iter(values)? })
+    }
+
+    #[inline]
+    pub fn value(&self) -> Value<'e> {
+        self.value
+    }
+}
+
+impl<'e> FromLisp<'e> for HashTable<'e> {
+    fn from_lisp(value: Value<'e>) -> Result<HashTable<'e>> {
+        let env = value.env;
+        if !env.call(hash_table_p, [value])?.is_not_nil() {
+            return env.signal(symbol::wrong_type_argument, (hash_table_p, value));
+        }
+        Ok(HashTable { value })
+    }
+}
+
+impl<'e> IntoLisp<'e> for HashTable<'e> {
+    #[inline(always)]
+    fn into_lisp(self, _: &'e Env) -> Result<Value<'e>> {
+        Ok(self.value)
+    }
+}
+
+/// An iterator over the entries of a [`HashTable`], as `(key, value)` pairs of [`Value`] structs.
+///
+/// [`HashTable`]: struct.HashTable.html
+/// [`Value`]: struct.Value.html
+pub struct Iter<'e> {
+    keys: ListIter<'e>,
+    values: ListIter<'e>,
+}
+
+impl<'e> Iterator for Iter<'e> {
+    type Item = Result<(Value<'e>, Value<'e>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.keys.next()?, self.values.next()?) {
+            (Ok(key), Ok(value)) => Some(Ok((key, value))),
+            (Err(error), _) | (_, Err(error)) => Some(Err(error)),
+        }
+    }
+}
+
+impl Env {
+    /// Creates a new, empty Lisp hash table that compares keys with `test`. This is the
+    /// equivalent of the Lisp function [`make-hash-table`].
+    ///
+    /// [`make-hash-table`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Creating-Hash.html
+    pub fn make_hash_table(&self, test: HashTableTest) -> Result<HashTable<'_>> {
+        let value = self.call("make-hash-table", (kw_test, test.symbol()))?;
+        Ok(HashTable { value })
+    }
+}
@@ -1,4 +1,4 @@
-use std::convert::TryInto;
+use std::{convert::TryInto, fmt::Display};
 
 use super::*;
 
@@ -8,13 +8,30 @@ impl FromLisp<'_> for i64 {
     }
 }
 
+/// Signals `args-out-of-range` with the data `(VALUE MIN MAX)`.
+fn out_of_range<T, B: TryInto<i64> + Display>(value: Value<'_>, min: B, max: B) -> Result<T> {
+    let env = value.env;
+    let bound = |b: B| {
+        let s = b.to_string();
+        match b.try_into() {
+            Ok(b) => i64::into_lisp(b, env),
+            // Becomes a bignum in Emacs 27+, a float otherwise.
+            Err(_) => env.call("string-to-number", (s,)),
+        }
+    };
+    env.signal(symbol::args_out_of_range, (value, bound(min)?, bound(max)?))
+}
+
 macro_rules! int_from_lisp {
     ($name:ident) => {
         impl FromLisp<'_> for $name {
             #[cfg(not(feature = "lossy-integer-conversion"))]
             fn from_lisp(value: Value<'_>) -> Result<$name> {
                 let i: i64 = value.into_rust()?;
-                Ok(i.try_into()?)
+                match i.try_into() {
+                    Ok(i) => Ok(i),
+                    Err(_) => out_of_range(value, $name::MIN, $name::MAX),
+                }
             }
 
             #[cfg(feature = "lossy-integer-conversion")]
@@ -83,3 +100,59 @@ int_into_lisp!(isize, lossless);
 int_into_lisp!(u64, lossless);
 #[cfg(not(feature = "lossy-integer-conversion"))]
 int_into_lisp!(usize, lossless);
+
+/// Returns whether `value` is a bignum, i.e. an integer that doesn't fit in a fixnum. Bignums were
+/// added in Emacs 27. Before that, integer overflow wrapped around silently.
+fn is_bignum(value: Value<'_>) -> Result<bool> {
+    let env = value.env;
+    Ok(env.emacs_version_at_least(27, 1)? && env.call("bignump", [value])?.is_not_nil())
+}
+
+macro_rules! big_int_from_lisp {
+    ($name:ident) => {
+        /// Converts a Lisp integer, including a bignum, into a Rust integer. An
+        /// `args-out-of-range` error is signaled if it doesn't fit.
+        impl FromLisp<'_> for $name {
+            fn from_lisp(value: Value<'_>) -> Result<$name> {
+                let out_of_range = || out_of_range(value, $name::MIN, $name::MAX);
+                if is_bignum(value)? {
+                    let s: String = value.env.call("number-to-string", [value])?.into_rust()?;
+                    match s.parse() {
+                        Ok(i) => Ok(i),
+                        Err(_) => out_of_range(),
+                    }
+                } else {
+                    match i64::from_lisp(value)?.try_into() {
+                        Ok(i) => Ok(i),
+                        Err(_) => out_of_range(),
+                    }
+                }
+            }
+        }
+    };
+}
+
+big_int_from_lisp!(i128);
+big_int_from_lisp!(u128);
+
+macro_rules! big_int_into_lisp {
+    ($name:ident) => {
+        /// Converts a Rust integer into a Lisp integer, which is a bignum if it doesn't fit in a
+        /// fixnum. Before Emacs 27, which doesn't support bignums, an "out of range" `rust-error`
+        /// is signaled if the integer doesn't fit in `i64`.
+        impl IntoLisp<'_> for $name {
+            fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
+                match self.try_into() {
+                    Ok(i) => i64::into_lisp(i, env),
+                    Err(_) if env.emacs_version_at_least(27, 1)? => {
+                        env.call("string-to-number", (self.to_string(),))
+                    }
+                    Err(error) => Err(error.into()),
+                }
+            }
+        }
+    };
+}
+
+big_int_into_lisp!(i128);
+big_int_into_lisp!(u128);
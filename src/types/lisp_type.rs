@@ -0,0 +1,134 @@
+use crate::{global::OnceGlobalRef, subr, Env, Result, Value};
+
+// Symbols returned by `type-of`.
+crate::use_symbols! {
+    symbol integer float string cons vector hash_table user_ptr
+    subr module_function compiled_function byte_code_function interpreted_function
+    primitive_function special_form subr_native_elisp native_comp_function
+    buffer
+}
+
+/// Types of Lisp values, as determined by [`Env::lisp_type_of`].
+///
+/// This enum is non-exhaustive, since more variants may be recognized in the future. Matching
+/// against it must therefore include a catch-all arm.
+///
+/// [`Env::lisp_type_of`]: struct.Env.html#method.lisp_type_of
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy)]
+pub enum LispType<'e> {
+    Symbol,
+    /// Either a fixnum or a bignum.
+    Integer,
+    Float,
+    String,
+    Cons,
+    Vector,
+    HashTable,
+    UserPtr,
+    /// A built-in, module, byte-compiled, or natively-compiled function. Interpreted lambdas are
+    /// also included in Emacs 30+. In older versions, they are lists, and thus [`Cons`].
+    ///
+    /// [`Cons`]: #variant.Cons
+    Function,
+    /// A value of another type, e.g. a buffer, or a marker. This carries the original value, whose
+    /// type can be inspected further with [`Env::type_of`].
+    ///
+    /// [`Env::type_of`]: struct.Env.html#method.type_of
+    Other(Value<'e>),
+}
+
+impl Env {
+    /// Returns the type of the given value. Unlike [`type_of`], this returns a Rust enum instead of
+    /// a Lisp symbol. The symbols for common types are cached, so this is cheap.
+    ///
+    /// [`type_of`]: #method.type_of
+    pub fn lisp_type_of<'e>(&'e self, value: Value<'e>) -> Result<LispType<'e>> {
+        let kind = self.type_of(value)?;
+        let is = |t: &OnceGlobalRef| kind.eq(t.bind(self));
+        let lisp_type = if is(symbol) {
+            LispType::Symbol
+        } else if is(integer) {
+            LispType::Integer
+        } else if is(float) {
+            LispType::Float
+        } else if is(string) {
+            LispType::String
+        } else if is(cons) {
+            LispType::Cons
+        } else if is(vector) {
+            LispType::Vector
+        } else if is(hash_table) {
+            LispType::HashTable
+        } else if is(user_ptr) {
+            LispType::UserPtr
+        } else if [
+            // Symbols returned by `type-of` for functions, across Emacs versions.
+            subr, module_function, compiled_function, byte_code_function, interpreted_function,
+            primitive_function, special_form, subr_native_elisp, native_comp_function,
+        ].iter().any(|t| is(t)) {
+            LispType::Function
+        } else {
+            LispType::Other(value)
+        };
+        Ok(lisp_type)
+    }
+}
+
+impl<'e> Value<'e> {
+    // Errors are treated as a mismatch, so that the predicates below never signal.
+    fn has_type(&self, t: &OnceGlobalRef) -> bool {
+        let env = self.env;
+        matches!(env.type_of(*self), Ok(kind) if kind.eq(t.bind(env)))
+    }
+
+    /// Returns `true` if this value is a string. This is the equivalent of the Lisp function
+    /// `stringp`.
+    pub fn is_string(&self) -> bool {
+        self.has_type(string)
+    }
+
+    /// Returns `true` if this value is an integer, either a fixnum or a bignum. This is the
+    /// equivalent of the Lisp function `integerp`.
+    pub fn is_integer(&self) -> bool {
+        self.has_type(integer)
+    }
+
+    /// Returns `true` if this value is a floating-point number. This is the equivalent of the Lisp
+    /// function `floatp`.
+    pub fn is_float(&self) -> bool {
+        self.has_type(float)
+    }
+
+    /// Returns `true` if this value is a cons cell. This is the equivalent of the Lisp function
+    /// `consp`. Note that `nil` is not a cons cell.
+    pub fn is_cons(&self) -> bool {
+        self.has_type(cons)
+    }
+
+    /// Returns `true` if this value is a vector. This is the equivalent of the Lisp function
+    /// `vectorp`. Note that records, char-tables, and byte-code objects are not vectors.
+    pub fn is_vector(&self) -> bool {
+        self.has_type(vector)
+    }
+
+    /// Returns `true` if this value is a buffer, live or killed. This is the equivalent of the
+    /// Lisp function `bufferp`.
+    pub fn is_buffer(&self) -> bool {
+        self.has_type(buffer)
+    }
+
+    /// Returns `true` if this value can be called as a function. This is the equivalent of the
+    /// Lisp function [`functionp`], so it includes lambda lists, and symbols with a function
+    /// definition. Unlike [`LispType::Function`], it excludes special forms and macros.
+    ///
+    /// Unlike the other type predicates, this calls Lisp code, which may signal, e.g. when the
+    /// user quits.
+    ///
+    /// [`functionp`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/What-Is-a-Function.html#index-functionp
+    /// [`LispType::Function`]: enum.LispType.html#variant.Function
+    pub fn is_function(&self) -> Result<bool> {
+        let env = self.env;
+        Ok(env.call(subr::functionp, (*self,))?.is_not_nil())
+    }
+}
@@ -0,0 +1,87 @@
+use super::*;
+use crate::subr;
+
+/// An iterator over the elements of a Lisp list, as [`Value`] structs. It is created by
+/// [`Env::list_iter`].
+///
+/// Each element is obtained by taking the `car` of the remaining list, which is then advanced with
+/// `cdr`. Iteration stops at `nil`. If an improper tail is encountered, a `wrong-type-argument`
+/// error is yielded once, after which the iterator is exhausted. Circular lists are not detected,
+/// so iterating over one never ends, unless it is stopped early.
+///
+/// [`Value`]: struct.Value.html
+/// [`Env::list_iter`]: struct.Env.html#method.list_iter
+#[derive(Debug, Clone)]
+pub struct ListIter<'e> {
+    tail: Option<Value<'e>>,
+}
+
+impl<'e> Iterator for ListIter<'e> {
+    type Item = Result<Value<'e>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let tail = self.tail.take()?;
+        if !tail.is_not_nil() {
+            return None;
+        }
+        // `car` signals `wrong-type-argument` if `tail` is not a cons.
+        let item = tail.car().and_then(|car| {
+            self.tail = Some(tail.cdr()?);
+            Ok(car)
+        });
+        Some(item)
+    }
+}
+
+impl Env {
+    /// Returns an iterator over the elements of `list`, without copying them into a `Vec` first.
+    ///
+    /// A `wrong-type-argument` error is signaled if `list` is not a list. See [`ListIter`] for how
+    /// improper lists are handled.
+    ///
+    /// [`ListIter`]: struct.ListIter.html
+    pub fn list_iter<'e>(&'e self, list: Value<'e>) -> Result<ListIter<'e>> {
+        if !self.call(subr::listp, [list])?.is_not_nil() {
+            let listp = self.intern("listp")?;
+            return self.signal(symbol::wrong_type_argument, (listp, list));
+        }
+        Ok(ListIter { tail: Some(list) })
+    }
+}
+
+/// Converts a proper Lisp list into a `Vec`, converting each element with [`FromLisp`].
+///
+/// A `wrong-type-argument` error is signaled if the value is not a list, or if it is an improper
+/// list, i.e. its last `cdr` is not `nil`. A `circular-list` error is signaled if it is circular.
+///
+/// A unibyte string is converted as a list of its bytes, so that `Vec<u8>` round-trips through its
+/// [`IntoLisp`] conversion, which creates a unibyte string.
+///
+/// [`FromLisp`]: trait.FromLisp.html
+/// [`IntoLisp`]: trait.IntoLisp.html
+impl<'e, T: FromLisp<'e>> FromLisp<'e> for Vec<T> {
+    fn from_lisp(value: Value<'e>) -> Result<Self> {
+        let env = value.env;
+        if value.is_string() && !env.call("multibyte-string-p", [value])?.is_not_nil() {
+            let bytes = env.string_raw_bytes(value)?;
+            return bytes
+                .into_iter()
+                .map(|byte| i64::from(byte).into_lisp(env)?.into_rust())
+                .collect();
+        }
+        // Unlike `length`, `safe-length` doesn't signal on improper or circular lists. It counts
+        // the distinct conses, so a proper list ends right after that many elements.
+        let capacity = env.call("safe-length", [value])?.into_rust()?;
+        let mut vec = Vec::with_capacity(capacity);
+        let mut items = env.list_iter(value)?;
+        for item in items.by_ref().take(capacity) {
+            vec.push(item?.into_rust()?);
+        }
+        // An improper tail yields an error. Another element means the list loops back on itself.
+        if let Some(item) = items.next() {
+            item?;
+            return env.signal(symbol::circular_list, [value]);
+        }
+        Ok(vec)
+    }
+}
@@ -1,13 +1,28 @@
 use crate::{symbol, Env, Value, Result};
 
-pub use {user_ptr::Transfer, vector::Vector};
+pub use {
+    user_ptr::Transfer,
+    vector::{Vector, VecIter},
+    list::ListIter,
+    seq::SeqIter,
+    hash_table::{HashTable, HashTableTest},
+    lisp_type::LispType,
+};
 
 mod integer;
 mod float;
 mod string;
+mod list;
+mod seq;
+mod hash_map;
+mod hash_table;
+mod time;
+mod path;
+mod char;
 
 mod user_ptr;
 mod vector;
+mod lisp_type;
 
 // XXX: More accurate would be `CloneFromLisp` or `Decode`, but ...
 /// Converting Lisp [`Value`] into a Rust type.
@@ -49,6 +64,10 @@ impl<'e> IntoLisp<'e> for Value<'e> {
     }
 }
 
+/// Converts `nil` into `None`, and any other value into `Some`, using `T`'s conversion.
+///
+/// Since `nil` is always `None`, `T`'s own conversion never sees `nil`. For example, an empty Lisp
+/// list becomes `None` as an `Option<Vec<T>>`, not `Some(vec![])`.
 impl<'e, T: FromLisp<'e>> FromLisp<'e> for Option<T> {
     fn from_lisp(value: Value<'e>) -> Result<Self> {
         if value.is_not_nil() {
@@ -59,6 +78,10 @@ impl<'e, T: FromLisp<'e>> FromLisp<'e> for Option<T> {
     }
 }
 
+/// Converts `None` into `nil`, and `Some(x)` into the conversion of `x`.
+///
+/// Note that the conversion is not reversible if `x` itself becomes `nil`. For example, both
+/// `Some(false)` and `Some(())` become `nil`, which is converted back into `None`.
 impl<'e, T: IntoLisp<'e>> IntoLisp<'e> for Option<T> {
     fn into_lisp(self, env: &'e Env) -> Result<Value<'_>> {
         match self {
@@ -74,6 +97,20 @@ impl IntoLisp<'_> for () {
     }
 }
 
+// Tuples are converted into Lisp lists, e.g. `(1, "a")` becomes `(1 "a")`.
+emacs_macros::impl_into_lisp_for_tuples!(12);
+
+/// Converts `nil` into `false`, and any other value into `true`, following Lisp's notion of truth.
+///
+/// Note that this never fails: every non-nil value is `true`, including `0`, `""`, and `[]`.
+impl FromLisp<'_> for bool {
+    #[inline(always)]
+    fn from_lisp(value: Value<'_>) -> Result<bool> {
+        Ok(value.is_not_nil())
+    }
+}
+
+/// Converts `true` into `t`, and `false` into `nil`.
 impl IntoLisp<'_> for bool {
     fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
         if self {
@@ -0,0 +1,88 @@
+//! Conversions between Rust paths and Lisp file names.
+//!
+//! On Unix, file names are encoded and decoded with the coding system Emacs uses for file names,
+//! i.e. `file-name-coding-system`, or `default-file-name-coding-system`. Bytes that are invalid in
+//! that coding system are kept as raw bytes, so they survive the round trip. On other platforms,
+//! where paths are Unicode, file names are converted like other strings.
+
+use std::path::{Path, PathBuf};
+
+use super::*;
+
+#[cfg(unix)]
+use std::{ffi::OsString, os::unix::ffi::{OsStrExt, OsStringExt}};
+
+#[cfg(unix)]
+crate::use_symbols! {
+    file_name_coding_system
+    default_file_name_coding_system
+}
+
+/// Returns the coding system Emacs uses to encode and decode file names, or nil if file names are
+/// passed through unchanged.
+#[cfg(unix)]
+fn file_name_coding(env: &Env) -> Result<Value<'_>> {
+    let coding = env.call("symbol-value", (file_name_coding_system,))?;
+    if coding.is_not_nil() {
+        return Ok(coding);
+    }
+    env.call("symbol-value", (default_file_name_coding_system,))
+}
+
+#[cfg(unix)]
+impl FromLisp<'_> for PathBuf {
+    fn from_lisp(value: Value<'_>) -> Result<Self> {
+        let env = value.env;
+        let coding = file_name_coding(env)?;
+        let encoded = if coding.is_not_nil() {
+            env.call("encode-coding-string", (value, coding, true))?
+        } else {
+            value
+        };
+        Ok(PathBuf::from(OsString::from_vec(env.string_raw_bytes(encoded)?)))
+    }
+}
+
+#[cfg(unix)]
+impl IntoLisp<'_> for &Path {
+    fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
+        let bytes = env.make_unibyte_string(self.as_os_str().as_bytes())?;
+        let coding = file_name_coding(env)?;
+        if coding.is_not_nil() {
+            env.call("decode-coding-string", (bytes, coding, true))
+        } else {
+            Ok(bytes)
+        }
+    }
+}
+
+#[cfg(not(unix))]
+impl FromLisp<'_> for PathBuf {
+    fn from_lisp(value: Value<'_>) -> Result<Self> {
+        Ok(PathBuf::from(value.into_rust::<String>()?))
+    }
+}
+
+#[cfg(not(unix))]
+impl IntoLisp<'_> for &Path {
+    fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
+        match self.to_str() {
+            Some(s) => s.into_lisp(env),
+            None => env.signal(symbol::rust_error, ("Path is not valid Unicode", self.to_string_lossy().as_ref())),
+        }
+    }
+}
+
+impl IntoLisp<'_> for &PathBuf {
+    #[inline]
+    fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
+        self.as_path().into_lisp(env)
+    }
+}
+
+impl IntoLisp<'_> for PathBuf {
+    #[inline]
+    fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
+        self.as_path().into_lisp(env)
+    }
+}
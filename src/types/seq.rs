@@ -0,0 +1,65 @@
+use super::*;
+use crate::{ListIter, VecIter};
+
+crate::use_symbols! {
+    list_or_vector_p
+}
+
+/// An iterator over the elements of a Lisp list or vector, as [`Value`] structs. It is created by
+/// [`Value::iter_seq`].
+///
+/// Lists are iterated like with [`ListIter`], and vectors like with [`VecIter`].
+///
+/// [`Value`]: struct.Value.html
+/// [`Value::iter_seq`]: struct.Value.html#method.iter_seq
+/// [`ListIter`]: struct.ListIter.html
+/// [`VecIter`]: struct.VecIter.html
+#[derive(Debug, Clone)]
+pub enum SeqIter<'e> {
+    List(ListIter<'e>),
+    Vector(VecIter<'e>),
+}
+
+impl<'e> Iterator for SeqIter<'e> {
+    type Item = Result<Value<'e>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            SeqIter::List(iter) => iter.next(),
+            SeqIter::Vector(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            SeqIter::List(iter) => iter.size_hint(),
+            SeqIter::Vector(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<'e> Value<'e> {
+    /// Returns an iterator over the elements of this Lisp list or vector. This allows collecting
+    /// them into any collection, not just a `Vec`.
+    ///
+    /// Strings are not treated as sequences of characters. A `wrong-type-argument` error is
+    /// signaled for them, as for any value that is neither a list nor a vector. To iterate over
+    /// the characters of a string, convert it into a `String` first.
+    ///
+    /// ```
+    /// # use emacs::{Result, Value};
+    /// # use std::collections::BTreeSet;
+    /// fn distinct(seq: Value) -> Result<BTreeSet<i64>> {
+    ///     seq.iter_seq()?.map(|v| v?.into_rust::<i64>()).collect()
+    /// }
+    /// ```
+    pub fn iter_seq(&self) -> Result<SeqIter<'e>> {
+        if self.is_vector() {
+            return Ok(SeqIter::Vector(self.vec_iter()?));
+        }
+        if self.is_cons() || !self.is_not_nil() {
+            return Ok(SeqIter::List(self.env.list_iter(*self)?));
+        }
+        self.env.signal(symbol::wrong_type_argument, (list_or_vector_p, *self))
+    }
+}
@@ -1,7 +1,20 @@
-use std::{os, ptr, cmp};
+use std::{cell::Cell, cmp, os, ptr};
 
 use super::*;
 
+thread_local! {
+    /// The buffer reused by [`Value::with_str_bytes`], to avoid allocating one for each call.
+    ///
+    /// [`Value::with_str_bytes`]: struct.Value.html#method.with_str_bytes
+    // `const` initializers are not supported by our MSRV.
+    #[allow(clippy::missing_const_for_thread_local)]
+    static STRING_BUFFER: Cell<Vec<u8>> = Cell::new(Vec::new());
+}
+
+/// The largest buffer kept by [`STRING_BUFFER`] between calls. A larger one, needed for an
+/// unusually large string, is freed afterward, instead of being held for the thread's lifetime.
+const STRING_BUFFER_MAX_KEPT: usize = 1 << 20;
+
 impl FromLisp<'_> for String {
     #[cfg(not(feature = "utf-8-validation"))]
     fn from_lisp(value: Value<'_>) -> Result<Self> {
@@ -42,7 +55,95 @@ impl IntoLisp<'_> for String {
     }
 }
 
+/// Converts bytes into a unibyte Lisp string holding exactly these bytes, without assuming any
+/// encoding.
+///
+/// The reverse conversion is done by [`FromLisp`] for `Vec<u8>`, which accepts a unibyte string, as
+/// well as a Lisp list of integers. To also get the bytes of an ASCII multibyte string, use
+/// [`Value::as_bytes`].
+///
+/// [`Value::as_bytes`]: struct.Value.html#method.as_bytes
+/// [`FromLisp`]: trait.FromLisp.html
+impl IntoLisp<'_> for &[u8] {
+    fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
+        env.make_unibyte_string(self)
+    }
+}
+
+impl IntoLisp<'_> for Vec<u8> {
+    fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
+        self.as_slice().into_lisp(env)
+    }
+}
+
 impl<'e> Value<'e> {
+    /// Returns the raw bytes of this Lisp string, including any embedded null bytes.
+    ///
+    /// For a unibyte string, these are exactly the bytes it holds. A multibyte string is only
+    /// accepted if it is pure ASCII. Otherwise, a `rust-error` is signaled, since its bytes depend
+    /// on the encoding. Such a string should be explicitly encoded first, e.g. with
+    /// [`Env::encode_coding_string`].
+    ///
+    /// [`Env::encode_coding_string`]: struct.Env.html#method.encode_coding_string
+    pub fn as_bytes(&self) -> Result<Vec<u8>> {
+        let env = self.env;
+        let bytes = env.string_raw_bytes(*self)?;
+        if !bytes.is_ascii() && env.call("multibyte-string-p", [*self])?.is_not_nil() {
+            return env.signal(symbol::rust_error, (
+                "Cannot get the bytes of a non-ASCII multibyte string without encoding it",
+                *self,
+            ));
+        }
+        Ok(bytes)
+    }
+
+    /// Calls `f` with the UTF-8 bytes of this Lisp string, i.e. the same bytes as those of the
+    /// `String` it converts into, then returns the result. A `wrong-type-argument` error is
+    /// signaled if this value is not a string.
+    ///
+    /// Unlike converting into `String`, this avoids allocating a new buffer for each call, by
+    /// reusing one buffer per thread. This is useful for parsing large strings, e.g. buffer
+    /// contents, without keeping an owned copy. Buffers larger than 1 MiB are not kept, so a
+    /// single huge string does not hold on to its memory afterward.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result, Value};
+    /// fn count_lines(env: &Env) -> Result<usize> {
+    ///     let text = env.call("buffer-string", [])?;
+    ///     text.with_str_bytes(|bytes| bytes.iter().filter(|&&b| b == b'\n').count())
+    /// }
+    /// ```
+    pub fn with_str_bytes<R, F: FnOnce(&[u8]) -> R>(&self, f: F) -> Result<R> {
+        // Take the buffer out, instead of borrowing it, so that `f` can call this again.
+        let mut buffer = STRING_BUFFER.with(Cell::take);
+        let result = self.env.copy_string_into(*self, &mut buffer).map(|_| {
+            // Like the conversion into `String`.
+            let len = buffer.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+            f(&buffer[..len])
+        });
+        if buffer.capacity() <= STRING_BUFFER_MAX_KEPT {
+            STRING_BUFFER.with(|b| b.set(buffer));
+        }
+        result
+    }
+
+    /// Like [`with_str_bytes`], but passes the content of this Lisp string as a `&str`.
+    ///
+    /// [`with_str_bytes`]: #method.with_str_bytes
+    #[cfg(not(feature = "utf-8-validation"))]
+    pub fn with_str<R, F: FnOnce(&str) -> R>(&self, f: F) -> Result<R> {
+        // Safety: We trust Emacs to give us valid utf-8 bytes.
+        self.with_str_bytes(|bytes| f(unsafe { std::str::from_utf8_unchecked(bytes) }))
+    }
+
+    /// Like [`with_str_bytes`], but passes the content of this Lisp string as a `&str`.
+    ///
+    /// [`with_str_bytes`]: #method.with_str_bytes
+    #[cfg(feature = "utf-8-validation")]
+    pub fn with_str<R, F: FnOnce(&str) -> R>(&self, f: F) -> Result<R> {
+        self.with_str_bytes(|bytes| f(std::str::from_utf8(bytes).unwrap()))
+    }
+
     /// Copies the content of this Lisp string value to the given buffer as a null-terminated UTF-8
     /// string. Returns the copied bytes, excluding the null terminator.
     ///
@@ -66,8 +167,27 @@ impl<'e> Value<'e> {
 
 impl Env {
     fn string_bytes(&self, value: Value<'_>) -> Result<Vec<u8>> {
+        let mut bytes = self.string_raw_bytes(value)?;
+        strip_trailing_zero_bytes(&mut bytes);
+        Ok(bytes)
+    }
+
+    /// Returns the bytes of the given Lisp string, excluding the null terminator added by
+    /// `copy_string_contents`. Unlike [`string_bytes`], embedded and trailing null bytes are kept.
+    ///
+    /// [`string_bytes`]: #method.string_bytes
+    pub(crate) fn string_raw_bytes(&self, value: Value<'_>) -> Result<Vec<u8>> {
+        let mut bytes = vec![];
+        self.copy_string_into(value, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Replaces the content of `bytes` with the bytes of the given Lisp string, excluding the null
+    /// terminator added by `copy_string_contents`. The existing allocation is reused if it is
+    /// large enough.
+    fn copy_string_into(&self, value: Value<'_>, bytes: &mut Vec<u8>) -> Result<()> {
         let mut len: isize = 0;
-        let mut bytes = unsafe {
+        unsafe {
             let copy_string_contents = raw_fn!(self, copy_string_contents);
             let ok: bool = self.handle_exit(copy_string_contents(
                 self.raw,
@@ -81,7 +201,8 @@ impl Env {
                 panic!("Emacs failed to give string's length but did not raise a signal");
             }
 
-            let mut bytes = vec![0u8; len as usize];
+            bytes.clear();
+            bytes.resize(len as usize, 0);
             let ok: bool = self.handle_exit(copy_string_contents(
                 self.raw,
                 value.raw,
@@ -93,10 +214,20 @@ impl Env {
             if !ok {
                 panic!("Emacs failed to copy string but did not raise a signal");
             }
-            bytes
-        };
-        strip_trailing_zero_bytes(&mut bytes);
-        Ok(bytes)
+        }
+        // The reported length includes the null terminator.
+        bytes.truncate(cmp::max(len, 1) as usize - 1);
+        Ok(())
+    }
+
+    /// Creates a unibyte Lisp string holding exactly the given bytes.
+    ///
+    /// `make_string` expects UTF-8, so each byte is first passed as the character with the same
+    /// code point, which is then encoded back into that byte by Latin-1.
+    pub(crate) fn make_unibyte_string(&self, bytes: &[u8]) -> Result<Value<'_>> {
+        let chars: String = bytes.iter().map(|&b| b as char).collect();
+        let coding = self.intern("iso-latin-1-unix")?;
+        self.call("encode-coding-string", (chars.as_str(), coding))
     }
 }
 
@@ -0,0 +1,94 @@
+//! Conversions between Rust's time types and Lisp [time values].
+//!
+//! Rust values are converted into the `(TICKS . HZ)` form, with nanosecond resolution. Lisp values
+//! of any form accepted by [`time-convert`] can be converted back, including the old list form
+//! `(HIGH LOW USEC PSEC)`. Both `(TICKS . HZ)` and `time-convert` require Emacs 27 or later.
+//!
+//! [time values]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Time-of-Day.html
+//! [`time-convert`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Time-Conversion.html
+
+use std::{
+    convert::TryInto,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use super::*;
+
+/// The number of ticks per second used when converting time values.
+const HZ: i64 = 1_000_000_000;
+
+fn check_emacs_version(env: &Env) -> Result<()> {
+    if !env.emacs_version_at_least(27, 1)? {
+        return env.signal(symbol::rust_error, ("Converting time values requires Emacs 27 or later",));
+    }
+    Ok(())
+}
+
+/// Converts a Lisp time value into the number of nanoseconds since the epoch.
+fn ticks_from_lisp(value: Value<'_>) -> Result<i64> {
+    let env = value.env;
+    check_emacs_version(env)?;
+    env.call("time-convert", (value, HZ))?.car()
+}
+
+/// Converts a number of nanoseconds since the epoch into a Lisp time value.
+fn ticks_into_lisp(env: &Env, ticks: i128) -> Result<Value<'_>> {
+    check_emacs_version(env)?;
+    let ticks: i64 = match ticks.try_into() {
+        Ok(ticks) => ticks,
+        Err(_) => return env.signal(symbol::rust_error, ("Time value out of range",)),
+    };
+    env.cons(ticks, HZ)
+}
+
+fn duration_ticks(duration: Duration) -> i128 {
+    duration.as_secs() as i128 * HZ as i128 + duration.subsec_nanos() as i128
+}
+
+/// Converts a Lisp time value into a `Duration`, interpreting it as a length of time. A
+/// `rust-error` is signaled if the time value is negative.
+impl FromLisp<'_> for Duration {
+    fn from_lisp(value: Value<'_>) -> Result<Self> {
+        let ticks = ticks_from_lisp(value)?;
+        if ticks < 0 {
+            return value.env.signal(symbol::rust_error, ("Negative time value cannot be a Duration", value));
+        }
+        Ok(Duration::new((ticks / HZ) as u64, (ticks % HZ) as u32))
+    }
+}
+
+/// Converts a `Duration` into a Lisp time value of the form `(TICKS . HZ)`.
+impl IntoLisp<'_> for Duration {
+    fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
+        ticks_into_lisp(env, duration_ticks(self))
+    }
+}
+
+/// Converts a Lisp time value into a `SystemTime`. Note that nil is the current time.
+impl FromLisp<'_> for SystemTime {
+    fn from_lisp(value: Value<'_>) -> Result<Self> {
+        let ticks = ticks_from_lisp(value)?;
+        let secs = ticks.div_euclid(HZ);
+        let nanos = Duration::from_nanos(ticks.rem_euclid(HZ) as u64);
+        let time = if secs >= 0 {
+            UNIX_EPOCH.checked_add(Duration::from_secs(secs as u64))
+        } else {
+            UNIX_EPOCH.checked_sub(Duration::from_secs((-(secs as i128)) as u64))
+        };
+        match time.and_then(|time| time.checked_add(nanos)) {
+            Some(time) => Ok(time),
+            None => value.env.signal(symbol::rust_error, ("Time value out of range", value)),
+        }
+    }
+}
+
+/// Converts a `SystemTime` into a Lisp time value of the form `(TICKS . HZ)`.
+impl IntoLisp<'_> for SystemTime {
+    fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
+        let ticks = match self.duration_since(UNIX_EPOCH) {
+            Ok(after) => duration_ticks(after),
+            Err(before) => -duration_ticks(before.duration()),
+        };
+        ticks_into_lisp(env, ticks)
+    }
+}
@@ -1,6 +1,8 @@
 use std::{
     os,
-    any,
+    any::{self, TypeId},
+    panic,
+    ptr,
     cell::RefCell,
     rc::Rc,
     sync::{Mutex, RwLock, Arc},
@@ -52,8 +54,6 @@ pub trait Transfer: Sized + 'static {
         any::type_name::<Self>()
     }
 
-    // TODO: Consider using a wrapper struct to carry the type info, to enable better runtime
-    // reporting of type error (and to enable something like `rs-module/type-of`).
 }
 
 impl<'e, T: Transfer> FromLisp<'e> for &'e T {
@@ -62,21 +62,109 @@ impl<'e, T: Transfer> FromLisp<'e> for &'e T {
     }
 }
 
-/// Finalizes an embedded pointer. This is called by the GC when it discards a `user-ptr`.
+/// The header of a value embedded in a `user-ptr` by this crate. It comes first in [`Embedded`], so
+/// it can be read before the type of the value is known.
 ///
-/// This function also serves as a form of runtime type tag, relying on Rust's mono-morphization.
-unsafe extern "C" fn finalize<T: Transfer>(ptr: *mut os::raw::c_void) {
+/// [`Embedded`]: struct.Embedded.html
+#[repr(C)]
+struct Header {
+    /// The runtime type tag of the embedded value.
+    type_id: TypeId,
+    /// The name of the embedded value's type, as returned by [`Transfer::type_name`]. This is used
+    /// to report which type was found, when a `user-ptr` of an unexpected type is received.
+    ///
+    /// [`Transfer::type_name`]: trait.Transfer.html#method.type_name
+    type_name: &'static str,
+    /// Finalizes and drops the whole [`Embedded`] struct.
+    ///
+    /// [`Embedded`]: struct.Embedded.html
+    drop: unsafe fn(*mut Header),
+    /// Points to the embedded value.
+    value: *mut os::raw::c_void,
+}
+
+/// A value embedded in a `user-ptr` by this crate, along with its custom finalizer, if any. See
+/// [`Env::make_user_ptr_with_finalizer`].
+///
+/// [`Env::make_user_ptr_with_finalizer`]: struct.Env.html#method.make_user_ptr_with_finalizer
+#[repr(C)]
+struct Embedded<T> {
+    header: Header,
+    finalizer: Option<fn(&mut T)>,
+    value: T,
+}
+
+/// Finalizes an embedded value. This is called by the GC when it discards a `user-ptr` created by
+/// this crate.
+///
+/// Unlike a generic function, which may be instantiated more than once, this function has a single
+/// address, which identifies such `user-ptr` objects. The type of the value is then checked with
+/// the tag in its [`Header`].
+///
+/// [`Header`]: struct.Header.html
+unsafe extern "C" fn finalize(ptr: *mut os::raw::c_void) {
+    let header = ptr as *mut Header;
+    ((*header).drop)(header)
+}
+
+/// Runs the custom finalizer, if any, then drops the value. Panics are caught and reported, since
+/// unwinding into the GC is undefined behavior.
+unsafe fn drop_embedded<T: Transfer>(header: *mut Header) {
     #[cfg(build = "debug")]
-    println!("Finalizing {:#?} {}", ptr, T::type_name());
-    drop(Box::from_raw(ptr as *mut T));
+    println!("Finalizing {:#?} {}", header, T::type_name());
+    let mut boxed = Box::from_raw(header as *mut Embedded<T>);
+    if let Some(finalizer) = boxed.finalizer {
+        // Nothing is observed after a panic, except for the reported payload.
+        let finalized = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            finalizer(&mut boxed.value)
+        }));
+        if let Err(payload) = finalized {
+            report_finalizer_panic::<T>(payload);
+        }
+    }
+    if let Err(payload) = panic::catch_unwind(panic::AssertUnwindSafe(|| drop(boxed))) {
+        report_finalizer_panic::<T>(payload);
+    }
+}
+
+/// Returns whether `fin` is the finalizer of `user-ptr` objects created by this crate.
+fn is_embedded(fin: Finalizer) -> bool {
+    // Compare addresses. `finalize` is not generic, so it has a single one.
+    fin as usize == finalize as Finalizer as usize
+}
+
+type Finalizer = unsafe extern "C" fn(ptr: *mut os::raw::c_void);
+
+impl Env {
+    /// Embeds `value` in a new `user-ptr` object, whose finalizer calls `finalizer` on it, if any,
+    /// before dropping it.
+    pub(crate) fn embed<T: Transfer>(&self, value: T, finalizer: Option<fn(&mut T)>) -> Result<Value<'_>> {
+        let header = Header {
+            type_id: TypeId::of::<T>(),
+            type_name: T::type_name(),
+            drop: drop_embedded::<T>,
+            value: ptr::null_mut(),
+        };
+        let raw = Box::into_raw(Box::new(Embedded { header, finalizer, value }));
+        // Safety: `raw` comes from a live box, which nothing else references yet. The box is
+        // forgotten by `into_raw`, so it's safe for the GC to take over.
+        unsafe {
+            (*raw).header.value = &mut (*raw).value as *mut T as *mut os::raw::c_void;
+            self.make_user_ptr(Some(finalize), raw as *mut os::raw::c_void)
+        }
+    }
+}
+
+fn report_finalizer_panic<T: Transfer>(payload: Box<dyn any::Any + Send>) {
+    let message = payload.downcast_ref::<&str>().copied()
+        .or_else(|| payload.downcast_ref::<String>().map(|s| s.as_str()))
+        .unwrap_or("Box<Any>");
+    eprintln!("Panic while finalizing {}: {}", T::type_name(), message);
 }
 
 impl<T: Transfer> IntoLisp<'_> for Box<T> {
     fn into_lisp(self, env: &Env) -> Result<Value<'_>> {
-        let raw = Box::into_raw(self);
-        let ptr = raw as *mut os::raw::c_void;
-        // Safety: self is forgotten by `into_raw`, so it's safe for the GC to take over.
-        unsafe { env.make_user_ptr(Some(finalize::<T>), ptr) }
+        env.embed(*self, None)
     }
 }
 
@@ -120,15 +208,51 @@ impl Env {
     pub unsafe fn make_user_ptr(&self, fin: emacs_finalizer_function, ptr: *mut os::raw::c_void) -> Result<Value> {
         unsafe_raw_call_value!(self, make_user_ptr, fin, ptr)
     }
+
+    /// Creates and returns a `user-ptr` object that embeds `value`, like converting it with
+    /// [`IntoLisp`]. When the object is garbage-collected, `finalizer` is called on the value,
+    /// before it is dropped. This is useful for cleanup that can't be done by [`Drop`], e.g. because
+    /// it needs extra information, or its errors must be handled.
+    ///
+    /// The value can be accessed through `&T` like other embedded values.
+    ///
+    /// The finalizer runs during garbage collection, without a live [`Env`], so it cannot call
+    /// Lisp. If it panics, the panic is caught and printed to stderr, and the value is still
+    /// dropped.
+    ///
+    /// ```
+    /// # use std::{cell::RefCell, fs::File, io::{BufWriter, Write}};
+    /// # use emacs::{Env, Result, Value};
+    /// fn open_log(env: &Env, file: File) -> Result<Value<'_>> {
+    ///     env.make_user_ptr_with_finalizer(RefCell::new(BufWriter::new(file)), |writer| {
+    ///         if let Err(error) = writer.get_mut().flush() {
+    ///             eprintln!("Failed to flush log: {}", error);
+    ///         }
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// [`IntoLisp`]: trait.IntoLisp.html
+    /// [`Env`]: struct.Env.html
+    pub fn make_user_ptr_with_finalizer<T: Transfer>(
+        &self,
+        value: T,
+        finalizer: fn(&mut T),
+    ) -> Result<Value<'_>> {
+        self.embed(value, Some(finalizer))
+    }
 }
 
 impl<'e> Value<'e> {
     /// Returns the raw pointer wrapped in this `user-ptr` object.
     ///
     /// In general, prefer the `user-ptr` supported provided by the [`defun`] attr macro. Use this
-    /// function only for special `user-ptr` types, such as newtypes wrapping opaque pointers.
+    /// function only for special `user-ptr` types, such as newtypes wrapping opaque pointers. For
+    /// `user-ptr` objects created through [`IntoLisp`], the pointer is to internal type information,
+    /// not to the embedded value.
     ///
     /// [`defun`]: attr.defun.html
+    /// [`IntoLisp`]: trait.IntoLisp.html
     #[inline]
     pub fn get_user_ptr(self) -> Result<*mut os::raw::c_void> {
         unsafe_raw_call!(self.env, get_user_ptr, self.raw)
@@ -146,16 +270,17 @@ impl<'e> Value<'e> {
     }
 
     pub(crate) fn get_raw_pointer<T: Transfer>(self) -> Result<*mut T> {
-        match self.get_user_finalizer()? {
-            // TODO: Consider using dynamic dispatch for finalize, and core::any for type checking.
-            Some(fin) if fin == finalize::<T> => {
-                let ptr = self.get_user_ptr()?;
-                Ok(ptr as *mut T)
-            }
-            _ => {
-                let expected = T::type_name();
-                Err(ErrorKind::WrongTypeUserPtr { expected }.into())
-            }
+        let expected = T::type_name();
+        let header = match self.get_user_finalizer()? {
+            Some(fin) if is_embedded(fin) => self.get_user_ptr()? as *const Header,
+            _ => return Err(ErrorKind::WrongTypeUserPtr { expected, found: None }.into()),
+        };
+        // Safety: `user-ptr` objects with our finalizer point to an `Embedded`, which starts with a
+        // `Header`, and stays alive at least as long as this value.
+        let Header { type_id, type_name, value, .. } = unsafe { &*header };
+        if *type_id != TypeId::of::<T>() {
+            return Err(ErrorKind::WrongTypeUserPtr { expected, found: Some(type_name) }.into());
         }
+        Ok(*value as *mut T)
     }
 }
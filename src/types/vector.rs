@@ -132,6 +132,48 @@ impl<'e> IntoIterator for Vector<'e> {
     }
 }
 
+/// An iterator over the elements of a Lisp vector held in a [`Value`]. It is created by
+/// [`Value::vec_iter`].
+///
+/// The length is read once, when the iterator is created. Each element is then fetched by index,
+/// so an error is yielded if the vector is mutated during iteration.
+///
+/// [`Value`]: struct.Value.html
+/// [`Value::vec_iter`]: struct.Value.html#method.vec_iter
+#[derive(Debug, Clone)]
+pub struct VecIter<'e> {
+    value: Value<'e>,
+    len: usize,
+    i: usize,
+}
+
+impl<'e> VecIter<'e> {
+    pub(crate) fn new(value: Value<'e>) -> Result<Self> {
+        Ok(VecIter { value, len: value.vec_len()?, i: 0 })
+    }
+}
+
+impl<'e> Iterator for VecIter<'e> {
+    type Item = Result<Value<'e>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let i = self.i;
+        if i >= self.len {
+            None
+        } else {
+            self.i += 1;
+            Some(self.value.vec_get(i))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.i;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'e> ExactSizeIterator for VecIter<'e> {}
+
 impl Env {
     pub fn make_vector<'e, T: IntoLisp<'e>>(&'e self, length: usize, init: T) -> Result<Vector> {
         let value = self.call(subr::make_vector, (length, init))?;
@@ -1,8 +1,11 @@
-use std::cell::{RefCell, Ref, RefMut};
+use std::{
+    cell::{RefCell, Ref, RefMut},
+    convert::TryInto,
+};
 
 use emacs_module::emacs_value;
 
-use crate::{subr, Env, Result, FromLisp, Transfer};
+use crate::{subr, symbol, Env, ErrorExt, Result, FromLisp, IntoLisp, Transfer, VecIter};
 
 /// A type that represents Lisp values.
 /// Values of this type can be copied around, but are lifetime-bound to the [`Env`] they come from.
@@ -61,7 +64,22 @@ impl<'e> Value<'e> {
         unsafe_raw_call_no_exit!(env, is_not_nil, self.raw)
     }
 
-    // TODO: Decide what we want for PartialEq (==, !=): eq vs. eql vs. equal.
+    // `PartialEq` is not implemented, since none of these fits it: `eq` is not what `==` usually
+    // means, while `eql` and `equal` call into Lisp, so they can fail.
+    /// Returns whether this value and `other` are the same Lisp object. This is the equivalent of
+    /// the Lisp function [`eq`].
+    ///
+    /// Lisp has 3 main equality predicates, from the strictest to the loosest:
+    /// - `eq` compares identity. Symbols and small integers with the same name or value are `eq`,
+    ///   but strings, lists, and floats usually are not, even if they look the same.
+    /// - [`eql`] is like `eq`, but also compares numbers by type and value, e.g. `1.0` is `eql` to
+    ///   another `1.0`, but not to `1`.
+    /// - [`equal`] compares structure, e.g. strings by their contents, and lists by their elements.
+    ///   This is usually what Rust's `==` means.
+    ///
+    /// [`eq`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Equality-Predicates.html
+    /// [`eql`]: #method.eql
+    /// [`equal`]: #method.equal
     #[allow(clippy::should_implement_trait)]
     pub fn eq(&self, other: Value<'e>) -> bool {
         let env = self.env;
@@ -69,12 +87,71 @@ impl<'e> Value<'e> {
         unsafe_raw_call_no_exit!(env, eq, self.raw, other.raw)
     }
 
+    /// Returns whether this value and `other` are [`eq`], or are numbers of the same type and
+    /// value. This is the equivalent of the Lisp function [`eql`].
+    ///
+    /// [`eq`]: #method.eq
+    /// [`eql`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Equality-Predicates.html
+    pub fn eql(&self, other: Value<'e>) -> Result<bool> {
+        Ok(self.env.call(subr::eql, (*self, other))?.is_not_nil())
+    }
+
+    /// Returns whether this value and `other` have the same structure and contents. This is the
+    /// equivalent of the Lisp function [`equal`]. See [`eq`] for how it differs from other
+    /// equality predicates.
+    ///
+    /// Unlike [`eq`], this calls into Lisp, so it can fail, e.g. if the values are circular
+    /// lists.
+    ///
+    /// ```
+    /// # use emacs::{Env, IntoLisp, Result, Value};
+    /// fn is_hello(env: &Env, value: Value) -> Result<bool> {
+    ///     // `eq` would almost always be false here, since strings are not interned.
+    ///     value.equal("hello".into_lisp(env)?)
+    /// }
+    /// ```
+    ///
+    /// [`equal`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Equality-Predicates.html
+    /// [`eq`]: #method.eq
+    pub fn equal(&self, other: Value<'e>) -> Result<bool> {
+        Ok(self.env.call(subr::equal, (*self, other))?.is_not_nil())
+    }
+
     /// Converts this value into a Rust value of the given type.
     #[inline(always)]
     pub fn into_rust<T: FromLisp<'e>>(self) -> Result<T> {
         FromLisp::from_lisp(self)
     }
 
+    /// Converts this value into a Rust value of the given type, like [`into_rust`], but signals
+    /// `wrong-type-argument` if the conversion fails, with `context` and the printed form of this
+    /// value as data. This is useful for validating arguments with consistent error messages.
+    ///
+    /// Non-local exits that are not conversion failures, i.e. quits and throws, are propagated
+    /// unchanged.
+    ///
+    /// ```
+    /// # use emacs::{defun, Result, Value};
+    /// #[defun]
+    /// fn set_width(width: Value) -> Result<()> {
+    ///     // (wrong-type-argument "width" "\"wide\"")
+    ///     let width: u32 = width.expect("width")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`into_rust`]: #method.into_rust
+    pub fn expect<T: FromLisp<'e>>(&self, context: &str) -> Result<T> {
+        let env = self.env;
+        match self.into_rust() {
+            Err(error) if !error.is_throw() && !error.is_quit(env) => {
+                let printed = env.call("prin1-to-string", (*self,))?;
+                env.signal(symbol::wrong_type_argument, (context, printed))
+            }
+            result => result,
+        }
+    }
+
     #[inline]
     pub fn into_ref<T: 'static>(self) -> Result<Ref<'e, T>> {
         let container: &RefCell<T> = self.into_rust()?;
@@ -116,4 +193,46 @@ impl<'e> Value<'e> {
     pub fn cdr<T: FromLisp<'e>>(self) -> Result<T> {
         self.env.call(subr::cdr, (self,))?.into_rust()
     }
+
+    /// Returns the element at index `i` of this Lisp vector.
+    ///
+    /// A `wrong-type-argument` error is signaled if this value is not a vector, and an
+    /// `args-out-of-range` error if `i` is out of bounds. To access many elements, it is more
+    /// efficient to convert this value into a [`Vector`] once.
+    ///
+    /// [`Vector`]: struct.Vector.html
+    pub fn vec_get(&self, i: usize) -> Result<Value<'e>> {
+        let env = self.env;
+        // Safety: Same lifetime. Emacs does type and bound checking.
+        unsafe_raw_call_value!(env, vec_get, self.raw, i as isize)
+    }
+
+    /// Sets the element at index `i` of this Lisp vector to `value`.
+    ///
+    /// A `wrong-type-argument` error is signaled if this value is not a vector, and an
+    /// `args-out-of-range` error if `i` is out of bounds.
+    pub fn vec_set<T: IntoLisp<'e>>(&self, i: usize, value: T) -> Result<()> {
+        let env = self.env;
+        let value = value.into_lisp(env)?;
+        // Safety: Same lifetime. Emacs does type and bound checking.
+        unsafe_raw_call!(env, vec_set, self.raw, i as isize, value.raw)
+    }
+
+    /// Returns the length of this Lisp vector. A `wrong-type-argument` error is signaled if this
+    /// value is not a vector.
+    pub fn vec_len(&self) -> Result<usize> {
+        let env = self.env;
+        let len = unsafe_raw_call!(env, vec_size, self.raw)?;
+        Ok(len.try_into().expect("Invalid size from Emacs"))
+    }
+
+    /// Returns an iterator over the elements of this Lisp vector, fetching them by index. This is
+    /// the vector counterpart of [`Env::list_iter`].
+    ///
+    /// A `wrong-type-argument` error is signaled if this value is not a vector.
+    ///
+    /// [`Env::list_iter`]: struct.Env.html#method.list_iter
+    pub fn vec_iter(&self) -> Result<VecIter<'e>> {
+        VecIter::new(*self)
+    }
 }
@@ -0,0 +1,185 @@
+//! Helpers for working with variables.
+
+use crate::{Env, FromLisp, IntoLisp, Result, Value};
+
+crate::use_symbols! {
+    this_command last_command
+}
+
+impl Env {
+    /// Calls `f` with the given variables dynamically bound to the given values, then restores
+    /// their previous values. This is the equivalent of the Lisp special form [`let`], for special
+    /// (dynamically-bound) variables.
+    ///
+    /// This evaluates a `let` form that calls `f`, so the variables are bound exactly as by `let`:
+    /// in order, only in the current Lisp thread, and even if they are not special. A variable
+    /// that is buffer-local in the current buffer has its buffer-local value bound. Otherwise, its
+    /// default value is bound. The previous values are restored even if `f` returns an error or
+    /// panics, or switches to another buffer. An error from `f` takes precedence over one from
+    /// restoring them.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result, Value};
+    /// fn search_ignoring_case(env: &Env, regexp: &str) -> Result<bool> {
+    ///     env.let_bind(&[("case-fold-search", env.intern("t")?)], || {
+    ///         Ok(env.call("re-search-forward", (regexp, (), true))?.is_not_nil())
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// [`let`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Local-Variables.html
+    pub fn let_bind<R, F>(&self, bindings: &[(&str, Value<'_>)], f: F) -> Result<R>
+    where
+        F: FnOnce() -> Result<R>,
+    {
+        self.call_scoped(f, |function| {
+            // (let ((VAR 'VALUE)...) (funcall 'FUNCTION))
+            let quote = self.intern("quote")?;
+            let bindings = bindings.iter()
+                .map(|(name, value)| self.list((self.intern(name)?, self.list((quote, *value))?)))
+                .collect::<Result<Vec<_>>>()?;
+            let body = self.list((self.intern("funcall")?, self.list((quote, function))?))?;
+            let form = self.list((self.intern("let")?, self.list(&bindings)?, body))?;
+            // Evaluate with dynamic binding, so that non-special variables are bound dynamically.
+            self.call("eval", (form, ()))
+        })
+    }
+
+    /// Returns the value of the variable `name`, converted into a Rust value of the given type,
+    /// with [`FromLisp`]. This is the equivalent of the Lisp function [`symbol-value`]. A
+    /// `void-variable` error is signaled if the variable is void.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result};
+    /// fn fill_column(env: &Env) -> Result<i64> {
+    ///     env.get_var("fill-column")
+    /// }
+    /// ```
+    ///
+    /// [`FromLisp`]: trait.FromLisp.html
+    /// [`symbol-value`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Accessing-Variables.html
+    pub fn get_var<'e, T: FromLisp<'e>>(&'e self, name: &str) -> Result<T> {
+        self.call("symbol-value", [self.intern(name)?])?.into_rust()
+    }
+
+    /// Sets the variable `name` to `value`. This is the equivalent of the Lisp function [`set`].
+    ///
+    /// As with `set`, if the variable has a buffer-local binding in the current buffer, or is
+    /// automatically buffer-local, only the current buffer's value is changed.
+    ///
+    /// [`set`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Setting-Variables.html
+    pub fn set_var<'e, V: IntoLisp<'e>>(&'e self, name: &str, value: V) -> Result<()> {
+        self.call("set", (self.intern(name)?, value))?;
+        Ok(())
+    }
+
+    /// Makes the variable `name` have a separate value in the current buffer, initially the same as
+    /// its default value. This is the equivalent of the Lisp function [`make-local-variable`].
+    ///
+    /// [`make-local-variable`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Creating-Buffer_002dLocal.html
+    pub fn make_local_var(&self, name: &str) -> Result<()> {
+        self.call("make-local-variable", [self.intern(name)?])?;
+        Ok(())
+    }
+
+    /// Defines `name` as a special variable, with the documentation string `doc`, then returns its
+    /// symbol. This is the equivalent of the Lisp special form [`defvar`].
+    ///
+    /// As with `defvar`, the variable is set to `default` only if it is void, so that a value set
+    /// by the user before the module is loaded takes precedence.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result};
+    /// fn init(env: &Env) -> Result<()> {
+    ///     env.defvar("my-module-cache-size", 128, "Number of entries to cache.")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`defvar`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Defining-Variables.html
+    pub fn defvar<'e, V>(&'e self, name: &str, default: V, doc: &str) -> Result<Value<'e>>
+    where
+        V: IntoLisp<'e>,
+    {
+        let default = self.list((self.intern("quote")?, default))?;
+        self.eval_form(self.list((self.intern("defvar")?, self.intern(name)?, default, doc))?)
+    }
+
+    /// Defines `name` as a user option, which can be set through the Customize interface, then
+    /// returns its symbol. This is the equivalent of the Lisp macro [`defcustom`].
+    ///
+    /// `group` names the customization group the option belongs to. `custom_type` is read as the
+    /// option's [customization type], e.g. `"boolean"`, `"integer"`, or `"(repeat string)"`. As
+    /// with [`defvar`], the variable is set to `default` only if it is void.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result};
+    /// fn init(env: &Env) -> Result<()> {
+    ///     let doc = "Whether to cache results.";
+    ///     env.defcustom("my-module-enable-cache", true, doc, "my-module", "boolean")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`defcustom`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Variable-Definitions.html
+    /// [customization type]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Customization-Types.html
+    /// [`defvar`]: #method.defvar
+    pub fn defcustom<'e, V: IntoLisp<'e>>(
+        &'e self,
+        name: &str,
+        default: V,
+        doc: &str,
+        group: &str,
+        custom_type: &str,
+    ) -> Result<Value<'e>> {
+        let quote = self.intern("quote")?;
+        let default = self.list((quote, default))?;
+        let group = self.list((quote, self.intern(group)?))?;
+        let custom_type = self.list((quote, self.call("read", (custom_type,))?))?;
+        self.eval_form(self.list((
+            self.intern("defcustom")?, self.intern(name)?, default, doc,
+            self.intern(":type")?, custom_type,
+            self.intern(":group")?, group,
+        ))?)
+    }
+
+    /// Returns the command now being executed, i.e. the value of the variable [`this-command`].
+    ///
+    /// [`this-command`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Command-Loop-Info.html
+    pub fn this_command(&self) -> Result<Value<'_>> {
+        self.call("symbol-value", (this_command,))
+    }
+
+    /// Sets the variable [`this-command`], which becomes the value of `last-command` once the
+    /// current command finishes. Commands such as `kill-region` do this to make the next command
+    /// treat them as another command.
+    ///
+    /// [`this-command`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Command-Loop-Info.html
+    pub fn set_this_command(&self, command: Value<'_>) -> Result<()> {
+        self.call("set", (this_command, command))?;
+        Ok(())
+    }
+
+    /// Returns the previous command, i.e. the value of the variable [`last-command`]. This can be
+    /// used to make a command behave differently when repeated, like `kill-append`.
+    ///
+    /// ```
+    /// # use emacs::{Env, Result};
+    /// fn is_repeated(env: &Env) -> Result<bool> {
+    ///     Ok(env.call("eq", (env.last_command()?, env.this_command()?))?.is_not_nil())
+    /// }
+    /// ```
+    ///
+    /// [`last-command`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Command-Loop-Info.html
+    pub fn last_command(&self) -> Result<Value<'_>> {
+        self.call("symbol-value", (last_command,))
+    }
+
+    /// Sets the variable [`last-command`].
+    ///
+    /// [`last-command`]: https://www.gnu.org/software/emacs/manual/html_node/elisp/Command-Loop-Info.html
+    pub fn set_last_command(&self, command: Value<'_>) -> Result<()> {
+        self.call("set", (last_command, command))?;
+        Ok(())
+    }
+}
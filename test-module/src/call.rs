@@ -1,5 +1,9 @@
 use crate::*;
 
+emacs::use_symbols! {
+    string_to_number
+}
+
 #[defun]
 fn list(env: &Env, n: u16) -> Result<Value> {
     let x = "x";
@@ -48,3 +52,114 @@ fn mapc_vec(function: Value, vector: emacs::Vector) -> Result<()> {
     }
     Ok(())
 }
+
+/// Call the function named NAME with ARG, if it is defined. Otherwise, return `unbound'.
+#[defun]
+fn if_bound<'e>(env: &'e Env, name: String, arg: Value<'e>) -> Result<Value<'e>> {
+    if !env.fboundp(name.as_str())? {
+        return env.intern("unbound");
+    }
+    env.symbol_function(name.as_str())?.call([arg])
+}
+
+#[defun]
+fn define_obsolete(env: &Env, obsolete: String, current: String, since: String) -> Result<Value<'_>> {
+    env.define_obsolete_function_alias(&obsolete, &current, &since)
+}
+
+/// Read and evaluate FORM, a string, with lexical binding, unless DYNAMIC is non-nil.
+#[defun]
+fn eval_string<'e>(env: &'e Env, form: String, dynamic: Option<Value<'e>>) -> Result<Value<'e>> {
+    match dynamic {
+        Some(dynamic) if dynamic.is_not_nil() => env.eval_in(&form, false),
+        _ => env.eval(&form),
+    }
+}
+
+/// Return A + B, computed by Lisp.
+#[defun]
+fn funcall_add(env: &Env, a: i64, b: i64) -> Result<i64> {
+    env.funcall("+", (a, b))
+}
+
+/// Return the sum of the numbers represented by STRINGS.
+#[defun]
+fn funcall_sum_strings(env: &Env, strings: Vec<String>) -> Result<f64> {
+    let mut sum = 0.0;
+    for s in strings {
+        sum += string_to_number.funcall::<f64, _>(env, (s,))?;
+    }
+    Ok(sum)
+}
+
+/// Return the sum of the integers below N, calling `+' by name.
+#[defun]
+fn sum_by_name(env: &Env, n: i64) -> Result<i64> {
+    let mut sum = 0;
+    for i in 0..n {
+        sum = env.funcall("+", (sum, i))?;
+    }
+    Ok(sum)
+}
+
+/// Return the sum of the integers below N, calling `+' through a cached symbol.
+#[defun]
+fn sum_cached(env: &Env, n: i64) -> Result<i64> {
+    let mut sum = 0;
+    for i in 0..n {
+        sum = env.funcall(emacs::lisp_fn!("+"), (sum, i))?;
+    }
+    Ok(sum)
+}
+
+/// Intern NAME N times, through the symbol cache if CACHED is non-nil, and return the symbol.
+#[defun]
+fn intern_repeatedly(env: &Env, name: String, n: i64, cached: bool) -> Result<Value<'_>> {
+    let mut symbol = env.nil();
+    for _ in 0..n {
+        symbol = if cached { env.intern_cached(&name)? } else { env.intern(&name)? };
+    }
+    Ok(symbol)
+}
+
+/// Return the cached symbols `nil' and `t'.
+#[defun]
+fn nil_and_t(env: &Env) -> Result<Value<'_>> {
+    env.list((env.nil(), env.t()))
+}
+
+static LISP_FN_TARGET: emacs::LispFn = emacs::LispFn::new("t--lisp-fn-target");
+
+/// Call the function symbol cached in a named static with ARG, returning the result.
+#[defun]
+fn lisp_fn_static<'e>(env: &'e Env, arg: Value<'e>) -> Result<Value<'e>> {
+    env.call(&LISP_FN_TARGET, [arg])
+}
+
+/// Return the sum of NUMBERS, applying `+' to them.
+#[defun]
+fn apply_sum(env: &Env, numbers: Value) -> Result<i64> {
+    env.apply("+", numbers)
+}
+
+/// Apply F to 1, "two", and the elements of REST.
+#[defun]
+fn apply_with<'e>(env: &'e Env, f: Value<'e>, rest: Value<'e>) -> Result<Value<'e>> {
+    env.apply_with(f, (1, "two"), rest)
+}
+
+/// Return the name of a cached function symbol, after calling it with ARG.
+#[defun]
+fn lisp_fn_name(env: &Env, arg: Value) -> Result<String> {
+    let func = emacs::lisp_fn!("t--lisp-fn-target");
+    env.call(func, [arg])?;
+    Ok(func.name().to_owned())
+}
+
+/// Evaluate (setq SYMBOL (when TEST VALUE)), returning the result.
+#[defun]
+fn eval_setq_when<'e>(symbol: Value<'e>, test: Value<'e>, value: Value<'e>) -> Result<Value<'e>> {
+    let env = symbol.env;
+    let when = env.list((env.intern("when")?, test, value))?;
+    env.eval_form(env.list((env.intern("setq")?, symbol, when))?)
+}
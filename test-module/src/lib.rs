@@ -1,4 +1,7 @@
-use std::{env, panic};
+use std::{
+    env, panic,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use lazy_static::lazy_static;
 
@@ -11,6 +14,21 @@ mod test_basics;
 mod test_error;
 mod test_lifetime;
 mod test_vector;
+mod test_coding;
+mod test_display;
+mod test_timer;
+mod test_buffer;
+mod test_list;
+mod test_minibuffer;
+mod test_progress;
+mod test_text;
+mod test_quit;
+mod test_variable;
+mod test_math;
+mod test_file_notify;
+mod test_serde;
+mod test_process;
+mod test_hook;
 mod call;
 
 mod ref_cell;
@@ -36,6 +54,17 @@ fn t(env: &Env) -> Result<()> {
 
     test_basics::init(env)?;
     test_error::init(env)?;
+
+    emacs::on_unload(|env| {
+        env.call("set", (env.intern("t--unloaded")?, true))?;
+        Ok(())
+    });
+    // The automatically defined unload function should chain to this one.
+    let unload = env.make_closure(0..0, "Record that the module is being unloaded.", |env| {
+        env.call("set", (env.intern("t--user-unloaded")?, true))?;
+        ().into_lisp(env)
+    })?;
+    env.call("fset", (env.intern("t-unload-function")?, unload))?;
     Ok(())
 }
 
@@ -84,6 +113,33 @@ fn make_dec(env: &Env) -> Result<Value<'_>> {
     emacs::lambda!(env, dec, 1..1, "decrement")
 }
 
+/// The number of closures created by `t/make-adder' that have been dropped.
+static ADDERS_DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+struct CountDrop;
+
+impl Drop for CountDrop {
+    fn drop(&mut self) {
+        ADDERS_DROPPED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Return a function that returns the sum of N and its one or two arguments.
+#[defun]
+fn make_adder(env: &Env, n: i64) -> Result<Value<'_>> {
+    let counted = CountDrop;
+    env.make_closure(1..2, "Add N.", move |env| {
+        let _ = &counted;
+        let args = env.args().into_iter().map(|a| a.into_rust()).collect::<Result<Vec<i64>>>()?;
+        (n + args.iter().sum::<i64>()).into_lisp(env)
+    })
+}
+
+#[defun]
+fn adders_dropped() -> Result<usize> {
+    Ok(ADDERS_DROPPED.load(Ordering::SeqCst))
+}
+
 #[defun]
 fn make_inc_and_plus(env: &Env) -> Result<Value<'_>> {
     fn inc(env: &CallEnv) -> Result<Value<'_>> {
@@ -1,7 +1,19 @@
 //! Testing RefCell embedded in user-ptr.
 
-use emacs::{defun, Result, Value};
-use std::cell::RefCell;
+use emacs::{defun, Env, IntoLisp, Result, Value};
+use std::{cell::RefCell, sync::Mutex};
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+    /// The ids of finalized `Finalized` values, in the order they were finalized.
+    static ref FINALIZED_IDS: Mutex<Vec<i64>> = Mutex::new(vec![]);
+}
+
+struct Finalized {
+    id: i64,
+    panic: bool,
+}
 
 // TODO: Add tests for Mutex and RwLock, and more tests for RefCell.
 
@@ -31,3 +43,31 @@ fn unwrap_and_call(_: &i64, lambda: Value<'_>) -> Result<()> {
     lambda.call([])?;
     Ok(())
 }
+
+/// Wrap ID in a RefCell, with a custom finalizer that records ID. If PANIC is non-nil, the
+/// finalizer panics after recording.
+#[defun]
+fn make_finalized<'e>(env: &'e Env, id: i64, panic: Value<'e>) -> Result<Value<'e>> {
+    let value = RefCell::new(Finalized { id, panic: panic.is_not_nil() });
+    env.make_user_ptr_with_finalizer(value, |cell| {
+        let finalized = cell.get_mut();
+        FINALIZED_IDS.lock().unwrap().push(finalized.id);
+        if finalized.panic {
+            panic!("Finalizer of {} panicked", finalized.id);
+        }
+    })
+}
+
+#[defun]
+fn finalized_id(r: Value<'_>) -> Result<i64> {
+    let r: &RefCell<Finalized> = r.into_rust()?;
+    Ok(r.try_borrow()?.id)
+}
+
+/// Return the ids of finalized values, in the order they were finalized.
+#[defun]
+fn finalized_ids(env: &Env) -> Result<Value<'_>> {
+    let ids = FINALIZED_IDS.lock().unwrap().clone();
+    let ids = ids.into_iter().map(|id| id.into_lisp(env)).collect::<Result<Vec<_>>>()?;
+    env.list(&ids)
+}
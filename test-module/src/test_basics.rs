@@ -1,4 +1,10 @@
-use emacs::{defun, CallEnv, Env, IntoLisp, Result, Value};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use emacs::{defun, CallEnv, Env, HashTable, HashTableTest, IntoLisp, Result, Value};
 use emacs::func::Manage;
 
 use super::MODULE_PREFIX;
@@ -30,6 +36,13 @@ fn to_lowercase_or_nil(env: &Env, input: Option<String>) -> Result<Value<'_>> {
     output.as_ref().into_lisp(env)
 }
 
+/// Return the integer value of PROP in PLIST, plus 1, or nil if PROP is absent.
+#[defun(mod_in_name = false)]
+fn plist_get_inc(env: &Env, plist: Value, prop: Value) -> Result<Option<i64>> {
+    let value: Option<i64> = env.funcall("plist-get", (plist, prop))?;
+    Ok(value.map(|i| i + 1))
+}
+
 pub fn init(env: &Env) -> Result<()> {
     using_fset(env)?;
 
@@ -65,6 +78,58 @@ fn identity_u8(i: u8) -> Result<u8> {
     Ok(i)
 }
 
+#[defun(mod_in_name = false)]
+fn identity_f32(f: f32) -> Result<f32> {
+    Ok(f)
+}
+
+/// Return whether A and B are `eq', `eql', and `equal', as a list.
+#[defun(mod_in_name = false)]
+fn equality<'e>(a: Value<'e>, b: Value<'e>) -> Result<(bool, bool, bool)> {
+    Ok((a.eq(b), a.eql(b)?, a.equal(b)?))
+}
+
+#[defun(mod_in_name = false)]
+fn identity_bool(b: bool) -> Result<bool> {
+    Ok(b)
+}
+
+/// Convert V to a `u8', signaling `wrong-type-argument' with CONTEXT if that fails.
+#[defun(mod_in_name = false)]
+fn expect_u8(v: Value, context: String) -> Result<u8> {
+    v.expect(&context)
+}
+
+/// Return the character after C, or nil if there is none.
+#[defun(mod_in_name = false)]
+fn next_char(c: char) -> Result<Option<char>> {
+    Ok(std::char::from_u32(c as u32 + 1))
+}
+
+#[defun(mod_in_name = false)]
+fn identity_u64(i: u64) -> Result<u64> {
+    Ok(i)
+}
+
+#[defun(mod_in_name = false)]
+fn identity_i128(i: i128) -> Result<i128> {
+    Ok(i)
+}
+
+#[defun(mod_in_name = false)]
+fn identity_u128(i: u128) -> Result<u128> {
+    Ok(i)
+}
+
+/// Return the factorial of N, signaling `overflow-error' if it doesn't fit in 128 bits.
+#[defun(mod_in_name = false)]
+fn factorial(env: &Env, n: u32) -> Result<u128> {
+    match (1..=n as u128).try_fold(1u128, |acc, i| acc.checked_mul(i)) {
+        Some(f) => Ok(f),
+        None => env.signal("overflow-error", (n,)),
+    }
+}
+
 #[defun(mod_in_name = false)]
 fn u64_overflow() -> Result<u64> {
     Ok(u64::max_value())
@@ -81,3 +146,235 @@ fn copy_string_contents(v: Value, size: usize) -> Result<String> {
     let s = v.copy_string_contents(&mut buffer)?;
     Ok(String::from_utf8_lossy(s).to_string())
 }
+
+#[defun(mod_in_name = false)]
+fn lisp_type_of(env: &Env, v: Value) -> Result<String> {
+    use emacs::LispType::*;
+    let name = match env.lisp_type_of(v)? {
+        Symbol => "symbol",
+        Integer => "integer",
+        Float => "float",
+        String => "string",
+        Cons => "cons",
+        Vector => "vector",
+        HashTable => "hash-table",
+        UserPtr => "user-ptr",
+        Function => "function",
+        Other(v) => return env.call("symbol-name", [env.type_of(v)?])?.into_rust(),
+        _ => "unknown",
+    };
+    Ok(name.to_owned())
+}
+
+type Predicate<'e> = fn(&Value<'e>) -> Result<bool>;
+
+/// Return the names of the type predicates that V satisfies.
+#[defun(mod_in_name = false)]
+fn type_predicates<'e>(env: &'e Env, v: Value<'e>) -> Result<Value<'e>> {
+    let checks: [(&str, Predicate<'e>); 7] = [
+        ("string", |v| Ok(v.is_string())),
+        ("integer", |v| Ok(v.is_integer())),
+        ("float", |v| Ok(v.is_float())),
+        ("cons", |v| Ok(v.is_cons())),
+        ("vector", |v| Ok(v.is_vector())),
+        ("function", Value::is_function),
+        ("buffer", |v| Ok(v.is_buffer())),
+    ];
+    let mut names = vec![];
+    for (name, check) in checks.iter() {
+        if check(&v)? {
+            names.push(env.intern(name)?);
+        }
+    }
+    env.list(&names)
+}
+
+#[defun(mod_in_name = false)]
+fn join_strings(strings: Vec<String>, separator: String) -> Result<String> {
+    Ok(strings.join(&separator))
+}
+
+#[defun(mod_in_name = false)]
+fn sum_integers(integers: Vec<i64>) -> Result<i64> {
+    Ok(integers.iter().sum())
+}
+
+#[defun(mod_in_name = false)]
+fn string_lengths(strings: Vec<String>) -> Result<HashMap<String, usize>> {
+    Ok(strings.into_iter().map(|s| {
+        let len = s.chars().count();
+        (s, len)
+    }).collect())
+}
+
+#[defun(mod_in_name = false)]
+fn sum_hash_table_values(table: HashMap<String, i64>) -> Result<i64> {
+    Ok(table.values().sum())
+}
+
+/// Return (X Y (X . Y) NAME).
+#[defun(mod_in_name = false)]
+fn tuple_to_list(env: &Env, x: i64, y: i64, name: String) -> Result<(i64, i64, Value<'_>, String)> {
+    Ok((x, y, env.cons(x, y)?, name))
+}
+
+#[defun(mod_in_name = false)]
+fn time_identity(time: SystemTime) -> Result<SystemTime> {
+    Ok(time)
+}
+
+/// Return the whole seconds and the nanoseconds of DURATION, as a list.
+#[defun(mod_in_name = false)]
+fn duration_parts(duration: Duration) -> Result<(u64, u32)> {
+    Ok((duration.as_secs(), duration.subsec_nanos()))
+}
+
+#[defun(mod_in_name = false)]
+fn make_duration(secs: u64, nanos: u32) -> Result<Duration> {
+    Ok(Duration::new(secs, nanos))
+}
+
+/// Return the file name PARENT joined with CHILD.
+#[defun(mod_in_name = false)]
+fn path_join(parent: PathBuf, child: PathBuf) -> Result<PathBuf> {
+    Ok(parent.join(child))
+}
+
+#[defun(mod_in_name = false)]
+fn path_absolute_p(path: PathBuf) -> Result<bool> {
+    Ok(path.is_absolute())
+}
+
+/// Return the bytes of PATH, as seen by the OS, in a unibyte string.
+#[cfg(unix)]
+#[defun(mod_in_name = false)]
+fn path_bytes(path: PathBuf) -> Result<Vec<u8>> {
+    use std::os::unix::ffi::OsStringExt;
+    Ok(path.into_os_string().into_vec())
+}
+
+/// Make a hash table that compares keys with `eq' if EQ is non-nil, `equal' otherwise. Fill it
+/// with the entries of ALIST, then remove the entry for REMOVED.
+#[defun(mod_in_name = false)]
+fn make_hash_table<'e>(
+    env: &'e Env,
+    eq: Value<'e>,
+    alist: Vec<Value<'e>>,
+    removed: Value<'e>,
+) -> Result<HashTable<'e>> {
+    let test = if eq.is_not_nil() { HashTableTest::Eq } else { HashTableTest::Equal };
+    let table = env.make_hash_table(test)?;
+    for entry in alist {
+        table.insert(entry.car::<Value>()?, entry.cdr::<Value>()?)?;
+    }
+    table.remove(removed)?;
+    Ok(table)
+}
+
+/// Return (t . VALUE) if TABLE has an entry for KEY, nil otherwise.
+#[defun(mod_in_name = false)]
+fn hash_table_lookup<'e>(env: &'e Env, table: HashTable<'e>, key: Value<'e>) -> Result<Value<'e>> {
+    match table.get(key)? {
+        Some(value) => env.cons(true, value),
+        None => ().into_lisp(env),
+    }
+}
+
+/// Return the entries of TABLE as an alist, and its size, in a cons.
+#[defun(mod_in_name = false)]
+fn hash_table_entries<'e>(env: &'e Env, table: HashTable<'e>) -> Result<Value<'e>> {
+    let mut entries = vec![];
+    for entry in table.iter()? {
+        let (key, value) = entry?;
+        entries.push(env.cons(key, value)?);
+    }
+    env.cons(env.list(&entries)?, table.len()?)
+}
+
+/// Return a unibyte string holding BYTES, a list of integers.
+#[defun(mod_in_name = false)]
+fn bytes_to_string(bytes: Vec<u8>) -> Result<Vec<u8>> {
+    Ok(bytes)
+}
+
+/// Return the bytes of the string S, as a vector of integers.
+#[defun(mod_in_name = false)]
+fn string_to_bytes<'e>(env: &'e Env, s: Value<'e>) -> Result<Value<'e>> {
+    let bytes = s.as_bytes()?.into_iter().map(|b| b.into_lisp(env)).collect::<Result<Vec<_>>>()?;
+    env.vector(bytes.as_slice())
+}
+
+/// Return the number of UTF-8 bytes in A and B, and the first character of A, if any. B is
+/// accessed while A is still borrowed.
+#[defun(mod_in_name = false)]
+fn str_byte_lengths(a: Value, b: Value) -> Result<(usize, usize, Option<String>)> {
+    let (len_a, len_b) = a.with_str_bytes(|x| b.with_str_bytes(|y| (x.len(), y.len())))??;
+    let first = a.with_str(|s| s.chars().next().map(String::from))?;
+    Ok((len_a, len_b, first))
+}
+
+#[defun(mod_in_name = false)]
+fn emacs_major_version(env: &Env) -> Result<u32> {
+    env.emacs_major_version()
+}
+
+#[defun(mod_in_name = false)]
+fn emacs_version(env: &Env) -> Result<(u32, u32)> {
+    env.emacs_version()
+}
+
+/// Return the module API version, and the size of `emacs_env' provided by Emacs.
+#[defun(mod_in_name = false)]
+fn module_api_info(env: &Env) -> Result<(u32, usize)> {
+    Ok((emacs::MODULE_API_VERSION, env.module_abi_size()))
+}
+
+#[defun(mod_in_name = false)]
+fn emacs_version_at_least(env: &Env, major: u32, minor: u32) -> Result<bool> {
+    env.emacs_version_at_least(major, minor)
+}
+
+#[defun(mod_in_name = false)]
+fn greet(name: String, title: Option<String>, suffix: Option<String>) -> Result<String> {
+    let mut greeting = match title {
+        Some(title) => format!("Hello, {} {}", title, name),
+        None => format!("Hello, {}", name),
+    };
+    greeting.push_str(suffix.as_deref().unwrap_or("!"));
+    Ok(greeting)
+}
+
+/// Return the numeric prefix argument N, doubled.
+#[defun(mod_in_name = false, interactive = "p")]
+fn double_prefix(n: i64) -> Result<i64> {
+    Ok(n * 2)
+}
+
+/// Return the bounds of the region, from BEG to END.
+#[defun(mod_in_name = false, interactive = "r")]
+fn region_bounds(beg: i64, end: i64) -> Result<(i64, i64)> {
+    Ok((beg, end))
+}
+
+#[defun(mod_in_name = false, interactive)]
+fn no_arg_command() -> Result<&'static str> {
+    Ok("called")
+}
+
+/// Return X unchanged.
+///
+/// Quotes: "double" and `single'.
+/// Backslashes: \ and \\.
+///     This line is indented.
+#[defun(mod_in_name = false)]
+fn doc_example(x: Value<'_>) -> Result<Value<'_>> {
+    Ok(x)
+}
+
+/// Return OBJECT unchanged.
+///
+/// (fn OBJECT)
+#[defun(mod_in_name = false)]
+fn doc_explicit_signature(x: Value<'_>) -> Result<Value<'_>> {
+    Ok(x)
+}
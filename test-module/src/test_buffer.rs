@@ -0,0 +1,164 @@
+//! Testing buffer-related helpers.
+
+use emacs::{defun, Buffer, Env, IntoLisp, Result, Value};
+
+#[defun(mod_in_name = false)]
+fn buffer_narrowed_p(env: &Env) -> Result<bool> {
+    env.buffer_narrowed_p()
+}
+
+#[defun(mod_in_name = false)]
+fn region_active_p(env: &Env) -> Result<bool> {
+    env.region_active_p()
+}
+
+/// Return the beginning and end positions of the line N - 1 lines away.
+#[defun(mod_in_name = false)]
+fn line_bounds(env: &Env, n: Option<i64>) -> Result<(i64, i64)> {
+    Ok((env.line_beginning_position(n)?, env.line_end_position(n)?))
+}
+
+#[defun(mod_in_name = false)]
+fn buffer_hash(env: &Env, buffer: Option<Value>) -> Result<String> {
+    env.buffer_hash(buffer)
+}
+
+/// Move point to POS, and return a marker at where point was before.
+#[defun(mod_in_name = false)]
+fn goto_char_marking(env: &Env, pos: usize) -> Result<Value<'_>> {
+    let marker = env.make_marker_at(env.point()?)?;
+    env.goto_char(pos)?;
+    Ok(marker)
+}
+
+#[defun(mod_in_name = false)]
+fn marker_position(marker: Value) -> Result<Option<usize>> {
+    marker.marker_position()
+}
+
+/// Return the name of the buffer named NAME, as found by `get-buffer', or nil if there is none.
+#[defun(mod_in_name = false)]
+fn get_buffer_name(env: &Env, name: String) -> Result<Option<String>> {
+    env.get_buffer(&name)?.map(|buffer| buffer.name()).transpose()
+}
+
+#[defun(mod_in_name = false)]
+fn current_buffer_name(env: &Env) -> Result<String> {
+    env.buffer_name(env.current_buffer()?.value())
+}
+
+#[defun(mod_in_name = false)]
+fn buffer_name_of(env: &Env, buffer: Value) -> Result<String> {
+    env.buffer_name(buffer)
+}
+
+#[defun(mod_in_name = false)]
+fn buffer_is_live(buffer: Buffer) -> Result<bool> {
+    buffer.is_live()
+}
+
+/// Return the text of BUFFER between START and END, with text properties if PROPERTIZED is
+/// non-nil.
+#[defun(mod_in_name = false)]
+fn buffer_substring<'e>(
+    buffer: Buffer<'e>,
+    start: usize,
+    end: usize,
+    propertized: Option<Value<'e>>,
+) -> Result<Value<'e>> {
+    match propertized {
+        Some(p) if p.is_not_nil() => buffer.substring_propertized(start, end),
+        _ => buffer.substring(start, end)?.into_lisp(buffer.value().env),
+    }
+}
+
+/// Call F with BUFFER temporarily made current.
+#[defun(mod_in_name = false)]
+fn with_buffer<'e>(env: &'e Env, buffer: Value<'e>, f: Value<'e>) -> Result<Value<'e>> {
+    env.with_buffer(buffer, |_| f.call([]))
+}
+
+/// Make BUFFER current, then panic.
+#[defun(mod_in_name = false)]
+fn with_buffer_panicking(env: &Env, buffer: Value) -> Result<()> {
+    env.with_buffer(buffer, |_| panic!("Panicking in another buffer"))
+}
+
+/// Inserts each string as if by a separate command, i.e. with undo boundaries in between, then
+/// panic if PANIC is non-nil. Return the values of `undo-outer-limit', `undo-limit', and
+/// `undo-strong-limit' while inserting.
+#[defun(mod_in_name = false)]
+fn insert_amalgamated<'e>(env: &'e Env, strings: Vec<String>, panic: Value) -> Result<Value<'e>> {
+    env.with_undo_amalgamate(|| {
+        for s in strings {
+            env.call("insert", (s,))?;
+            env.call("undo-boundary", [])?;
+        }
+        if panic.is_not_nil() {
+            panic!("Panicking after inserting");
+        }
+        env.list((
+            env.get_var::<Value>("undo-outer-limit")?,
+            env.get_var::<Value>("undo-limit")?,
+            env.get_var::<Value>("undo-strong-limit")?,
+        ))
+    })
+}
+
+/// Insert N lines, with undo recording disabled if DISABLE-UNDO is non-nil. Return whether undo
+/// recording was disabled while inserting.
+#[defun(mod_in_name = false)]
+fn insert_lines(env: &Env, n: i64, disable_undo: Value) -> Result<bool> {
+    let insert = || {
+        for i in 0..n {
+            env.call("insert", (format!("Line {}\n", i),))?;
+        }
+        Ok(env.call("symbol-value", [env.intern("buffer-undo-list")?])?.eq(env.intern("t")?))
+    };
+    if disable_undo.is_not_nil() {
+        env.with_undo_disabled(insert)
+    } else {
+        insert()
+    }
+}
+
+/// Insert TEXT at the end of the buffer, then delete it, preserving the modified flag. Return
+/// whether the buffer was modified in between. If FAIL is non-nil, signal an error instead of
+/// deleting the text, or panic if it is `panic'.
+#[defun(mod_in_name = false)]
+fn insert_transiently(env: &Env, text: String, fail: Value) -> Result<bool> {
+    env.preserving_modified_flag(|| {
+        let end: i64 = env.call("point-max", [])?.into_rust()?;
+        env.call("goto-char", (end,))?;
+        env.call("insert", (text,))?;
+        let modified = env.call("buffer-modified-p", [])?.is_not_nil();
+        if fail.eq(env.intern("panic")?) {
+            panic!("Panicking after inserting");
+        }
+        if fail.is_not_nil() {
+            env.call("error", ("Failed after inserting",))?;
+        }
+        env.call("delete-region", (end, env.call("point-max", [])?))?;
+        Ok(modified)
+    })
+}
+
+#[defun(mod_in_name = false)]
+fn yank(env: &Env) -> Result<()> {
+    env.yank()
+}
+
+#[defun(mod_in_name = false)]
+fn yank_pop(env: &Env) -> Result<()> {
+    env.yank_pop()
+}
+
+#[defun(mod_in_name = false)]
+fn current_kill(env: &Env, n: i64, do_not_move: Value) -> Result<String> {
+    env.current_kill(n, do_not_move.is_not_nil())
+}
+
+#[defun(mod_in_name = false)]
+fn replace_buffer_contents(env: &Env, source: Value, max_secs: Option<f64>) -> Result<bool> {
+    env.replace_buffer_contents(source, max_secs)
+}
@@ -0,0 +1,28 @@
+//! Testing conversion through Emacs's coding systems.
+
+use emacs::{defun, Env, IntoLisp, Result, Value, Vector};
+
+#[defun(mod_in_name = false)]
+fn encode_coding_string<'e>(env: &'e Env, s: String, coding: String) -> Result<Value<'e>> {
+    let bytes = env.encode_coding_string(&s, &coding)?;
+    let bytes = bytes.into_iter().map(|b| b.into_lisp(env)).collect::<Result<Vec<_>>>()?;
+    env.vector(bytes.as_slice())
+}
+
+#[defun(mod_in_name = false)]
+fn decode_coding_string(env: &Env, bytes: Vector, coding: String) -> Result<String> {
+    let bytes = bytes.into_iter().map(|b| b.into_rust()).collect::<Result<Vec<u8>>>()?;
+    env.decode_coding_string(&bytes, &coding)
+}
+
+#[defun(mod_in_name = false)]
+fn coding_round_trip(env: &Env, s: String, coding: String) -> Result<String> {
+    let bytes = env.encode_coding_string(&s, &coding)?;
+    env.decode_coding_string(&bytes, &coding)
+}
+
+/// Call F with CODING used to read and write files.
+#[defun(mod_in_name = false)]
+fn with_coding_system<'e>(env: &'e Env, coding: String, f: Value<'e>) -> Result<Value<'e>> {
+    env.with_coding_system(&coding, || f.call([]))
+}
@@ -0,0 +1,131 @@
+//! Testing display-related helpers.
+
+use emacs::{defun, Env, ImageSource, IntoLisp, Overlay, Result, Value};
+
+#[defun(mod_in_name = false)]
+fn window_text_pixel_size<'e>(
+    env: &'e Env,
+    window: Value<'e>,
+    from: i64,
+    to: i64,
+) -> Result<Value<'e>> {
+    let (width, height) = env.window_text_pixel_size(window.into_rust()?, from, to)?;
+    env.cons(width, height)
+}
+
+/// Split the selected window, then return the number of windows, restoring the window layout
+/// afterward. If FAIL is non-nil, signal an error after splitting instead, or panic if it is
+/// `panic'.
+#[defun(mod_in_name = false)]
+fn split_window_excursion(env: &Env, fail: Value) -> Result<i64> {
+    env.save_window_excursion(|| {
+        env.call("split-window", [])?;
+        if fail.eq(env.intern("panic")?) {
+            panic!("Panicking after splitting");
+        }
+        if fail.is_not_nil() {
+            env.call("error", ("Failed after splitting",))?;
+        }
+        env.call("count-windows", [])?.into_rust()
+    })
+}
+
+#[defun(mod_in_name = false)]
+fn overlays_in(env: &Env, start: i64, end: i64) -> Result<Value<'_>> {
+    env.list(&env.overlays_in(start, end)?)
+}
+
+/// Highlight the matches of REGEXP in the current buffer with FACE, using overlays. Return them.
+#[defun(mod_in_name = false)]
+fn highlight_matches<'e>(env: &'e Env, regexp: String, face: Value<'e>) -> Result<Value<'e>> {
+    let mut overlays = vec![];
+    env.goto_char(1)?;
+    while env.call("re-search-forward", (regexp.as_str(), (), true))?.is_not_nil() {
+        let start: usize = env.call("match-beginning", (0,))?.into_rust()?;
+        let end: usize = env.call("match-end", (0,))?.into_rust()?;
+        let overlay = env.make_overlay(start, end, None)?;
+        overlay.put("face", face)?;
+        overlays.push(overlay.value());
+    }
+    env.list(&overlays)
+}
+
+#[defun(mod_in_name = false)]
+fn make_overlay<'e>(
+    env: &'e Env,
+    start: usize,
+    end: usize,
+    buffer: Option<Value<'e>>,
+) -> Result<Overlay<'e>> {
+    env.make_overlay(start, end, buffer)
+}
+
+/// Move OVERLAY to START and END, then return its new bounds.
+#[defun(mod_in_name = false)]
+fn move_overlay(overlay: Overlay, start: usize, end: usize) -> Result<(usize, usize)> {
+    overlay.move_to(start, end)?;
+    Ok((overlay.start()?, overlay.end()?))
+}
+
+#[defun(mod_in_name = false)]
+fn delete_overlay(overlay: Overlay) -> Result<bool> {
+    overlay.delete()?;
+    overlay.is_live()
+}
+
+#[defun(mod_in_name = false)]
+fn overlays_at(env: &Env, pos: i64) -> Result<Value<'_>> {
+    env.list(&env.overlays_at(pos)?)
+}
+
+#[defun(mod_in_name = false)]
+fn make_display_table(env: &Env) -> Result<Value<'_>> {
+    env.make_display_table()
+}
+
+/// Make TABLE show the character C as the characters of GLYPHS, or normally if GLYPHS is nil.
+#[defun(mod_in_name = false)]
+fn set_display_table_entry(table: Value, c: u32, glyphs: Option<String>) -> Result<()> {
+    let c = std::char::from_u32(c).expect("Invalid character");
+    let glyphs: Option<Vec<char>> = glyphs.map(|g| g.chars().collect());
+    table.env.set_display_table_entry(table, c, glyphs.as_deref())
+}
+
+/// Insert a button labeled LABEL, which calls FUNC with the button when activated.
+#[defun(mod_in_name = false)]
+fn insert_button<'e>(env: &'e Env, label: String, func: Value<'e>) -> Result<Value<'e>> {
+    let func = func.make_global_ref();
+    env.insert_button(&label, move |env, button| {
+        func.call(env, [button])?;
+        Ok(())
+    })
+}
+
+#[defun(mod_in_name = false)]
+fn font_lock_add_keywords<'e>(
+    env: &'e Env,
+    mode: Value<'e>,
+    keywords: Value<'e>,
+    how: Value<'e>,
+) -> Result<Value<'e>> {
+    let mode: Option<String> = mode.into_rust()?;
+    env.font_lock_add_keywords(mode.as_deref(), keywords, how)
+}
+
+/// Insert an image of TYPE, created from the bytes of the string DATA, at point, scaled by SCALE.
+#[defun(mod_in_name = false)]
+fn insert_image_data(
+    env: &Env,
+    data: Value,
+    image_type: String,
+    scale: f64,
+    string: Option<String>,
+) -> Result<()> {
+    let data = data.as_bytes()?;
+    let image = env.create_image(
+        ImageSource::Data(&data),
+        &image_type,
+        &[(":scale", scale.into_lisp(env)?)],
+    )?;
+    env.insert_image(image, string.as_deref())
+}
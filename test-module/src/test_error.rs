@@ -2,9 +2,9 @@
 
 use std::fs;
 
-use emacs::{defun, CallEnv, Env, Result, Value};
-use emacs::ErrorKind::{self, Signal, Throw};
-use emacs::ResultExt;
+use emacs::{defun, CallEnv, Env, Error, OwnedGlobalRef, Result, Value};
+use emacs::ErrorKind::{self, Signal};
+use emacs::{ErrorExt, ResultExt};
 
 use super::MODULE_PREFIX;
 
@@ -29,42 +29,91 @@ fn lisp_divide(x: Value<'_>, y: Value<'_>) -> Result<i64> {
 fn get_type(f: Value<'_>) -> Result<Value<'_>> {
     let env = f.env;
     match f.call([]) {
-        Err(error) => {
-            if let Some(Signal { symbol, .. }) = error.downcast_ref::<ErrorKind>() {
-                unsafe {
-                    return Ok(symbol.value(env));
-                }
+        Err(error) => match error.signal_symbol(env)? {
+            Some(symbol) => Ok(symbol),
+            None => Err(error),
+        },
+        v => v,
+    }
+}
+
+/// Call LAMBDA, and return the symbol of the error it signals, after freeing the error's values.
+#[defun(mod_in_name = false, name = "error:get-type-and-free")]
+fn get_type_and_free(f: Value<'_>) -> Result<Value<'_>> {
+    let env = f.env;
+    match f.call([]) {
+        Err(error) => match error.downcast::<ErrorKind>() {
+            Ok(Signal { symbol, data }) => {
+                let value = symbol.rooted().to_local(env)?;
+                symbol.into_rooted().free(env)?;
+                data.into_rooted().free(env)?;
+                env.call("garbage-collect", [])?;
+                Ok(value)
             }
-            Err(error)
-        }
+            Ok(kind) => Err(kind.into()),
+            Err(error) => Err(error),
+        },
         v => v,
     }
 }
 
+/// Call LAMBDA, drop the error it signals, and return the number of global references queued to be
+/// freed as a result.
+#[defun(mod_in_name = false, name = "error:drop-and-count-pending")]
+fn drop_and_count_pending(f: Value<'_>) -> Result<usize> {
+    let pending = OwnedGlobalRef::pending_count();
+    match f.call([]) {
+        Err(error) if error.is_signal() => drop(error),
+        result => return result.map(|_| 0),
+    }
+    Ok(OwnedGlobalRef::pending_count() - pending)
+}
+
 /// Call LAMBDA and return the result. Return the thrown value if EXPECTED-TAG is thrown.
 #[defun(mod_in_name = false, name = "error:catch")]
 fn catch<'e>(expected_tag: Value<'e>, lambda: Value<'e>) -> Result<Value<'e>> {
-    let env = expected_tag.env;
-    match lambda.call([]) {
-        Err(error) => {
-            if let Some(Throw { tag, value }) = error.downcast_ref::<ErrorKind>() {
-                unsafe {
-                    if tag.value(env).eq(expected_tag) {
-                        return Ok(value.value(env));
-                    }
-                }
+    emacs::catch!(expected_tag.env, expected_tag, {
+        lambda.call([])
+    })
+}
+
+/// Call BODY, then CLEANUP, even if BODY exits non-locally. If PANIC is non-nil, panic after
+/// calling BODY.
+#[defun(mod_in_name = false, name = "error:unwind-protect")]
+fn unwind_protect<'e>(
+    env: &'e Env,
+    body: Value<'e>,
+    cleanup: Value<'e>,
+    panic: Option<String>,
+) -> Result<Value<'e>> {
+    env.unwind_protect(
+        || {
+            let value = body.call([])?;
+            if let Some(message) = panic {
+                panic!("{}", message);
             }
-            Err(error)
-        }
-        v => v,
-    }
+            Ok(value)
+        },
+        || {
+            cleanup.call([]).expect("Cleanup failed");
+        },
+    )
 }
 
 /// Call `apply` on LAMBDA and ARGS, propagating any signaled error.
 #[defun(mod_in_name = false, name = "error:apply")]
 fn apply<'e>(lambda: Value<'e>, args: Value<'e>) -> Result<Value<'e>> {
-    let env = lambda.env;
-    env.call("apply", (lambda, args))
+    lambda.env.apply(lambda, args)
+}
+
+/// Call LAMBDA, returning `quit' if it quits, and `error' if it signals another error.
+#[defun(mod_in_name = false, name = "error:classify-quit")]
+fn classify_quit<'e>(env: &'e Env, lambda: Value<'e>) -> Result<Value<'e>> {
+    match lambda.call([]) {
+        Err(error) if error.is_quit(env) => env.intern("quit"),
+        Err(error) if error.is_signal() => env.intern("error"),
+        result => result,
+    }
 }
 
 #[defun(mod_in_name = false)]
@@ -82,22 +131,67 @@ fn signal(env: &Env, symbol: Value, message: String) -> Result<()> {
     env.signal(symbol, (message,))
 }
 
+/// Parse TEXT as an integer, signaling `emrs-file-error' with the data (FILE LINE MESSAGE) on failure.
+#[defun(mod_in_name = false, name = "error:parse-at")]
+fn parse_at(env: &Env, file: String, line: i64, text: String) -> Result<i64> {
+    text.parse::<i64>().or_signal_with(env, emrs_file_error, |e| (file, line, e.to_string()))
+}
+
+/// Call LAMBDA, returning the data of the error it signals, or `none' if it does not signal.
+#[defun(mod_in_name = false, name = "error:get-data")]
+fn get_data<'e>(env: &'e Env, lambda: Value<'e>) -> Result<Value<'e>> {
+    match lambda.call([]) {
+        Err(error) => match error.signal_data(env)? {
+            Some(data) => Ok(data),
+            None => Err(error),
+        },
+        Ok(_) => env.intern("none"),
+    }
+}
+
+/// Call LAMBDA, and describe how it exited: (signal SYMBOL DATA), (throw TAG VALUE), or (return).
+#[defun(mod_in_name = false, name = "error:classify")]
+fn classify<'e>(env: &'e Env, lambda: Value<'e>) -> Result<Value<'e>> {
+    match lambda.call([]) {
+        Err(error) if error.is_signal() => {
+            env.list((env.intern("signal")?, error.signal_symbol(env)?, error.signal_data(env)?))
+        }
+        Err(error) if error.is_throw() => {
+            env.list((env.intern("throw")?, error.throw_tag(env)?, error.throw_value(env)?))
+        }
+        Err(error) => Err(error),
+        Ok(_) => env.list((env.intern("return")?,)),
+    }
+}
+
+#[defun(mod_in_name = false, name = "error:define-checked")]
+fn define_checked<'e>(name: Value<'e>, parent: Value<'e>) -> Result<Value<'e>> {
+    name.env.define_error_checked(name, "Checked error", [parent])
+}
+
 fn parse_arg(env: &CallEnv) -> Result<String> {
     let i: i64 = env.parse_arg(0)?;
     let s: String = env.parse_arg(i as usize)?;
     Ok(s)
 }
 
+fn parse_keyword(env: &CallEnv) -> Result<Option<i64>> {
+    let keyword: String = env.parse_arg(0)?;
+    env.parse_keyword(1, &keyword)
+}
+
 emacs::define_errors! {
     emrs_file_error "File error"
     emacs_module_rs_test_error "Hello" (rust_error)
     error_defined_without_parent "Error"
+    error_with_computed_message { |env| env.funcall("format", ("Computed by %s", "closure")) } (rust_error)
 }
 
 pub fn init(env: &Env) -> Result<()> {
     emacs::__export_functions! {
         env, format!("{}error:", *MODULE_PREFIX), {
-            "parse-arg"   => (parse_arg   , 2..5),
+            "parse-arg"     => (parse_arg    , 2..5),
+            "parse-keyword" => (parse_keyword, 1..7),
         }
     }
 
@@ -108,3 +202,18 @@ pub fn init(env: &Env) -> Result<()> {
 
     Ok(())
 }
+
+/// Parse TEXT as an integer, adding context to the error if that fails.
+#[defun(mod_in_name = false, name = "error:parse-with-context")]
+fn parse_with_context(text: String) -> Result<i64> {
+    fn parse(text: &str) -> Result<i64> {
+        text.parse().map_err(|e| Error::from(e).context(format!("while parsing {:?}", text)))
+    }
+    parse(&text).map_err(|e| e.context("while loading config"))
+}
+
+/// Like `t/error:parse-with-context', but signal `emrs-file-error' instead of `rust-error'.
+#[defun(mod_in_name = false, name = "error:parse-with-context-or-signal")]
+fn parse_with_context_or_signal(env: &Env, text: String) -> Result<i64> {
+    parse_with_context(text).or_signal(env, emrs_file_error)
+}
@@ -0,0 +1,19 @@
+//! Testing file notifications backed by Rust closures.
+
+use emacs::{defun, Env, Result, Value};
+
+/// Watch FILE for changes, calling FUNC with each event. Return the watch descriptor.
+#[defun(mod_in_name = false)]
+fn file_notify_watch<'e>(env: &'e Env, file: String, func: Value<'e>) -> Result<Value<'e>> {
+    let func = func.make_global_ref();
+    env.file_notify_add_watch(&file, &["change"], move |env, event| {
+        func.call(env, [event])?;
+        Ok(())
+    })
+}
+
+/// Stop watching the file identified by DESCRIPTOR.
+#[defun(mod_in_name = false)]
+fn file_notify_unwatch(env: &Env, descriptor: Value) -> Result<()> {
+    env.file_notify_rm_watch(descriptor)
+}
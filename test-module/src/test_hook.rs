@@ -0,0 +1,34 @@
+//! Testing hook-related helpers.
+
+use emacs::{defun, Env, Result, Value};
+
+/// Add FUNCTION to HOOK. APPEND and LOCAL are as in `add-hook'.
+#[defun(mod_in_name = false)]
+fn add_hook<'e>(
+    env: &'e Env,
+    hook: String,
+    function: Value<'e>,
+    append: Value<'e>,
+    local: Value<'e>,
+) -> Result<()> {
+    env.add_hook(&hook, function, append.is_not_nil(), local.is_not_nil())
+}
+
+/// Add the function named NAME to HOOK, through a global reference.
+#[defun(mod_in_name = false)]
+fn add_hook_global_ref(env: &Env, hook: String, name: String) -> Result<()> {
+    let function = env.intern(&name)?.make_global_ref();
+    let result = env.add_hook(&hook, &function, false, false);
+    function.free(env)?;
+    result
+}
+
+#[defun(mod_in_name = false)]
+fn remove_hook<'e>(env: &'e Env, hook: String, function: Value<'e>, local: Value<'e>) -> Result<()> {
+    env.remove_hook(&hook, function, local.is_not_nil())
+}
+
+#[defun(mod_in_name = false)]
+fn run_hooks(env: &Env, hook: String) -> Result<()> {
+    env.run_hooks(&hook)
+}
@@ -1,6 +1,8 @@
 //! Testing soundness of GC interactions.
 
-use emacs::{defun, Env, IntoLisp, Result, Value};
+use std::thread;
+
+use emacs::{defun, Env, IntoLisp, OwnedGlobalRef, Result, Value};
 use emacs::ErrorKind::{self, Signal};
 
 use super::MODULE_PREFIX;
@@ -106,9 +108,7 @@ fn gc_after_catching_1<'e>(env: &'e Env, f: Value<'e>) -> Result<Value<'e>> {
         match env.call("funcall", [f]) {
             Err(error) => {
                 if let Some(Signal { data, .. }) = error.downcast_ref::<ErrorKind>() {
-                    unsafe {
-                        return Ok(data.value(env));
-                    }
+                    return data.rooted().to_local(env);
                 }
                 Err(error)
             }
@@ -136,3 +136,36 @@ fn trigger_double_free_global_ref<'e>(env: &'e Env, func: Value<'e>) -> Result<(
     eprintln!("3 ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
     Ok(())
 }
+
+/// Drop an owned global reference to VALUE on a background thread, where no env is available.
+/// Return the number of global references waiting to be freed.
+#[defun(mod_in_name = false)]
+fn owned_global_ref_drop_on_thread(value: Value<'_>) -> Result<usize> {
+    let owned = OwnedGlobalRef::new(value);
+    thread::spawn(move || drop(owned)).join().expect("Failed to drop on a background thread");
+    Ok(OwnedGlobalRef::pending_count())
+}
+
+/// Return the number of global references waiting to be freed.
+#[defun(mod_in_name = false)]
+fn owned_global_ref_pending_count() -> Result<usize> {
+    Ok(OwnedGlobalRef::pending_count())
+}
+
+/// Wrap an owned global reference to VALUE in a `user-ptr`, whose finalizer drops it.
+#[defun(mod_in_name = false, user_ptr)]
+fn owned_global_ref_in_user_ptr(value: Value<'_>) -> Result<OwnedGlobalRef> {
+    Ok(OwnedGlobalRef::new(value))
+}
+
+/// Drop one of 2 owned global references to VALUE, then return VALUE through the other one.
+#[defun(mod_in_name = false)]
+fn owned_global_ref_drop_in_call<'e>(env: &'e Env, value: Value<'e>) -> Result<Value<'e>> {
+    let owned = OwnedGlobalRef::new(value);
+    let copy = owned.clone(env);
+    let pending = OwnedGlobalRef::pending_count();
+    drop(owned);
+    assert_eq!(OwnedGlobalRef::pending_count(), pending + 1);
+    gc(env)?;
+    env.call("copy-tree", [copy.bind(env)])
+}
@@ -0,0 +1,97 @@
+//! Testing list and sequence helpers.
+
+use std::collections::BTreeSet;
+
+use emacs::{defun, Env, HashTableTest, IntoLisp, Result, Value};
+
+#[defun(mod_in_name = false)]
+fn assoc<'e>(
+    env: &'e Env,
+    key: Value<'e>,
+    alist: Value<'e>,
+    testfn: Option<Value<'e>>,
+) -> Result<Option<Value<'e>>> {
+    env.assoc(key, alist, testfn)
+}
+
+#[defun(mod_in_name = false)]
+fn rassoc<'e>(env: &'e Env, value: Value<'e>, alist: Value<'e>) -> Result<Option<Value<'e>>> {
+    env.rassoc(value, alist)
+}
+
+#[defun(mod_in_name = false)]
+fn count<'e>(env: &'e Env, elem: Value<'e>, seq: Value<'e>) -> Result<usize> {
+    env.count(elem, seq)
+}
+
+#[defun(mod_in_name = false)]
+fn count_if<'e>(env: &'e Env, pred: Value<'e>, seq: Value<'e>) -> Result<usize> {
+    env.count_if(pred, seq)
+}
+
+/// Remove duplicates from SEQ, comparing elements with `eq' if EQ is non-nil, `equal' otherwise.
+#[defun(mod_in_name = false)]
+fn remove_duplicates<'e>(env: &'e Env, seq: Value<'e>, eq: Value<'e>) -> Result<Value<'e>> {
+    let test = if eq.is_not_nil() { HashTableTest::Eq } else { HashTableTest::Equal };
+    env.remove_duplicates(seq, test)
+}
+
+#[defun(mod_in_name = false)]
+fn seq_take<'e>(env: &'e Env, seq: Value<'e>, n: i64) -> Result<Value<'e>> {
+    env.seq_take(seq, n)
+}
+
+#[defun(mod_in_name = false)]
+fn seq_drop<'e>(env: &'e Env, seq: Value<'e>, n: i64) -> Result<Value<'e>> {
+    env.seq_drop(seq, n)
+}
+
+#[defun(mod_in_name = false)]
+fn list_iter_sum<'e>(env: &'e Env, list: Value<'e>) -> Result<i64> {
+    let mut sum = 0;
+    for item in env.list_iter(list)? {
+        sum += item?.into_rust::<i64>()?;
+    }
+    Ok(sum)
+}
+
+/// Return the distinct integers in SEQ, a list or vector, in ascending order.
+#[defun(mod_in_name = false)]
+fn distinct_sorted<'e>(env: &'e Env, seq: Value<'e>) -> Result<Value<'e>> {
+    let set = seq.iter_seq()?.map(|v| v?.into_rust::<i64>()).collect::<Result<BTreeSet<_>>>()?;
+    let items = set.into_iter().map(|i| i.into_lisp(env)).collect::<Result<Vec<_>>>()?;
+    env.list(&items)
+}
+
+#[defun(mod_in_name = false)]
+fn number_sequence(env: &Env, from: i64, to: i64, step: Option<i64>) -> Result<Value<'_>> {
+    env.number_sequence(from, to, step)
+}
+
+/// Return the integer value of KEY in PLIST, or nil if it is absent.
+#[defun(mod_in_name = false)]
+fn plist_get_int(plist: Value, key: String) -> Result<Option<i64>> {
+    plist.plist_get(&key)
+}
+
+/// Return whether KEY is present in PLIST, and its value, if any.
+#[defun(mod_in_name = false)]
+fn plist_get<'e>(plist: Value<'e>, key: String) -> Result<(bool, Option<Value<'e>>)> {
+    let value: Option<Value> = plist.plist_get(&key)?;
+    Ok((value.is_some(), value))
+}
+
+/// Return whether the string KEY is present in ALIST, and its value, if any.
+#[defun(mod_in_name = false)]
+fn alist_get<'e>(alist: Value<'e>, key: String) -> Result<(bool, Option<Value<'e>>)> {
+    let value: Option<Value> = alist.alist_get(key)?;
+    Ok((value.is_some(), value))
+}
+
+/// Return the pairs in PLIST, as an alist.
+#[defun(mod_in_name = false)]
+fn plist_to_alist<'e>(env: &'e Env, plist: Value<'e>) -> Result<Value<'e>> {
+    let pairs = plist.plist_to_vec()?;
+    let pairs = pairs.into_iter().map(|(k, v)| env.cons(k, v)).collect::<Result<Vec<_>>>()?;
+    env.list(&pairs)
+}
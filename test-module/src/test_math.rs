@@ -0,0 +1,43 @@
+//! Testing number-related helpers.
+
+use emacs::{defun, Env, Result, Value};
+
+#[defun(mod_in_name = false)]
+fn floor<'e>(env: &'e Env, x: Value<'e>, divisor: Option<Value<'e>>) -> Result<Value<'e>> {
+    env.floor(x, divisor)
+}
+
+#[defun(mod_in_name = false)]
+fn ceiling<'e>(env: &'e Env, x: Value<'e>, divisor: Option<Value<'e>>) -> Result<Value<'e>> {
+    env.ceiling(x, divisor)
+}
+
+#[defun(mod_in_name = false)]
+fn round<'e>(env: &'e Env, x: Value<'e>, divisor: Option<Value<'e>>) -> Result<Value<'e>> {
+    env.round(x, divisor)
+}
+
+#[defun(mod_in_name = false)]
+fn truncate<'e>(env: &'e Env, x: Value<'e>, divisor: Option<Value<'e>>) -> Result<Value<'e>> {
+    env.truncate(x, divisor)
+}
+
+#[defun(mod_in_name = false)]
+fn gcd<'e>(env: &'e Env, a: Value<'e>, b: Value<'e>) -> Result<Value<'e>> {
+    env.gcd(a, b)
+}
+
+#[defun(mod_in_name = false)]
+fn lcm<'e>(env: &'e Env, a: Value<'e>, b: Value<'e>) -> Result<Value<'e>> {
+    env.lcm(a, b)
+}
+
+#[defun(mod_in_name = false)]
+fn expt<'e>(env: &'e Env, base: Value<'e>, power: Value<'e>) -> Result<Value<'e>> {
+    env.expt(base, power)
+}
+
+#[defun(mod_in_name = false)]
+fn isqrt<'e>(env: &'e Env, x: Value<'e>) -> Result<Value<'e>> {
+    env.isqrt(x)
+}
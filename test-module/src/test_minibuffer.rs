@@ -0,0 +1,60 @@
+//! Testing minibuffer-related helpers.
+
+use emacs::{defun, Env, Result, Value};
+
+#[defun(mod_in_name = false)]
+fn minibufferp(env: &Env) -> Result<bool> {
+    env.minibufferp()
+}
+
+#[defun(mod_in_name = false)]
+fn abort_recursive_edit(env: &Env) -> Result<()> {
+    env.abort_recursive_edit()
+}
+
+#[defun(mod_in_name = false)]
+fn read_string(
+    env: &Env,
+    prompt: String,
+    initial: Option<String>,
+    history: Option<String>,
+    default: Option<String>,
+) -> Result<String> {
+    env.read_string(&prompt, initial.as_deref(), history.as_deref(), default.as_deref())
+}
+
+#[defun(mod_in_name = false)]
+fn add_to_history(
+    env: &Env,
+    history_var: String,
+    value: String,
+    maxelt: Option<i64>,
+) -> Result<Value<'_>> {
+    env.add_to_history(&history_var, &value, maxelt)
+}
+
+#[defun(mod_in_name = false)]
+fn read_number(env: &Env, prompt: String, default: Option<f64>) -> Result<f64> {
+    env.read_number(&prompt, default)
+}
+
+/// Read one of the strings in COLLECTION, requiring a match if REQUIRE-MATCH is non-nil.
+#[defun(mod_in_name = false)]
+fn completing_read<'e>(
+    env: &'e Env,
+    prompt: String,
+    collection: Value<'e>,
+    require_match: bool,
+) -> Result<String> {
+    env.completing_read(&prompt, collection, require_match)
+}
+
+/// Ask for a fruit and a quantity, then report the order.
+#[defun(mod_in_name = false, interactive)]
+fn order_fruit(env: &Env) -> Result<String> {
+    let fruit = env.completing_read("Fruit: ", ("apple", "banana", "cherry"), true)?;
+    let quantity = env.read_number("Quantity: ", Some(1.0))?;
+    let order = format!("{} x {}", quantity, fruit);
+    env.message(&order)?;
+    Ok(order)
+}
@@ -0,0 +1,12 @@
+//! Testing communication with subprocesses.
+
+use emacs::{defun, Env, Result, Value};
+
+/// Send each string in STRINGS to PROCESS, then send an end-of-file.
+#[defun(mod_in_name = false)]
+fn process_send_all<'e>(env: &'e Env, process: Value<'e>, strings: Vec<String>) -> Result<Value<'e>> {
+    for s in strings {
+        env.process_send_string(process, &s)?;
+    }
+    env.process_send_eof(process)
+}
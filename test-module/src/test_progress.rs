@@ -0,0 +1,14 @@
+//! Testing progress reporters.
+
+use emacs::{defun, Env, Result, Value};
+
+/// Report progress from 0 to MAX, returning the underlying Lisp progress reporter.
+#[defun(mod_in_name = false)]
+fn report_progress(env: &Env, message: String, max: i64) -> Result<Value<'_>> {
+    let reporter = env.make_progress_reporter(&message, 0, max)?;
+    for i in 0..=max {
+        reporter.update(i)?;
+    }
+    reporter.done()?;
+    Ok(reporter.value())
+}
@@ -0,0 +1,26 @@
+//! Testing cooperative quitting.
+
+use emacs::{defun, Env, ProcessInputResult, Result, Value};
+
+/// Count to 1000, checking for quits with `process_input' if PROCESS-INPUT is non-nil, or with
+/// `should_quit' otherwise. Set `quit-flag' to simulate `C-g' when reaching QUIT-AT.
+#[defun(mod_in_name = false)]
+fn count_until_quit(env: &Env, quit_at: i64, process_input: Value) -> Result<i64> {
+    for i in 0.. {
+        if i == quit_at {
+            env.call("set", (env.intern("quit-flag")?, true))?;
+        }
+        let quit = if process_input.is_not_nil() {
+            env.process_input()? == ProcessInputResult::Quit
+        } else {
+            env.should_quit()
+        };
+        if quit {
+            return env.signal_quit();
+        }
+        if i >= 1000 {
+            return Ok(i);
+        }
+    }
+    unreachable!()
+}
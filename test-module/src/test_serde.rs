@@ -0,0 +1,90 @@
+//! Testing conversion between serde-compatible types and Lisp values.
+
+use std::collections::BTreeMap;
+
+use emacs::serde::{MapRepr, SerOptions, SeqRepr};
+use emacs::{defun, Env, Result, Value};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Point {
+    x: i64,
+    y: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Segment(Point, Point),
+    Rect { width: u32, height: u32 },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Drawing {
+    name: String,
+    label: Option<String>,
+    shapes: Vec<Shape>,
+    tags: BTreeMap<String, char>,
+}
+
+fn drawing() -> Drawing {
+    let mut tags = BTreeMap::new();
+    tags.insert("a".to_owned(), 'a');
+    tags.insert("b".to_owned(), 'b');
+    Drawing {
+        name: "d".to_owned(),
+        label: None,
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Segment(Point { x: 0, y: 0.5 }, Point { x: 1, y: 2.0 }),
+            Shape::Rect { width: 3, height: 4 },
+        ],
+        tags,
+    }
+}
+
+fn options(alist_vector: Value) -> SerOptions {
+    if alist_vector.is_not_nil() {
+        SerOptions { maps: MapRepr::Alist, sequences: SeqRepr::Vector }
+    } else {
+        SerOptions::default()
+    }
+}
+
+/// Return a sample drawing converted by serde. If ALIST-VECTOR is non-nil, convert maps into alists
+/// and sequences into vectors.
+#[defun(mod_in_name = false)]
+fn serde_drawing<'e>(env: &'e Env, alist_vector: Value<'e>) -> Result<Value<'e>> {
+    emacs::serde::to_lisp_with(env, &drawing(), &options(alist_vector))
+}
+
+/// Convert the sample drawing into Lisp and back, with the options selected by ALIST-VECTOR. Return
+/// whether the result is equal to the original.
+#[defun(mod_in_name = false)]
+fn serde_round_trip(env: &Env, alist_vector: Value) -> Result<bool> {
+    let value = emacs::serde::to_lisp_with(env, &drawing(), &options(alist_vector))?;
+    let converted: Drawing = emacs::serde::from_lisp(value)?;
+    Ok(converted == drawing())
+}
+
+/// Parse DRAWING, then convert it back into Lisp, with maps as alists, and sequences as vectors.
+#[defun(mod_in_name = false)]
+fn serde_reparse_drawing(drawing: Value) -> Result<Value> {
+    let parsed: Drawing = emacs::serde::from_lisp(drawing)?;
+    emacs::serde::to_lisp_with(drawing.env, &parsed, &options(drawing.env.intern("t")?))
+}
+
+/// Parse POINT, a plist or an alist, and return its coordinates as a list.
+#[defun(mod_in_name = false)]
+fn serde_point(point: Value) -> Result<(i64, f64)> {
+    let Point { x, y } = emacs::serde::from_lisp(point)?;
+    Ok((x, y))
+}
+
+/// Return a list of the integers from 0 below N, converted by serde.
+#[defun(mod_in_name = false)]
+fn serde_range(env: &Env, n: u32) -> Result<Value<'_>> {
+    emacs::serde::to_lisp(env, &(0..n).collect::<Vec<_>>())
+}
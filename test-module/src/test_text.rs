@@ -0,0 +1,61 @@
+//! Testing string helpers.
+
+use emacs::{defun, Env, FaceSpec, IntoLisp, Result, Value};
+
+#[defun(mod_in_name = false)]
+fn string_trim(env: &Env, s: String) -> Result<String> {
+    env.string_trim(&s)
+}
+
+#[defun(mod_in_name = false)]
+fn split_string<'e>(
+    env: &'e Env,
+    s: String,
+    separators: Value<'e>,
+    omit_nulls: Value<'e>,
+) -> Result<Value<'e>> {
+    let separators: Option<String> = separators.into_rust()?;
+    let parts = env.split_string(&s, separators.as_deref(), omit_nulls.is_not_nil())?;
+    let parts = parts.into_iter().map(|p| p.into_lisp(env)).collect::<Result<Vec<_>>>()?;
+    env.list(&parts)
+}
+
+/// Return a copy of S whose `face' is an anonymous face with the given attributes, and whose
+/// `help-echo' is HELP.
+#[defun(mod_in_name = false)]
+fn propertize_with_face<'e>(
+    env: &'e Env,
+    s: String,
+    help: Value<'e>,
+    foreground: Option<String>,
+    weight: Option<String>,
+    height: Option<f64>,
+) -> Result<Value<'e>> {
+    let face = FaceSpec { foreground, weight, height, ..Default::default() };
+    env.propertized(&s)
+        .with_property_on_all("face", face.into_lisp(env)?)
+        .with_property_on_all("help-echo", help)
+        .build()
+}
+
+/// Return TEXT with text properties given by SPECS, a list of (START END PROPERTY VALUE).
+#[defun(mod_in_name = false)]
+fn propertized<'e>(env: &'e Env, text: String, specs: Vec<Value<'e>>) -> Result<Value<'e>> {
+    let mut builder = env.propertized(&text);
+    for spec in specs {
+        let (start, end, property, value): (usize, usize, Value, Value) = (
+            env.call("nth", (0, spec))?.into_rust()?,
+            env.call("nth", (1, spec))?.into_rust()?,
+            env.call("nth", (2, spec))?,
+            env.call("nth", (3, spec))?,
+        );
+        let property: String = env.call("symbol-name", [property])?.into_rust()?;
+        builder = builder.with_property(start..end, &property, value);
+    }
+    builder.build()
+}
+
+#[defun(mod_in_name = false)]
+fn text_properties_at(s: Value, pos: usize) -> Result<Value> {
+    s.text_properties_at(pos)
+}
@@ -0,0 +1,41 @@
+//! Testing timers backed by Rust closures.
+
+use emacs::{defun, Env, Result, Value};
+
+fn idle_timers(env: &Env) -> Result<Value<'_>> {
+    env.call("symbol-value", [env.intern("timer-idle-list")?])
+}
+
+/// Register an idle timer that calls FUNC, run its function once by hand, then cancel it.
+/// Return whether the timer was active, and whether it was still active after cancellation.
+#[defun(mod_in_name = false)]
+fn idle_timer_lifecycle<'e>(env: &'e Env, func: Value<'e>) -> Result<Value<'e>> {
+    let func = func.make_global_ref();
+    let timer = env.run_with_idle_timer(3600.0, true, move |env| {
+        func.call(env, [])?;
+        Ok(())
+    })?;
+    let before = env.call("memq", (timer.bind(env), idle_timers(env)?))?.is_not_nil();
+    let count: usize = env.call("length", [idle_timers(env)?])?.into_rust()?;
+    env.call(env.call("timer--function", [timer.bind(env)])?, [])?;
+    timer.cancel(env)?;
+    let after: usize = env.call("length", [idle_timers(env)?])?.into_rust()?;
+    env.list((before, after == count))
+}
+
+/// Send each string in STRINGS from another thread, to be pushed onto `t--main-thread-received'
+/// on the Lisp thread, after being shown with `message'.
+#[defun(mod_in_name = false)]
+fn send_from_thread(env: &Env, strings: Vec<String>) -> Result<()> {
+    let sender = env.main_thread_sender()?;
+    std::thread::spawn(move || {
+        for s in strings {
+            sender.send(move |env| {
+                env.message(&s)?;
+                let received = env.get_var::<Value>("t--main-thread-received")?;
+                env.set_var("t--main-thread-received", env.cons(s, received)?)
+            });
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,63 @@
+//! Testing variable-related helpers.
+
+use emacs::{defun, Env, Result, Value};
+
+/// Call F with the variables in BINDINGS, an alist of (NAME . VALUE), bound to the given values.
+#[defun(mod_in_name = false)]
+fn let_bind<'e>(env: &'e Env, bindings: Vec<Value<'e>>, f: Value<'e>) -> Result<Value<'e>> {
+    let names = bindings.iter().map(|b| b.car()).collect::<Result<Vec<String>>>()?;
+    let values = bindings.iter().map(|b| b.cdr()).collect::<Result<Vec<Value>>>()?;
+    let bindings: Vec<(&str, Value)> = names.iter().map(|n| n.as_str()).zip(values).collect();
+    env.let_bind(&bindings, || f.call([]))
+}
+
+/// Bind the variable NAME to VALUE, then panic.
+#[defun(mod_in_name = false)]
+fn let_bind_panicking(env: &Env, name: String, value: Value) -> Result<()> {
+    env.let_bind(&[(&name, value)], || panic!("Panicking while {} is bound", name))
+}
+
+/// Set `this-command' to THIS and `last-command' to LAST, then return their values as a list.
+#[defun(mod_in_name = false)]
+fn set_commands<'e>(env: &'e Env, this: Value<'e>, last: Value<'e>) -> Result<Value<'e>> {
+    env.set_this_command(this)?;
+    env.set_last_command(last)?;
+    env.list((env.this_command()?, env.last_command()?))
+}
+
+/// Return the value of the variable NAME.
+#[defun(mod_in_name = false)]
+fn get_var(env: &Env, name: String) -> Result<Value<'_>> {
+    env.get_var(&name)
+}
+
+/// Set the variable NAME to VALUE, then return its value, which must be an integer.
+#[defun(mod_in_name = false)]
+fn set_int_var(env: &Env, name: String, value: i64) -> Result<i64> {
+    env.set_var(&name, value)?;
+    env.get_var(&name)
+}
+
+/// Make the variable NAME buffer-local, then set it to VALUE.
+#[defun(mod_in_name = false)]
+fn set_local_var(env: &Env, name: String, value: Value) -> Result<()> {
+    env.make_local_var(&name)?;
+    env.set_var(&name, value)
+}
+
+#[defun(mod_in_name = false)]
+fn defvar<'e>(env: &'e Env, name: String, default: Value<'e>, doc: String) -> Result<Value<'e>> {
+    env.defvar(&name, default, &doc)
+}
+
+#[defun(mod_in_name = false)]
+fn defcustom<'e>(
+    env: &'e Env,
+    name: String,
+    default: Value<'e>,
+    doc: String,
+    group: String,
+    custom_type: String,
+) -> Result<Value<'e>> {
+    env.defcustom(&name, default, &doc, &group, &custom_type)
+}
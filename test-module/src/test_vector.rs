@@ -17,6 +17,40 @@ fn vec_set(v: Vector, i: i64, value: Value) -> Result<()> {
     v.set(i as usize, value)
 }
 
+#[defun(mod_in_name = false)]
+fn value_vec_len(v: Value) -> Result<usize> {
+    v.vec_len()
+}
+
+#[defun(mod_in_name = false)]
+fn value_vec_get(v: Value, i: usize) -> Result<Value> {
+    v.vec_get(i)
+}
+
+#[defun(mod_in_name = false)]
+fn value_vec_set(v: Value, i: usize, value: Value) -> Result<()> {
+    v.vec_set(i, value)
+}
+
+/// Return the sum of the numbers in vector V, and their count.
+#[defun(mod_in_name = false)]
+fn value_vec_sum(v: Value) -> Result<(f64, usize)> {
+    let iter = v.vec_iter()?;
+    let count = iter.len();
+    let mut sum = 0.0;
+    for item in iter {
+        sum += item?.into_rust::<f64>()?;
+    }
+    Ok((sum, count))
+}
+
+/// Return a list of the elements of vector V, in order.
+#[defun(mod_in_name = false)]
+fn value_vec_to_list(v: Value) -> Result<Value> {
+    let items = v.vec_iter()?.collect::<Result<Vec<_>>>()?;
+    v.env.list(&items)
+}
+
 #[defun(mod_in_name = false)]
 fn identity_if_vector(v: Vector) -> Result<Vector> {
     Ok(v)
@@ -0,0 +1,38 @@
+;;; Benchmarks, which are not part of the test suite run by `bin/test', since they only report
+;;; timings. Run them with `bin/bench'.
+
+(require 'benchmark)
+(require 'subr-x)
+
+(require 't)
+
+(defvar t/bench-n 100000
+  "The number of iterations each benchmark runs.")
+
+(defun t/bench-compare (what &rest variants)
+  "Time each of VARIANTS, a plist of LABEL FUNCTION, then report the timings.
+Each FUNCTION is called once with `t/bench-n' as argument."
+  (let (timings)
+    (while variants
+      (let ((label (pop variants))
+            (f (pop variants)))
+        (push (format "%.3fs %s" (car (benchmark-run 1 (funcall f t/bench-n))) label) timings)))
+    (message "%s %d times: %s" what t/bench-n (string-join (nreverse timings) ", "))))
+
+(ert-deftest bench::insert-lines ()
+  (t/bench-compare
+   "Inserting lines"
+   "with undo" (lambda (n) (with-temp-buffer (buffer-enable-undo) (t/insert-lines n nil)))
+   "without undo" (lambda (n) (with-temp-buffer (buffer-enable-undo) (t/insert-lines n t)))))
+
+(ert-deftest bench::call-lisp-fn ()
+  (t/bench-compare
+   "Calling `+'"
+   "by name" #'t/call-sum-by-name
+   "cached" #'t/call-sum-cached))
+
+(ert-deftest bench::intern ()
+  (t/bench-compare
+   "Interning a symbol"
+   "uncached" (lambda (n) (t/call-intern-repeatedly "t--interned" n nil))
+   "cached" (lambda (n) (t/call-intern-repeatedly "t--interned" n t))))
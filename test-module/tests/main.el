@@ -1,3 +1,4 @@
+(require 'cl-lib)
 (require 'subr-x)
 (require 'help)
 
@@ -20,6 +21,11 @@
          (s (help-split-fundoc docstring sym)))
     (car s)))
 
+(defun t/same-elements-p (a b)
+  "Return non-nil if lists A and B have the same elements (compared with `eq'), in any order."
+  (and (= (length a) (length b))
+       (cl-every (lambda (x) (memq x b)) a)))
+
 (defun t/run-in-sub-process (f-symbol)
   (let* ((default-directory (getenv "PROJECT_ROOT"))
          (name (symbol-name f-symbol))
@@ -53,6 +59,24 @@
     (unless (= exit-code 0)
       (error "Exit code: %s. Error: %s" exit-code error-string))))
 
+;;; ----------------------------------------------------------------------------
+;;; Emacs version.
+
+(ert-deftest version::detection ()
+  (should (= (t/emacs-major-version) emacs-major-version))
+  (should (t/emacs-version-at-least emacs-major-version emacs-minor-version))
+  (should (t/emacs-version-at-least emacs-major-version 0))
+  (should (t/emacs-version-at-least (1- emacs-major-version) 99))
+  (should (t/emacs-version-at-least 25 0))
+  (should-not (t/emacs-version-at-least emacs-major-version (1+ emacs-minor-version)))
+  (should-not (t/emacs-version-at-least (1+ emacs-major-version) 0)))
+
+(ert-deftest version::module-api ()
+  (should (equal (t/emacs-version) (list emacs-major-version emacs-minor-version)))
+  (pcase-let ((`(,api-version ,size) (t/module-api-info)))
+    (should (<= api-version emacs-major-version))
+    (should (> size 0))))
+
 ;;; ----------------------------------------------------------------------------
 ;;; Type conversion.
 
@@ -74,12 +98,100 @@
   (should (string-match-p
            "out of range"
            (cadr (should-error (t/u64-overflow) :type 'rust-error))))
-  (should (string-match-p
-           "out of range"
-           (cadr (should-error (t/identity-i8 128) :type 'rust-error))))
-  (should (string-match-p
-           "out of range"
-           (cadr (should-error (t/identity-u8 -1) :type 'rust-error)))))
+  ;; Narrowing conversions signal with the offending value, and the expected range.
+  (should (equal (should-error (t/identity-i8 128) :type 'args-out-of-range)
+                 '(args-out-of-range 128 -128 127)))
+  (should (equal (should-error (t/identity-u8 -1) :type 'args-out-of-range)
+                 '(args-out-of-range -1 0 255)))
+  (should (equal (error-message-string (should-error (t/identity-u8 256)))
+                 "Args out of range: 256, 0, 255"))
+  (let ((err (should-error (t/identity-u64 -1) :type 'args-out-of-range)))
+    (should (equal (seq-take err 3) '(args-out-of-range -1 0)))
+    (when (>= emacs-major-version 27)
+      (should (= (nth 3 err) (1- (expt 2 64)))))))
+
+(ert-deftest conversion::f32 ()
+  (should (= (t/identity-f32 1.5) 1.5))
+  (should (= (t/identity-f32 -0.25) -0.25))
+  ;; Not exactly representable, so rounded to the nearest `f32'.
+  (should (= (t/identity-f32 0.1) 0.10000000149011612))
+  (should (= (t/identity-f32 16777217.0) 16777216.0))
+  ;; The result is exactly representable, so it round-trips.
+  (should (= (t/identity-f32 (t/identity-f32 0.1)) 0.10000000149011612))
+  (should (= (t/identity-f32 1e300) 1.0e+INF))
+  (should (= (t/identity-f32 -1e300) -1.0e+INF))
+  (should (isnan (t/identity-f32 0.0e+NaN)))
+  (should-error (t/identity-f32 1) :type 'wrong-type-argument))
+
+(ert-deftest conversion::equality ()
+  (should (equal (t/equality 'a 'a) '(t t t)))
+  (should (equal (t/equality 1 1) '(t t t)))
+  (should (equal (t/equality (+ 1.0 0.5) (* 1.5 1)) '(nil t t)))
+  (should (equal (t/equality 1 1.0) '(nil nil nil)))
+  (should (equal (t/equality "abc" (copy-sequence "abc")) '(nil nil t)))
+  (should (equal (t/equality (list 1 '(2)) (list 1 '(2))) '(nil nil t)))
+  (should (equal (t/equality "abc" "abd") '(nil nil nil)))
+  (let ((a (list 1))
+        (b (list 1)))
+    (setcdr a a)
+    (setcdr b b)
+    ;; `circular-list' since Emacs 27, a stack overflow error before.
+    (should-error (t/equality a b) :type 'error)))
+
+(ert-deftest conversion::bool ()
+  (should (eq (t/identity-bool nil) nil))
+  (should (eq (t/identity-bool t) t))
+  ;; Every non-nil value is true, as in Lisp.
+  (dolist (v (list 0 "" [] 'foo (list nil)))
+    (should (eq (t/identity-bool v) t))))
+
+(ert-deftest conversion::expect ()
+  (should (= (t/expect-u8 5 "count") 5))
+  (should (equal (should-error (t/expect-u8 "abc" "count") :type 'wrong-type-argument)
+                 '(wrong-type-argument "count" "\"abc\"")))
+  ;; Other conversion failures are also reported as `wrong-type-argument'.
+  (should (equal (should-error (t/expect-u8 256 "count") :type 'wrong-type-argument)
+                 '(wrong-type-argument "count" "256"))))
+
+(ert-deftest conversion::char ()
+  (should (eq (t/next-char ?a) ?b))
+  (should (eq (t/next-char ?é) ?ê))
+  (should (eq (t/next-char #x10FFFE) #x10FFFF))
+  (should (eq (t/next-char 0) 1))
+  ;; The next code point is a surrogate.
+  (should-not (t/next-char #xD7FF))
+  (should-error (t/next-char "a") :type 'wrong-type-argument)
+  (should (equal (should-error (t/next-char -1) :type 'wrong-type-argument)
+                 '(wrong-type-argument characterp -1)))
+  (should-error (t/next-char (1+ (max-char))) :type 'wrong-type-argument)
+  ;; Characters that are not Unicode scalar values.
+  (should-error (t/next-char #xD800) :type 'rust-error)
+  (should-error (t/next-char #x110000) :type 'rust-error)
+  (should-error (t/next-char (unibyte-char-to-multibyte #xFF)) :type 'rust-error))
+
+(ert-deftest conversion::big-integers ()
+  (should (= (t/identity-i128 5) 5))
+  (should (= (t/identity-u128 0) 0))
+  (should (= (t/factorial 20) 2432902008176640000))
+  (should-error (t/identity-u128 -1) :type 'args-out-of-range)
+  (if (< emacs-major-version 27)
+      (should-error (t/factorial 21) :type 'rust-error)
+    (let ((i64-max (1- (expt 2 63)))
+          (i64-min (- (expt 2 63))))
+      ;; Boundaries of the fast path through `i64'.
+      (dolist (i (list i64-max (1+ i64-max) i64-min (1- i64-min)
+                       most-positive-fixnum (1+ most-positive-fixnum)))
+        (should (= (t/identity-i128 i) i)))
+      (should (= (t/identity-u128 (1+ i64-max)) (1+ i64-max)))
+      (should (= (t/identity-i128 (1- (expt 2 127))) (1- (expt 2 127))))
+      (should (= (t/identity-i128 (- (expt 2 127))) (- (expt 2 127))))
+      (should (= (t/identity-u128 (1- (expt 2 128))) (1- (expt 2 128))))
+      (should (equal (should-error (t/identity-i128 (expt 2 127)) :type 'args-out-of-range)
+                     (list 'args-out-of-range (expt 2 127) (- (expt 2 127)) (1- (expt 2 127)))))
+      (should-error (t/identity-u128 (expt 2 128)) :type 'args-out-of-range)
+      (should-error (t/identity-u128 (- (expt 2 64))) :type 'args-out-of-range)
+      (should (= (t/factorial 34) (seq-reduce #'* (number-sequence 1 34) 1)))
+      (should-error (t/factorial 35) :type 'overflow-error))))
 
 (ert-deftest conversion::passthrough ()
   (let ((x "x"))
@@ -88,6 +200,39 @@
     (should (string-match-p (regexp-quote "Return the input (not a copy).")
                             (documentation #'t/identity) ))))
 
+(ert-deftest conversion::lisp-type-of ()
+  (should (equal (t/lisp-type-of 'a) "symbol"))
+  (should (equal (t/lisp-type-of nil) "symbol"))
+  (should (equal (t/lisp-type-of 5) "integer"))
+  (should (equal (t/lisp-type-of 5.0) "float"))
+  (should (equal (t/lisp-type-of "a") "string"))
+  (should (equal (t/lisp-type-of '(1 . 2)) "cons"))
+  (should (equal (t/lisp-type-of [1 2]) "vector"))
+  (should (equal (t/lisp-type-of (make-hash-table)) "hash-table"))
+  (should (equal (t/lisp-type-of (t/vector-make 1 2)) "user-ptr"))
+  (should (equal (t/lisp-type-of (symbol-function 'car)) "function"))
+  (when (>= emacs-major-version 27)
+    (should (equal (t/lisp-type-of (symbol-function 't/inc)) "function")))
+  (should (equal (t/lisp-type-of (current-buffer)) "buffer"))
+  (should (equal (t/lisp-type-of (point-marker)) "marker")))
+
+(ert-deftest conversion::type-predicates ()
+  (should (equal (t/type-predicates "a") '(string)))
+  (should (equal (t/type-predicates 5) '(integer)))
+  (when (>= emacs-major-version 27)
+    (should (equal (t/type-predicates (expt 2 100)) '(integer))))
+  (should (equal (t/type-predicates 5.0) '(float)))
+  (should (equal (t/type-predicates '(1 . 2)) '(cons)))
+  (should (equal (t/type-predicates [1 2]) '(vector)))
+  (should (equal (t/type-predicates (current-buffer)) '(buffer)))
+  (should (equal (t/type-predicates #'car) '(function)))
+  (should (equal (t/type-predicates (symbol-function 'car)) '(function)))
+  ;; Interpreted closures are lists before Emacs 30.
+  (should (memq 'function (t/type-predicates (lambda (x) x))))
+  (should (equal (t/type-predicates 'if) nil))
+  (should (equal (t/type-predicates nil) nil))
+  (should (equal (t/type-predicates (make-hash-table)) nil)))
+
 (ert-deftest conversion::string ()
   (should (equal (t/to-uppercase "abc") "ABC"))
   ;; copy_string_contents copies the null terminator.
@@ -102,6 +247,131 @@
   (should (equal (t/to-lowercase-or-nil nil) nil))
   (should-error (t/to-lowercase-or-nil 1) :type 'wrong-type-argument))
 
+(ert-deftest conversion::option-from-plist ()
+  (should (equal (t/plist-get-inc '(:a 1 :b 2) :b) 3))
+  (should (equal (t/plist-get-inc '(:a 1 :b nil) :b) nil))
+  (should (equal (t/plist-get-inc '(:a 1) :b) nil))
+  (should-error (t/plist-get-inc '(:a "1") :a) :type 'wrong-type-argument))
+
+(ert-deftest conversion::list-to-vec ()
+  (should (equal (t/join-strings '("a" "b" "c") ", ") "a, b, c"))
+  (should (equal (t/join-strings nil ", ") ""))
+  (should (= (t/sum-integers '(1 2 3)) 6))
+  (should (= (t/sum-integers '()) 0))
+  (should-error (t/sum-integers '(1 "2")) :type 'wrong-type-argument)
+  (should-error (t/sum-integers '(1 2 . 3)) :type 'wrong-type-argument)
+  (should-error (t/sum-integers [1 2]) :type 'wrong-type-argument)
+  (let ((circular (list 1 2 3)))
+    (setcdr (last circular) circular)
+    (should-error (t/sum-integers circular) :type 'circular-list))
+  (should-error (t/join-strings "abc" "") :type 'wrong-type-argument))
+
+(ert-deftest conversion::bytes ()
+  (let ((s (t/bytes-to-string '(0 97 255 10 0))))
+    (should-not (multibyte-string-p s))
+    (should (equal s "\0a\377\n\0"))
+    (should (equal (t/string-to-bytes s) [0 97 255 10 0]))
+    (should (equal (t/bytes-to-string s) s)))
+  ;; Multibyte strings have no canonical bytes.
+  (should-error (t/bytes-to-string "é") :type 'wrong-type-argument)
+  (should (equal (t/bytes-to-string nil) ""))
+  (should (equal (t/string-to-bytes "") []))
+  (should (equal (t/string-to-bytes (string-to-multibyte "abc")) [97 98 99]))
+  (should (equal (t/string-to-bytes (encode-coding-string "é" 'utf-8)) [195 169]))
+  (should-error (t/string-to-bytes "é") :type 'rust-error)
+  (should-error (t/string-to-bytes 5) :type 'wrong-type-argument)
+  (let ((s (apply #'unibyte-string (number-sequence 0 255))))
+    (should (equal (t/bytes-to-string (append (t/string-to-bytes s) nil)) s))))
+
+(ert-deftest conversion::with-str-bytes ()
+  (should (equal (t/str-byte-lengths "abc" "") '(3 0 "a")))
+  (should (equal (t/str-byte-lengths "" "éa") '(0 3 nil)))
+  (should (equal (t/str-byte-lengths "a\0b" (make-string 10000 ?x)) '(3 10000 "a")))
+  ;; The reused buffer is not truncated by a previous, longer string.
+  (should (equal (t/str-byte-lengths "é" "x") '(2 1 "é")))
+  ;; Trailing null bytes are stripped, as when converting into `String'.
+  (should (equal (t/str-byte-lengths "a\0\0" "\0") '(1 0 "a")))
+  ;; A buffer too large to be kept.
+  (should (equal (t/str-byte-lengths (make-string 2000000 ?x) "ab") '(2000000 2 "x")))
+  (should-error (t/str-byte-lengths 5 "x") :type 'wrong-type-argument)
+  (should-error (t/str-byte-lengths "x" 'x) :type 'wrong-type-argument))
+
+(ert-deftest conversion::hash-map ()
+  (let ((table (t/string-lengths '("a" "bcd" "éf"))))
+    (should (hash-table-p table))
+    (should (eq (hash-table-test table) 'equal))
+    (should (= (hash-table-count table) 3))
+    (should (= (gethash "a" table) 1))
+    (should (= (gethash "bcd" table) 3))
+    (should (= (gethash "éf" table) 2))
+    (should (= (t/sum-hash-table-values table) 6)))
+  (should (= (hash-table-count (t/string-lengths nil)) 0))
+  (should (= (t/sum-hash-table-values (make-hash-table)) 0))
+  (should-error (t/sum-hash-table-values '(("a" . 1))) :type 'wrong-type-argument)
+  (let ((table (make-hash-table :test 'equal)))
+    (puthash 'a 1 table)
+    (should-error (t/sum-hash-table-values table) :type 'wrong-type-argument)))
+
+(ert-deftest conversion::tuple ()
+  (should (equal (t/tuple-to-list 1 2 "a") '(1 2 (1 . 2) "a")))
+  ;; Rust strings with null bytes cannot be converted.
+  (should-error (t/tuple-to-list 1 2 "a\0b") :type 'rust-error))
+
+(ert-deftest conversion::time ()
+  (skip-unless (>= emacs-major-version 27))
+  (let ((now (current-time)))
+    (should (time-equal-p (t/time-identity now) now)))
+  (should (consp (t/time-identity nil)))
+  (should (equal (t/time-identity '(1 . 1000)) '(1000000 . 1000000000)))
+  ;; Old list form (HIGH LOW USEC PSEC).
+  (should (equal (t/time-identity '(0 1 2 3000)) '(1000002003 . 1000000000)))
+  ;; Before the epoch.
+  (should (equal (t/time-identity -1.5) '(-1500000000 . 1000000000)))
+  (should (equal (t/duration-parts '(3 . 2)) '(1 500000000)))
+  (should (equal (t/duration-parts 2.25) '(2 250000000)))
+  (should (equal (t/duration-parts '(0 1 2 3000)) '(1 2003)))
+  (should-error (t/duration-parts -1) :type 'rust-error)
+  (should (equal (t/make-duration 1 5) '(1000000005 . 1000000000))))
+
+(ert-deftest conversion::path ()
+  (should (equal (t/path-join "a" "b") "a/b"))
+  (should (equal (t/path-join "/tmp" "é") "/tmp/é"))
+  (should (equal (t/path-join "a" "/b") "/b"))
+  (should-not (t/path-absolute-p "a/b"))
+  (should (t/path-absolute-p (expand-file-name "a")))
+  (should-error (t/path-join 1 "a") :type 'wrong-type-argument))
+
+(ert-deftest conversion::path-coding ()
+  (when (eq system-type 'windows-nt)
+    (ert-skip "File names are not byte sequences on Windows"))
+  (let ((file-name-coding-system 'utf-8-unix))
+    (should (equal (t/path-bytes "é") (encode-coding-string "é" 'utf-8)))
+    ;; Invalid UTF-8 bytes are kept as raw bytes.
+    (let ((name (decode-coding-string "\377a" 'utf-8)))
+      (should (equal (t/path-bytes name) "\377a"))
+      (should (equal (t/path-join name "b") (concat name "/b")))))
+  (let ((file-name-coding-system 'latin-1))
+    (should (equal (t/path-bytes "é") "\351"))
+    (should (equal (t/path-join "é" "b") "é/b"))))
+
+(ert-deftest conversion::hash-table ()
+  (let ((table (t/make-hash-table nil '(("a" . 1) ("b" . nil) ("c" . 3)) "c")))
+    (should (eq (hash-table-test table) 'equal))
+    (should (= (hash-table-count table) 2))
+    (should (equal (t/hash-table-lookup table "a") '(t . 1)))
+    (should (equal (t/hash-table-lookup table "b") '(t . nil)))
+    (should (null (t/hash-table-lookup table "c")))
+    (should (null (t/hash-table-lookup table 'a)))
+    (let ((entries (t/hash-table-entries table)))
+      (should (= (cdr entries) 2))
+      (should (equal (sort (car entries) (lambda (x y) (string< (car x) (car y))))
+                     '(("a" . 1) ("b" . nil))))))
+  (let ((table (t/make-hash-table t '(("a" . 1)) nil)))
+    (should (eq (hash-table-test table) 'eq))
+    (should (null (t/hash-table-lookup table (copy-sequence "a")))))
+  (should (equal (t/hash-table-entries (make-hash-table)) '(nil . 0)))
+  (should-error (t/hash-table-entries '((a . 1))) :type 'wrong-type-argument))
+
 (ert-deftest conversion::vector-functions ()
   (should (equal (t/make-vector 5 nil) (make-vector 5 nil)))
   (let ((v [0 1 2 3]))
@@ -120,6 +390,998 @@
     (should (equal v ["0" "1" "2" "3"]))
     (should-error (t/stringify-num-vector v) :type 'wrong-type-argument)))
 
+(ert-deftest conversion::value-vector-functions ()
+  (let ((v (make-vector 3 0)))
+    (should (= 3 (t/value-vec-len v)))
+    (t/value-vec-set v 1 "a")
+    (should (equal v [0 "a" 0]))
+    (should (equal "a" (t/value-vec-get v 1)))
+    (should-error (t/value-vec-get v 3) :type 'args-out-of-range)
+    (should-error (t/value-vec-set v 3 'a) :type 'args-out-of-range))
+  (should-error (t/value-vec-len '(1 2)) :type 'wrong-type-argument)
+  (should-error (t/value-vec-get "abc" 0) :type 'wrong-type-argument)
+  (should-error (t/value-vec-set nil 0 'a) :type 'wrong-type-argument))
+
+(ert-deftest conversion::value-vector-iteration ()
+  (should (equal (t/value-vec-to-list [a "b" 3 (4)]) '(a "b" 3 (4))))
+  (should (equal (t/value-vec-to-list []) nil))
+  (should (equal (t/value-vec-sum [1.5 2.0 3.0]) '(6.5 3)))
+  (should (equal (t/value-vec-sum []) '(0.0 0)))
+  (should-error (t/value-vec-sum [1.0 a]) :type 'wrong-type-argument)
+  (should-error (t/value-vec-to-list '(1 2)) :type 'wrong-type-argument))
+
+(ert-deftest conversion::serde ()
+  (should (equal (t/serde-drawing t)
+                 '(:name "d"
+                   :label nil
+                   :shapes [Empty
+                            ((Circle . 1.5))
+                            ((Segment . [(:x 0 :y 0.5) (:x 1 :y 2.0)]))
+                            ((Rect :width 3 :height 4))]
+                   :tags (("a" . ?a) ("b" . ?b)))))
+  (let* ((drawing (t/serde-drawing nil))
+         (shapes (plist-get drawing :shapes))
+         (tags (plist-get drawing :tags)))
+    (should (equal (plist-get drawing :name) "d"))
+    (should (plist-member drawing :label))
+    (should (eq (nth 0 shapes) 'Empty))
+    (should (equal (gethash 'Circle (nth 1 shapes)) 1.5))
+    (should (equal (gethash 'Segment (nth 2 shapes)) '((:x 0 :y 0.5) (:x 1 :y 2.0))))
+    (should (equal (gethash 'Rect (nth 3 shapes)) '(:width 3 :height 4)))
+    (should (= (hash-table-count tags) 2))
+    (should (eq (gethash "b" tags) ?b)))
+  (should (= (length (t/serde-range 100000)) 100000))
+  (should (equal (t/serde-range 3) '(0 1 2)))
+  (should (equal (t/serde-range 0) nil)))
+
+(ert-deftest conversion::serde-deserialize ()
+  (should (t/serde-round-trip nil))
+  (should (t/serde-round-trip t))
+  (should (equal (t/serde-point '(:x 1 :y 2.5)) '(1 2.5)))
+  (should (equal (t/serde-point '((y . 2) (x . -1))) '(-1 2.0)))
+  (should (equal (t/serde-point #s(hash-table test equal data ("x" 3 "y" 0.5))) '(3 0.5)))
+  ;; Lists and vectors, symbols and hash tables are interchangeable.
+  (should (equal (t/serde-reparse-drawing
+                  '(:name "e"
+                    :shapes (Empty
+                             "Empty"
+                             ((Circle . 2))
+                             #s(hash-table data (Segment [(:x 0 :y 0) (:x 1 :y 1.0)]))
+                             ((Rect . ((width . 1) (height . 2)))))
+                    :tags ((:c . ?c))))
+                 '(:name "e"
+                   :label nil
+                   :shapes [Empty
+                            Empty
+                            ((Circle . 2.0))
+                            ((Segment . [(:x 0 :y 0.0) (:x 1 :y 1.0)]))
+                            ((Rect :width 1 :height 2))]
+                   :tags (("c" . ?c)))))
+  (let ((err (should-error (t/serde-point '(:x "1" :y 2.5)) :type 'rust-error)))
+    (should (equal (cadr err) "expected integer, found string at :x")))
+  (let ((err (should-error (t/serde-reparse-drawing
+                            '(:name "e" :shapes (Empty ((Rect :width 1 :height -2))) :tags nil))
+                           :type 'rust-error)))
+    (should (equal (cadr err) "integer out of range for u32 at :shapes[1] Rect :height")))
+  (should-error (t/serde-point '(:x 1)) :type 'rust-error)
+  (should-error (t/serde-point '(:x 1 :y)) :type 'rust-error)
+  (should-error (t/serde-point [1 2]) :type 'rust-error))
+
+;;; ----------------------------------------------------------------------------
+;;; Coding systems.
+
+(ert-deftest coding::round-trip ()
+  (dolist (coding '("utf-8" "latin-1"))
+    (should (equal (t/coding-round-trip "café" coding) "café"))
+    (should (equal (t/coding-round-trip "" coding) ""))))
+
+(ert-deftest coding::bytes ()
+  (should (equal (t/encode-coding-string "café" "utf-8") [99 97 102 195 169]))
+  (should (equal (t/encode-coding-string "café" "latin-1") [99 97 102 233]))
+  (should (equal (t/encode-coding-string "a\0b" "utf-8") [97 0 98]))
+  (should (equal (t/decode-coding-string [99 97 102 233] "latin-1") "café"))
+  (should (equal (t/decode-coding-string [99 97 102 195 169] "utf-8") "café"))
+  (should-error (t/encode-coding-string "x" "no-such-coding") :type 'coding-system-error))
+
+(ert-deftest coding::with-coding-system ()
+  (let ((file (make-temp-file "emacs-module-rs-")))
+    (unwind-protect
+        (progn
+          (let ((coding-system-for-write 'no-conversion))
+            (write-region (unibyte-string 99 97 102 233) nil file nil 'no-message))
+          (should (equal (t/with-coding-system
+                          "latin-1"
+                          (lambda ()
+                            (with-temp-buffer
+                              (insert-file-contents file)
+                              (list (buffer-string) coding-system-for-write))))
+                         '("café" latin-1)))
+          (should (equal (with-temp-buffer
+                           (set-buffer-multibyte nil)
+                           (t/with-coding-system
+                            "latin-1"
+                            (lambda ()
+                              (write-region "déjà" nil file nil 'no-message)))
+                           (insert-file-contents-literally file)
+                           (append (buffer-string) nil))
+                         '(100 233 106 224)))
+          (should-not coding-system-for-read))
+      (delete-file file)))
+  (should-error (t/with-coding-system "no-such-coding" #'ignore) :type 'coding-system-error))
+
+;;; ----------------------------------------------------------------------------
+;;; Display.
+
+(ert-deftest display::window-text-pixel-size ()
+  (skip-unless (display-graphic-p))
+  (with-temp-buffer
+    (insert "abc\ndef")
+    (set-window-buffer (selected-window) (current-buffer))
+    (let ((expected (window-text-pixel-size nil 1 (point-max))))
+      (should (equal (t/window-text-pixel-size nil 1 (point-max)) expected))
+      (should (equal (t/window-text-pixel-size (selected-window) 1 (point-max)) expected)))
+    (should (equal (t/window-text-pixel-size nil 1 1)
+                   (window-text-pixel-size nil 1 1)))))
+
+(ert-deftest display::save-window-excursion ()
+  (skip-unless (ignore-errors (save-window-excursion (split-window) t)))
+  (delete-other-windows)
+  (let ((window (selected-window)))
+    (should (= (t/split-window-excursion nil) 2))
+    (should (= (count-windows) 1))
+    (should (eq (selected-window) window))
+    (should-error (t/split-window-excursion t) :type 'error)
+    (should (= (count-windows) 1))
+    (should (eq (selected-window) window))
+    (should-error (t/split-window-excursion 'panic) :type 'rust-panic)
+    (should (= (count-windows) 1))
+    (should (eq (selected-window) window))))
+
+(ert-deftest display::overlays ()
+  (with-temp-buffer
+    (insert "0123456789")
+    (let ((o1 (make-overlay 2 5))
+          (o2 (make-overlay 4 8))
+          (o3 (make-overlay 9 9)))
+      (should (equal (t/overlays-at 1) nil))
+      (should (equal (t/overlays-at 2) (list o1)))
+      (should (t/same-elements-p (t/overlays-at 4) (list o1 o2)))
+      (should (equal (t/overlays-at 9) nil))
+      (should (t/same-elements-p (t/overlays-in 1 11) (list o1 o2 o3)))
+      (should (equal (t/overlays-in 5 7) (list o2)))
+      (should (equal (t/overlays-in 1 2) nil))
+      (should (equal (t/overlays-in 9 9) (list o3))))))
+
+(ert-deftest display::make-overlay ()
+  (with-temp-buffer
+    (insert "foo bar foo")
+    (let ((overlays (t/highlight-matches "foo" 'bold)))
+      (should (equal (mapcar (lambda (o) (list (overlay-start o) (overlay-end o))) overlays)
+                     '((1 4) (9 12))))
+      (should (eq (overlay-get (car overlays) 'face) 'bold)))
+    (let ((other (current-buffer))
+          (overlay (t/make-overlay 5 2 nil)))
+      (should (eq (overlay-buffer overlay) (current-buffer)))
+      (should (equal (list (overlay-start overlay) (overlay-end overlay)) '(2 5)))
+      (should (equal (t/move-overlay overlay 12 1) '(1 12)))
+      (should (equal (should-error (t/move-overlay overlay 1 13) :type 'args-out-of-range)
+                     '(args-out-of-range 13 1 12)))
+      ;; Narrowing doesn't restrict overlays.
+      (narrow-to-region 3 4)
+      (should (equal (t/move-overlay overlay 1 12) '(1 12)))
+      (widen)
+      (should (equal (should-error (t/make-overlay 0 1 nil) :type 'args-out-of-range)
+                     '(args-out-of-range 0 1 12)))
+      (with-temp-buffer
+        (should (eq (overlay-buffer (t/make-overlay 1 3 other)) other)))
+      (should-not (t/delete-overlay overlay))
+      (should-not (t/delete-overlay overlay))
+      (should-error (t/move-overlay overlay 1 2) :type 'error)
+      (should-error (t/move-overlay (current-buffer) 1 2) :type 'wrong-type-argument)))
+  (let ((buffer (generate-new-buffer " *dead*")))
+    (kill-buffer buffer)
+    (should-error (t/make-overlay 1 1 buffer) :type 'wrong-type-argument)))
+
+(ert-deftest display::display-table ()
+  (let ((table (t/make-display-table)))
+    (should (char-table-p table))
+    (should (eq (char-table-subtype table) 'display-table))
+    (t/set-display-table-entry table ?a "<A>")
+    (t/set-display-table-entry table ?é "e")
+    (should (equal (aref table ?a) [?< ?A ?>]))
+    (should (equal (aref table ?é) [?e]))
+    (should (null (aref table ?b)))
+    (t/set-display-table-entry table ?a nil)
+    (should (null (aref table ?a)))))
+
+(ert-deftest display::insert-button ()
+  (with-temp-buffer
+    (let* ((clicked nil)
+           (button (t/insert-button "Click me" (lambda (b) (setq clicked b)))))
+      (should (equal (buffer-string) "Click me"))
+      (should (eq (button-at 1) button))
+      (should (equal (button-label button) "Click me"))
+      (button-activate button)
+      (should (eq clicked button)))))
+
+(define-derived-mode t--font-lock-test-mode fundamental-mode "Font-Lock-Test")
+
+(ert-deftest display::font-lock-add-keywords ()
+  (let ((font-lock-keywords-alist nil)
+        (keywords '(("\\_<frobnicate\\_>" . font-lock-keyword-face)))
+        (more-keywords '(("\\_<quux\\_>" . font-lock-builtin-face))))
+    (t/font-lock-add-keywords "t--font-lock-test-mode" keywords nil)
+    (t/font-lock-add-keywords "t--font-lock-test-mode" more-keywords t)
+    (should (equal (cdr (assq 't--font-lock-test-mode font-lock-keywords-alist))
+                   `((,keywords) (,more-keywords . t))))
+    (with-temp-buffer
+      (t--font-lock-test-mode)
+      (insert "frobnicate quux")
+      (font-lock-ensure)
+      (should (eq (get-text-property 1 'face) 'font-lock-keyword-face))
+      (should (eq (get-text-property 12 'face) 'font-lock-builtin-face)))))
+
+(ert-deftest display::insert-image ()
+  (skip-unless (image-type-available-p 'pbm))
+  (with-temp-buffer
+    (t/insert-image-data "P1\n2 2\n1 0\n0 1\n" "pbm" 2.0 "[img]")
+    (should (equal (buffer-string) "[img]"))
+    (let ((image (get-text-property 1 'display)))
+      (should (eq (car image) 'image))
+      (should (eq (image-property image :type) 'pbm))
+      (should (equal (image-property image :data) "P1\n2 2\n1 0\n0 1\n"))
+      (should (equal (image-property image :scale) 2.0)))
+    (erase-buffer)
+    (t/insert-image-data "P1\n1 1\n1\n" "pbm" 1.0)
+    (should (equal (buffer-string) " "))))
+
+;;; ----------------------------------------------------------------------------
+;;; Timers.
+
+(ert-deftest timer::idle-timer-closure ()
+  (let ((count 0))
+    (should (equal (t/idle-timer-lifecycle (lambda () (setq count (1+ count))))
+                   '(t nil)))
+    (should (= count 1)))
+  (should-error (t/idle-timer-lifecycle (lambda () (error "abc"))) :type 'error))
+
+(defvar t--main-thread-received nil)
+
+(ert-deftest timer::main-thread-sender ()
+  (let ((t--main-thread-received nil))
+    (t/send-from-thread '("a" "b" "c"))
+    (with-timeout (5 (ert-fail "Timed out waiting for the closures to be called"))
+      (while (< (length t--main-thread-received) 3)
+        (accept-process-output nil 0.01)))
+    (should (equal t--main-thread-received '("c" "b" "a")))))
+
+;;; ----------------------------------------------------------------------------
+;;; File notifications.
+
+(ert-deftest file-notify::callback ()
+  (require 'filenotify)
+  (skip-unless file-notify--library)
+  (let* ((file (make-temp-file "emacs-module-rs-"))
+         (events nil)
+         (descriptor (t/file-notify-watch file (lambda (event) (push event events)))))
+    (unwind-protect
+        (progn
+          (should (file-notify-valid-p descriptor))
+          (write-region "changed" nil file nil 'no-message)
+          (with-timeout (5 nil)
+            (while (null events)
+              (accept-process-output nil 0.1)))
+          (should events)
+          (should (equal (car (car events)) descriptor)))
+      (t/file-notify-unwatch descriptor)
+      (delete-file file))
+    (should-not (file-notify-valid-p descriptor))))
+
+;;; ----------------------------------------------------------------------------
+;;; Processes.
+
+(ert-deftest process::send-string ()
+  (skip-unless (executable-find "cat"))
+  (let* ((output "")
+         (process (make-process :name "t-cat"
+                                :command '("cat")
+                                :connection-type 'pipe
+                                :noquery t
+                                :filter (lambda (_ s) (setq output (concat output s))))))
+    (unwind-protect
+        (progn
+          (should (eq (t/process-send-all process '("hello\n" "wor" "ld\n")) process))
+          (with-timeout (5 nil)
+            (while (process-live-p process)
+              (accept-process-output process 0.1)))
+          ;; Read any output left after the process exited.
+          (while (accept-process-output process 0.1))
+          (should (equal output "hello\nworld\n"))
+          (should (eq (process-status process) 'exit)))
+      (delete-process process))))
+
+;;; ----------------------------------------------------------------------------
+;;; Buffers.
+
+(ert-deftest buffer::narrowed-p ()
+  (with-temp-buffer
+    (insert "abc\ndef")
+    (should-not (t/buffer-narrowed-p))
+    (narrow-to-region 1 4)
+    (should (t/buffer-narrowed-p))
+    (widen)
+    (should-not (t/buffer-narrowed-p))))
+
+(ert-deftest buffer::line-positions ()
+  (with-temp-buffer
+    (insert "one\ntwo\n\nfour")
+    (goto-char 6)
+    (should (equal (t/line-bounds nil) '(5 8)))
+    (should (equal (t/line-bounds 1) '(5 8)))
+    (should (equal (t/line-bounds 0) '(1 4)))
+    (should (equal (t/line-bounds 2) '(9 9)))
+    (should (equal (t/line-bounds 3) '(10 14)))
+    (should (= (point) 6))))
+
+(ert-deftest buffer::buffer-hash ()
+  (skip-unless (>= emacs-major-version 26))
+  (with-temp-buffer
+    (insert "abc")
+    (let ((hash (t/buffer-hash)))
+      (should (equal hash (buffer-hash)))
+      (should (string-match-p "\\`[0-9a-f]+\\'" hash))
+      (put-text-property 1 2 'face 'bold)
+      (should (equal (t/buffer-hash) hash))
+      (insert "d")
+      (should-not (equal (t/buffer-hash) hash))
+      (delete-char -1)
+      (should (equal (t/buffer-hash (current-buffer)) hash)))))
+
+(ert-deftest buffer::with-buffer ()
+  (let ((other (generate-new-buffer " *other*")))
+    (unwind-protect
+        (with-temp-buffer
+          (let ((original (current-buffer)))
+            (should (eq (t/with-buffer other #'current-buffer) other))
+            (should (eq (current-buffer) original))
+            (should (eq (t/with-buffer (buffer-name other) #'current-buffer) other))
+            (should-error (t/with-buffer other (lambda () (insert "x") (error "Oops")))
+                          :type 'error)
+            (should (eq (current-buffer) original))
+            (should (equal (with-current-buffer other (buffer-string)) "x"))
+            (should (eq (catch 'ball (t/with-buffer other (lambda () (throw 'ball 5)))) 5))
+            (should (eq (current-buffer) original))
+            (should-error (t/with-buffer-panicking other) :type 'rust-panic)
+            (should (eq (current-buffer) original))
+            (should-error (t/with-buffer "no such buffer -- abc" #'ignore) :type 'error)
+            (should (eq (current-buffer) original))))
+      (kill-buffer other))))
+
+(ert-deftest buffer::region-active-p ()
+  (with-temp-buffer
+    (let ((transient-mark-mode t))
+      (insert "abc\ndef")
+      (should-not (t/region-active-p))
+      (set-mark 1)
+      (goto-char 4)
+      (activate-mark)
+      (should (t/region-active-p))
+      (deactivate-mark)
+      (should-not (t/region-active-p)))))
+
+(ert-deftest buffer::with-undo-amalgamate ()
+  (with-temp-buffer
+    (buffer-enable-undo)
+    (insert "x")
+    (undo-boundary)
+    (should (equal (t/insert-amalgamated '("a" "b" "c") nil)
+                   (list nil most-positive-fixnum most-positive-fixnum)))
+    (should (equal (buffer-string) "xabc"))
+    ;; Undo a single step, skipping any leading boundaries.
+    (primitive-undo 1 (seq-drop-while #'null buffer-undo-list))
+    (should (equal (buffer-string) "x"))
+    (undo-boundary)
+    ;; The changes are still combined after a panic.
+    (should-error (t/insert-amalgamated '("d" "e") t) :type 'rust-panic)
+    (should (equal (buffer-string) "xde"))
+    (primitive-undo 1 (seq-drop-while #'null buffer-undo-list))
+    (should (equal (buffer-string) "x"))))
+
+(ert-deftest buffer::with-undo-disabled ()
+  (with-temp-buffer
+    (buffer-enable-undo)
+    (insert "x")
+    (let ((undo-list buffer-undo-list))
+      (should (t/insert-lines 3 t))
+      (should (eq buffer-undo-list undo-list))
+      (should (equal (buffer-substring 2 (point-max)) "Line 0\nLine 1\nLine 2\n"))
+      (should-not (t/insert-lines 1 nil))
+      (should-not (eq buffer-undo-list undo-list)))))
+
+(ert-deftest buffer::yank ()
+  (with-temp-buffer
+    (let* ((interprogram-paste-function nil)
+           (kill-ring (list "c" "b" "a"))
+           (kill-ring-yank-pointer kill-ring))
+      (insert "<>")
+      (backward-char)
+      (t/yank)
+      (should (equal (buffer-string) "<c>"))
+      (should (= (mark t) 2))
+      (let ((last-command 'yank))
+        (t/yank-pop))
+      (should (equal (buffer-string) "<b>"))
+      (should (equal (current-kill 0) "b")))))
+
+(ert-deftest buffer::current-kill ()
+  (let* ((interprogram-paste-function nil)
+         (kill-ring (list "c" "b" "a"))
+         (kill-ring-yank-pointer kill-ring))
+    (should (equal (t/current-kill 0 nil) "c"))
+    (should (equal (t/current-kill 1 t) "b"))
+    (should (equal (t/current-kill 0 nil) "c"))
+    (should (equal (t/current-kill 2 nil) "a"))
+    (should (equal (t/current-kill 1 nil) "c"))
+    (kill-new "d")
+    (should (equal (t/current-kill 0 nil) "d"))))
+
+(ert-deftest buffer::preserving-modified-flag ()
+  (with-temp-buffer
+    (insert "abc")
+    (set-buffer-modified-p nil)
+    (should (t/insert-transiently "def" nil))
+    (should (equal (buffer-string) "abc"))
+    (should-not (buffer-modified-p))
+    (set-buffer-modified-p t)
+    (should (t/insert-transiently "def" nil))
+    (should (buffer-modified-p))
+    (set-buffer-modified-p nil)
+    (should-error (t/insert-transiently "def" t) :type 'error)
+    (should (equal (buffer-string) "abcdef"))
+    (should-not (buffer-modified-p))
+    (should-error (t/insert-transiently "ghi" 'panic) :type 'rust-panic)
+    (should (equal (buffer-string) "abcdefghi"))
+    (should-not (buffer-modified-p))))
+
+(ert-deftest buffer::replace-buffer-contents ()
+  (let ((source (generate-new-buffer " *source*")))
+    (unwind-protect
+        (with-temp-buffer
+          (with-current-buffer source
+            (insert "hello brave world\nfoo bar\n"))
+          (insert "hello world\nfoo baz\n")
+          (let ((marker (copy-marker (search-backward "world"))))
+            (search-forward "foo")
+            (put-text-property (point-min) (point-max) 'face 'bold)
+            (should (t/replace-buffer-contents source nil))
+            (should (equal (buffer-string) "hello brave world\nfoo bar\n"))
+            ;; Point and the marker move with the text they were in.
+            (should (looking-at-p " bar"))
+            (should (= marker 13))
+            (should (equal (buffer-substring-no-properties marker (+ marker 5)) "world"))
+            ;; Unchanged text keeps its properties.
+            (should (eq (get-text-property 1 'face) 'bold))
+            (when (>= emacs-major-version 27)
+              (with-current-buffer source
+                (erase-buffer)
+                (insert "hello"))
+              (should (t/replace-buffer-contents (buffer-name source) 1.0))
+              (should (equal (buffer-string) "hello")))))
+      (kill-buffer source))))
+
+(ert-deftest buffer::point-and-markers ()
+  (with-temp-buffer
+    (insert "hello world")
+    (let ((marker (t/goto-char-marking 7)))
+      (should (= (point) 7))
+      (should (markerp marker))
+      (should (eq (marker-buffer marker) (current-buffer)))
+      (should (eq (t/marker-position marker) 12))
+      (goto-char 1)
+      (insert "> ")
+      (should (eq (t/marker-position marker) 14))
+      (set-marker marker nil)
+      (should (eq (t/marker-position marker) nil)))
+    (should (equal (should-error (t/goto-char-marking 0) :type 'args-out-of-range)
+                   '(args-out-of-range 0 1 14)))
+    (narrow-to-region 3 5)
+    (should (equal (should-error (t/goto-char-marking 6) :type 'args-out-of-range)
+                   '(args-out-of-range 6 3 5)))
+    (should (= (point) 5))
+    (t/goto-char-marking 3)
+    (should (= (point) 3))
+    (should-error (t/marker-position 5) :type 'wrong-type-argument)))
+
+(ert-deftest buffer::buffer-handles ()
+  (with-temp-buffer
+    (should (equal (t/current-buffer-name) (buffer-name)))
+    (should (equal (t/get-buffer-name (buffer-name)) (buffer-name)))
+    (should (equal (t/get-buffer-name "t--no-such-buffer") nil))
+    (should (t/buffer-is-live (current-buffer)))
+    (should-error (t/buffer-is-live "foo") :type 'wrong-type-argument)
+    (insert (propertize "hello" 'face 'bold) " world")
+    (should (equal (t/buffer-substring (current-buffer) 1 6) "hello"))
+    (should (equal (t/buffer-substring (current-buffer) 6 1) "hello"))
+    (should-not (text-properties-at 0 (t/buffer-substring (current-buffer) 1 6)))
+    (should (eq (get-text-property 0 'face (t/buffer-substring (current-buffer) 1 6 t)) 'bold))
+    (should (equal (should-error (t/buffer-substring (current-buffer) 1 13)
+                                 :type 'args-out-of-range)
+                   '(args-out-of-range 13 1 12)))
+    (let ((buffer (current-buffer)))
+      (with-temp-buffer
+        ;; The positions are checked against the given buffer, not the current one.
+        (should (equal (t/buffer-substring buffer 7 12) "world")))))
+  (let ((buffer (generate-new-buffer " *dead*")))
+    (kill-buffer buffer)
+    (should-not (t/buffer-is-live buffer))
+    (should (equal (should-error (t/buffer-name-of buffer) :type 'wrong-type-argument)
+                   (list 'wrong-type-argument 'buffer-live-p buffer)))
+    (should-error (t/buffer-substring buffer 1 1) :type 'wrong-type-argument)))
+
+;;; ----------------------------------------------------------------------------
+;;; Lists and sequences.
+
+(ert-deftest list::assoc ()
+  (let ((alist (list (cons "a" 1) (cons "b" 2) (cons "B" 3))))
+    (should (eq (t/assoc "b" alist nil) (nth 1 alist)))
+    (should (eq (t/assoc "c" alist nil) nil))
+    (should (eq (t/assoc "B" alist #'string=) (nth 2 alist)))
+    (should (eq (t/assoc "b" alist (lambda (x y) (string= (upcase x) (upcase y))))
+                (nth 1 alist)))
+    (should (eq (t/rassoc 3 alist) (nth 2 alist)))
+    (should (eq (t/rassoc 4 alist) nil))))
+
+(ert-deftest list::plist-get ()
+  (let ((plist '(:width 2 :height nil face bold)))
+    (should (= (t/plist-get-int plist ":width") 2))
+    (should-not (t/plist-get-int plist ":depth"))
+    (should-error (t/plist-get-int plist ":height") :type 'wrong-type-argument)
+    (should (equal (t/plist-get plist ":height") '(t nil)))
+    (should (equal (t/plist-get plist ":depth") '(nil nil)))
+    (should (equal (t/plist-get plist "face") '(t bold)))
+    (should (equal (t/plist-get nil ":width") '(nil nil)))))
+
+(ert-deftest list::alist-get ()
+  (let ((alist '(("a" . 1) ("b") ("c" . 3))))
+    (should (equal (t/alist-get alist "a") '(t 1)))
+    (should (equal (t/alist-get alist "b") '(t nil)))
+    (should (equal (t/alist-get alist "d") '(nil nil)))))
+
+(ert-deftest list::plist-to-vec ()
+  (should (equal (t/plist-to-alist '(:a 1 :b nil)) '((:a . 1) (:b))))
+  (should-not (t/plist-to-alist nil))
+  (should-error (t/plist-to-alist '(:a 1 :b)) :type 'wrong-type-argument)
+  (should-error (t/plist-to-alist '(:a . 1)) :type 'wrong-type-argument)
+  (let ((circular (list :a 1)))
+    (setcdr (last circular) circular)
+    (should-error (t/plist-to-alist circular) :type 'circular-list)))
+
+(ert-deftest list::count ()
+  (should (= (t/count 'a '(a b a c a)) 3))
+  (should (= (t/count 'd '(a b a c a)) 0))
+  (should (= (t/count 2 [1 2 2 3]) 2))
+  (should (= (t/count 'a nil) 0))
+  (should (= (t/count-if #'cl-evenp '(1 2 3 4 6)) 3))
+  (should (= (t/count-if #'stringp '(1 "a" b "c")) 2))
+  (should (= (t/count-if #'stringp '(1 2)) 0)))
+
+(ert-deftest list::remove-duplicates ()
+  (let* ((a (list 1))
+         (list (list 'x a (copy-sequence "s") 'x (list 1) (copy-sequence "s") a 'y)))
+    (should (equal (t/remove-duplicates list t) '(x (1) "s" (1) "s" y)))
+    (should (eq (nth 1 (t/remove-duplicates list t)) a))
+    (should (equal (t/remove-duplicates list nil) '(x (1) "s" y)))
+    (should (equal list '(x (1) "s" x (1) "s" (1) y))))
+  (should (equal (t/remove-duplicates [1 2 1 3] nil) [1 2 3]))
+  (should (null (t/remove-duplicates nil t))))
+
+(ert-deftest list::number-sequence ()
+  (should (equal (t/number-sequence 1 5) '(1 2 3 4 5)))
+  (should (equal (t/number-sequence 1 10 3) '(1 4 7 10)))
+  (should (equal (t/number-sequence 1 9 3) '(1 4 7)))
+  (should (equal (t/number-sequence 5 1 -1) '(5 4 3 2 1)))
+  (should (equal (t/number-sequence 0 -7 -3) '(0 -3 -6)))
+  (should (equal (t/number-sequence 3 3) '(3)))
+  (should (equal (t/number-sequence 5 1) nil))
+  (should (equal (t/number-sequence 1 5 -1) nil))
+  (should-error (t/number-sequence 1 5 0)))
+
+(ert-deftest list::seq-take-drop ()
+  (let ((list '(1 2 3)))
+    (should (equal (t/seq-take list 2) '(1 2)))
+    (should (equal (t/seq-take list 3) list))
+    (should (equal (t/seq-take list 10) list))
+    (should (equal (t/seq-take list 0) nil))
+    (should (equal (t/seq-take list -1) nil))
+    (should (equal (t/seq-drop list 2) '(3)))
+    (should (equal (t/seq-drop list 10) nil))
+    (should (equal (t/seq-drop list 0) list))
+    (should (equal (t/seq-drop list -1) list)))
+  (should (equal (t/seq-take [1 2 3] 10) [1 2 3]))
+  (should (equal (t/seq-drop [1 2 3] 10) []))
+  (should (equal (t/seq-take "abc" 2) "ab"))
+  (should (equal (t/seq-drop "abc" 5) ""))
+  (should (equal (t/seq-take nil 2) nil)))
+
+(ert-deftest list::list-iter ()
+  (should (= (t/list-iter-sum '(1 2 3)) 6))
+  (should (= (t/list-iter-sum nil) 0))
+  (should (= (t/list-iter-sum (number-sequence 1 10000)) 50005000))
+  (should-error (t/list-iter-sum '(1 2 . 3)) :type 'wrong-type-argument)
+  (should-error (t/list-iter-sum [1 2 3]) :type 'wrong-type-argument)
+  (should-error (t/list-iter-sum '(1 a)) :type 'wrong-type-argument))
+
+(ert-deftest list::iter-seq ()
+  (should (equal (t/distinct-sorted '(3 1 2 3 1)) '(1 2 3)))
+  (should (equal (t/distinct-sorted [3 1 2 3 1]) '(1 2 3)))
+  (should (equal (t/distinct-sorted nil) nil))
+  (should (equal (t/distinct-sorted []) nil))
+  (should-error (t/distinct-sorted '(1 2 . 3)) :type 'wrong-type-argument)
+  (should-error (t/distinct-sorted [1 a]) :type 'wrong-type-argument)
+  (should (equal (should-error (t/distinct-sorted "abc") :type 'wrong-type-argument)
+                 '(wrong-type-argument list-or-vector-p "abc"))))
+
+;;; ----------------------------------------------------------------------------
+;;; Minibuffer.
+
+(ert-deftest minibuffer::minibufferp ()
+  (with-temp-buffer
+    (should-not (t/minibufferp)))
+  (with-current-buffer (window-buffer (minibuffer-window))
+    (should (t/minibufferp))))
+
+(ert-deftest minibuffer::read-string ()
+  (let* ((input "input")
+         (received nil))
+    (cl-letf (((symbol-function 'read-string)
+               (lambda (&rest args)
+                 (setq received args)
+                 input)))
+      (should (equal (t/read-string "Name: " "ini" "t--history" "def") "input"))
+      (should (equal received '("Name: " "ini" t--history "def")))
+      (should (equal (t/read-string "Name: " nil nil nil) "input"))
+      (should (equal received '("Name: " nil nil nil)))
+      (setq input "")
+      (should (equal (t/read-string "Name: " nil nil "def") "def"))
+      (should (equal (t/read-string "Name: " nil nil nil) "")))))
+
+(ert-deftest minibuffer::read-number ()
+  (let* ((input 3)
+         (received nil))
+    (cl-letf (((symbol-function 'read-number)
+               (lambda (&rest args)
+                 (setq received args)
+                 input)))
+      (should (equal (t/read-number "Count: " 2.5) 3.0))
+      (should (equal received '("Count: " 2.5)))
+      (setq input 1.5)
+      (should (equal (t/read-number "Count: " nil) 1.5))
+      (should (equal received '("Count: " nil)))
+      (setq input "3")
+      (should-error (t/read-number "Count: " nil) :type 'wrong-type-argument))))
+
+(ert-deftest minibuffer::completing-read ()
+  (let ((received nil))
+    (cl-letf (((symbol-function 'completing-read)
+               (lambda (&rest args)
+                 (setq received args)
+                 "b")))
+      (should (equal (t/completing-read "Pick: " '("a" "b") t) "b"))
+      (should (equal received '("Pick: " ("a" "b") nil t)))
+      (should (equal (t/completing-read "Pick: " ["a" "b"] nil) "b"))
+      (should (equal received '("Pick: " ["a" "b"] nil nil))))))
+
+(ert-deftest minibuffer::order-fruit ()
+  (should (commandp 't/order-fruit))
+  (cl-letf (((symbol-function 'completing-read)
+             (lambda (_prompt collection &rest _)
+               (should (equal collection '("apple" "banana" "cherry")))
+               "banana"))
+            ((symbol-function 'read-number) (lambda (&rest _) 2)))
+    (should (equal (call-interactively #'t/order-fruit) "2 x banana")))
+  ;; Quitting at the prompt is propagated as a `quit' signal.
+  (cl-letf (((symbol-function 'completing-read) (lambda (&rest _) (signal 'quit nil))))
+    (should (equal (condition-case err
+                       (call-interactively #'t/order-fruit)
+                     (quit err))
+                   '(quit)))))
+
+(defvar t--history)
+
+(ert-deftest minibuffer::add-to-history ()
+  (let ((t--history nil)
+        (history-delete-duplicates nil))
+    (should (equal (t/add-to-history "t--history" "a" nil) '("a")))
+    (t/add-to-history "t--history" "b" nil)
+    ;; Equal to the most recent element.
+    (should (equal (t/add-to-history "t--history" "b" nil) '("b" "a")))
+    (should (equal (t/add-to-history "t--history" "" nil) '("b" "a")))
+    (should (equal (t/add-to-history "t--history" "a" nil) '("a" "b" "a")))
+    (let ((history-delete-duplicates t))
+      (should (equal (t/add-to-history "t--history" "b" nil) '("b" "a" "a"))))
+    (should (equal (t/add-to-history "t--history" "c" 2) '("c" "b")))
+    (should (equal t--history '("c" "b")))))
+
+(ert-deftest minibuffer::abort-recursive-edit ()
+  ;; Not inside a recursive edit, so there is nothing to abort.
+  (should-error (t/abort-recursive-edit) :type 'user-error))
+
+;;; ----------------------------------------------------------------------------
+;;; Progress reporters.
+
+(ert-deftest progress::reporter ()
+  (let ((messages nil)
+        reporter)
+    (cl-letf (((symbol-function 'message)
+               (lambda (format-string &rest args)
+                 (push (apply #'format format-string args) messages))))
+      (setq reporter (t/report-progress "Working..." 100)))
+    (should (consp reporter))
+    (should messages)
+    (should (string-match-p "\\`Working\\.\\.\\..*done\\'" (car messages)))))
+
+;;; ----------------------------------------------------------------------------
+;;; Strings.
+
+(ert-deftest text::string-trim ()
+  (should (equal (t/string-trim "  a b \n\t") "a b"))
+  (should (equal (t/string-trim "ab") "ab"))
+  (should (equal (t/string-trim " \n ") "")))
+
+(ert-deftest text::split-string ()
+  (should (equal (t/split-string "  two words \n" nil nil) '("two" "words")))
+  (should (equal (t/split-string "  two words \n" nil t) '("two" "words")))
+  (should (equal (t/split-string "a,,b," "," nil) '("a" "" "b" "")))
+  (should (equal (t/split-string "a,,b," "," t) '("a" "b")))
+  (should (equal (t/split-string "a1b22c" "[0-9]+" nil) '("a" "b" "c")))
+  (should (equal (t/split-string "" "," t) nil)))
+
+(ert-deftest text::propertize ()
+  (let ((s (t/propertize-with-face "abc" "Help" "red" "bold" 1.5)))
+    (should (equal s "abc"))
+    (should (equal (get-text-property 0 'face s) '(:foreground "red" :weight bold :height 1.5)))
+    (should (equal (text-properties-at 2 s) (text-properties-at 0 s)))
+    (should (equal (get-text-property 1 'help-echo s) "Help")))
+  (let ((s (t/propertize-with-face "abc" nil nil "light")))
+    (should (equal (get-text-property 0 'face s) '(:weight light)))
+    (should (null (get-text-property 0 'help-echo s))))
+  (should (null (get-text-property 0 'face (t/propertize-with-face "a" nil)))))
+
+(ert-deftest text::propertized ()
+  (let ((s (t/propertized "héllo" '((0 5 help-echo "Hi")
+                                    (0 3 face bold)
+                                    (2 5 face italic)))))
+    (should (equal s "héllo"))
+    (should (equal (get-text-property 4 'help-echo s) "Hi"))
+    (should (eq (get-text-property 1 'face s) 'bold))
+    ;; The range added last wins where they overlap.
+    (should (eq (get-text-property 2 'face s) 'italic))
+    (should (eq (get-text-property 4 'face s) 'italic))
+    (let ((plist (t/text-properties-at s 1)))
+      (should (= (length plist) 4))
+      (should (eq (plist-get plist 'face) 'bold))
+      (should (equal (plist-get plist 'help-echo) "Hi")))
+    (should-error (t/text-properties-at s 5) :type 'args-out-of-range))
+  (should (equal (t/propertized "abc" nil) "abc"))
+  (should-not (t/text-properties-at "abc" 0))
+  (should-error (t/propertized "abc" '((0 4 face bold))) :type 'args-out-of-range)
+  (should-error (t/propertized "abc" '((2 1 face bold))) :type 'args-out-of-range))
+
+;;; ----------------------------------------------------------------------------
+;;; Quitting.
+
+(defun t/quit-result (f &rest args)
+  (condition-case nil
+      (apply f args)
+    (quit 'quit)))
+
+(ert-deftest quit::should-quit ()
+  (skip-unless (>= emacs-major-version 26))
+  (should (= (t/count-until-quit -1 nil) 1000))
+  (should (eq (t/quit-result #'t/count-until-quit 10 nil) 'quit))
+  (should-not quit-flag))
+
+(ert-deftest quit::process-input ()
+  (skip-unless (>= emacs-major-version 26))
+  (should (= (t/count-until-quit -1 t) 1000))
+  (should (eq (t/quit-result #'t/count-until-quit 10 t) 'quit))
+  (should-not quit-flag))
+
+;;; ----------------------------------------------------------------------------
+;;; Numbers.
+
+(ert-deftest math::rounding ()
+  (should (= (t/floor -2.5 nil) -3))
+  (should (= (t/ceiling -2.5 nil) -2))
+  (should (= (t/truncate -2.5 nil) -2))
+  ;; Halfway cases are rounded to even.
+  (should (= (t/round 2.5 nil) 2))
+  (should (= (t/round 3.5 nil) 4))
+  (should (= (t/round -2.5 nil) -2))
+  (should (integerp (t/round 2.7 nil))))
+
+(ert-deftest math::rounding-with-divisor ()
+  (should (= (t/floor 7 2) 3))
+  (should (= (t/floor -7 2) -4))
+  (should (= (t/ceiling 7 2) 4))
+  (should (= (t/truncate -7 2) -3))
+  (should (= (t/round 5 2) 2))
+  (should (= (t/floor 7.5 2) 3))
+  (should-error (t/floor 7 0) :type 'arith-error)
+  (when (>= emacs-major-version 27)
+    (should (= (t/floor (expt 10 30) 7) (/ (expt 10 30) 7)))))
+
+(ert-deftest math::integer-functions ()
+  (should (= (t/gcd 12 -18) 6))
+  (should (= (t/lcm 4 6) 12))
+  (should (= (t/expt 2 10) 1024))
+  (should (= (t/expt 2.0 -1) 0.5))
+  (should (= (t/isqrt 17) 4))
+  (should (= (t/isqrt 0) 0))
+  (should-error (t/isqrt -1) :type 'arith-error))
+
+(ert-deftest math::integer-functions-bignum ()
+  (skip-unless (>= emacs-major-version 27))
+  ;; Overflows `i64'.
+  (should (= (t/expt 3 100) (* (expt 3 50) (expt 3 50))))
+  (should (bignump (t/expt 2 64)))
+  (let ((a (* (expt 2 80) (expt 3 10)))
+        (b (* (expt 2 70) (expt 5 10))))
+    (should (= (t/gcd a b) (expt 2 70)))
+    (should (= (t/lcm a b) (* (expt 2 80) (expt 3 10) (expt 5 10)))))
+  (should (= (t/isqrt (expt 10 40)) (expt 10 20)))
+  (should (= (t/isqrt (1- (expt 10 40))) (1- (expt 10 20)))))
+
+;;; ----------------------------------------------------------------------------
+;;; Variables.
+
+(defvar t--let-bind-var)
+
+(ert-deftest variable::let-bind ()
+  (with-temp-buffer
+    (setq case-fold-search t)
+    (should (eq (t/let-bind '(("case-fold-search")) (lambda () case-fold-search)) nil))
+    (should (eq case-fold-search t))
+    (should-error (t/let-bind '(("case-fold-search")) (lambda () (error "Oops"))) :type 'error)
+    (should (eq case-fold-search t)))
+  (with-temp-buffer
+    (let ((default (default-value 'case-fold-search)))
+      ;; As with `let', the default value of `case-fold-search' is bound, since it has no
+      ;; buffer-local binding, even though it becomes buffer-local when set.
+      (should (equal (t/let-bind '(("case-fold-search" . 5))
+                                 (lambda () (list case-fold-search
+                                                  (default-value 'case-fold-search)
+                                                  (with-temp-buffer case-fold-search))))
+                     '(5 5 5)))
+      (should-not (local-variable-p 'case-fold-search))
+      (should (eq case-fold-search default))
+      ;; A buffer-local binding made by the function is kept.
+      (t/let-bind '(("case-fold-search" . 5)) (lambda () (setq-local case-fold-search 6)))
+      (should (local-variable-p 'case-fold-search))
+      (should (eq case-fold-search 6))
+      (should (eq (default-value 'case-fold-search) default))))
+  (should (equal (t/let-bind '(("t--let-bind-var" . 1) ("t--let-bind-var" . 2))
+                             (lambda () t--let-bind-var))
+                 2))
+  (should-not (boundp 't--let-bind-var)))
+
+;; Restoring a variable must not be skipped because the body panicked, or because restoring
+;; another one failed.
+(ert-deftest variable::let-bind-always-restoring ()
+  (setq t--let-bind-var 1)
+  (unwind-protect
+      (progn
+        (should-error (t/let-bind-panicking "t--let-bind-var" 2) :type 'rust-panic)
+        (should (eq t--let-bind-var 1))
+        (defvar t--let-bind-guarded 'bad)
+        (add-variable-watcher 't--let-bind-guarded
+                              (lambda (_symbol new _operation _where)
+                                (when (eq new 'bad) (error "Restoring"))))
+        (should-error (t/let-bind '(("t--let-bind-var" . 2) ("t--let-bind-guarded" . 3)) #'ignore)
+                      :type 'error)
+        (should (eq t--let-bind-var 1)))
+    (makunbound 't--let-bind-var)))
+
+(ert-deftest variable::command-variables ()
+  (let ((this-command nil)
+        (last-command nil))
+    (should (equal (t/set-commands 'kill-region 'yank) '(kill-region yank)))
+    (should (eq this-command 'kill-region))
+    (should (eq last-command 'yank))))
+
+(defvar t--set-var)
+
+(ert-deftest variable::set-var ()
+  (should (= (t/set-int-var "t--set-var" 5) 5))
+  (should (= t--set-var 5))
+  (setq t--set-var "abc")
+  (should-error (t/set-int-var "t--set-var" "abc") :type 'wrong-type-argument)
+  (should (equal (t/get-var "t--set-var") "abc"))
+  (makunbound 't--set-var)
+  (should-error (t/get-var "t--set-var") :type 'void-variable)
+  (with-temp-buffer
+    (setq-default t--set-var 1)
+    (t/set-local-var "t--set-var" 2)
+    (should (local-variable-p 't--set-var))
+    (should (= t--set-var 2))
+    (should (= (default-value 't--set-var) 1)))
+  (makunbound 't--set-var))
+
+(ert-deftest variable::defvar ()
+  (unwind-protect
+      (progn
+        (should (eq (t/defvar "t--defvar" '(a b) "A test variable.") 't--defvar))
+        (should (equal (symbol-value 't--defvar) '(a b)))
+        (should (special-variable-p 't--defvar))
+        (should (equal (documentation-property 't--defvar 'variable-documentation)
+                       "A test variable."))
+        ;; Already bound.
+        (t/defvar "t--defvar" 5 "A test variable.")
+        (should (equal (symbol-value 't--defvar) '(a b))))
+    (makunbound 't--defvar)))
+
+(ert-deftest variable::defcustom ()
+  (unwind-protect
+      (progn
+        (should (eq (t/defcustom "t--defcustom" 5 "A test option." "t--group" "integer")
+                    't--defcustom))
+        (should (= (symbol-value 't--defcustom) 5))
+        (should (custom-variable-p 't--defcustom))
+        (should (eq (get 't--defcustom 'custom-type) 'integer))
+        (should (assq 't--defcustom (get 't--group 'custom-group)))
+        (should (equal (documentation-property 't--defcustom 'variable-documentation)
+                       "A test option."))
+        (should (= (eval (car (get 't--defcustom 'standard-value))) 5))
+        (t/defcustom "t--defcustom-list" nil "A test option." "t--group" "(repeat string)")
+        (should (equal (get 't--defcustom-list 'custom-type) '(repeat string))))
+    (makunbound 't--defcustom)
+    (makunbound 't--defcustom-list)
+    (put 't--group 'custom-group nil)))
+
+(ert-deftest variable::let-bind-switching-buffer ()
+  (let ((other (generate-new-buffer " *other*")))
+    (unwind-protect
+        (with-temp-buffer
+          (setq case-fold-search nil)
+          (let ((buffer (current-buffer)))
+            (t/let-bind '(("case-fold-search" . t)) (lambda () (set-buffer other)))
+            (should (eq (current-buffer) other))
+            (should (eq (buffer-local-value 'case-fold-search buffer) nil))))
+      (kill-buffer other))))
+
+;;; ----------------------------------------------------------------------------
+;;; Hooks.
+
+(defvar t--hook nil)
+
+(ert-deftest hook::add-run-remove ()
+  (let* ((t--hook nil)
+         (calls nil)
+         (fn (lambda () (push 'lambda calls))))
+    (defalias 't--hook-function (lambda () (push 'named calls)))
+    (unwind-protect
+        (progn
+          (t/add-hook "t--hook" fn nil nil)
+          (t/add-hook-global-ref "t--hook" "t--hook-function")
+          (t/add-hook "t--hook" fn t nil)
+          (should (equal t--hook (list 't--hook-function fn)))
+          (t/run-hooks "t--hook")
+          (should (equal calls '(lambda named)))
+          (t/remove-hook "t--hook" fn nil)
+          (t/remove-hook "t--hook" 't--hook-function nil)
+          (should-not t--hook))
+      (fmakunbound 't--hook-function))))
+
+(ert-deftest hook::local ()
+  (let ((t--hook nil))
+    (with-temp-buffer
+      (t/add-hook "t--hook" #'ignore t t)
+      (should (local-variable-p 't--hook))
+      (should (equal t--hook '(t ignore)))
+      (should-not (default-value 't--hook))
+      (t/remove-hook "t--hook" #'ignore t)
+      (should-not (memq 'ignore t--hook)))))
+
 ;;; ----------------------------------------------------------------------------
 ;;; Non-local exits.
 
@@ -148,9 +1410,65 @@
   (should (> (length (t/read-file "Cargo.toml")) 0))
   (should-error (t/read-file "!@#%%&") :type 'emrs-file-error))
 
+(ert-deftest error::structured-signal-data ()
+  (should (= (t/error:parse-at "a.txt" 3 "42") 42))
+  (should (equal (t/get-error (t/error:parse-at "a.txt" 3 "4x"))
+                 '(emrs-file-error "a.txt" 3 "invalid digit found in string")))
+  (should (equal (t/error:get-data (lambda () (t/error:parse-at "b.txt" 7 "")))
+                 '("b.txt" 7 "cannot parse integer from empty string")))
+  (should (equal (t/error:get-data (lambda () (signal 'error '(1 "two" three))))
+                 '(1 "two" three)))
+  (should (eq (t/error:get-data (lambda () 5)) 'none))
+  ;; Throws are not signals, so they are propagated as is.
+  (should (equal (catch 'ball (t/error:get-data (lambda () (throw 'ball 5)))) 5)))
+
+(ert-deftest error::context-chain ()
+  (should (= (t/error:parse-with-context "42") 42))
+  (should (equal (should-error (t/error:parse-with-context "4x") :type 'rust-error)
+                 '(rust-error "while loading config: while parsing \"4x\": invalid digit found in string")))
+  (should (equal (should-error (t/error:parse-with-context-or-signal "4x") :type 'emrs-file-error)
+                 '(emrs-file-error "while loading config: while parsing \"4x\": invalid digit found in string"))))
+
+(ert-deftest error::unwind-protect ()
+  (let* ((cleanups 0)
+         (cleanup (lambda () (setq cleanups (1+ cleanups)))))
+    (should (= (t/error:unwind-protect (lambda () 5) cleanup) 5))
+    (should (= cleanups 1))
+    (should (equal (t/get-error (t/error:unwind-protect (lambda () (signal 'arith-error '(1)))
+                                                        cleanup))
+                   '(arith-error 1)))
+    (should (= cleanups 2))
+    (should (= (catch 'ball (t/error:unwind-protect (lambda () (throw 'ball 7)) cleanup)) 7))
+    (should (= cleanups 3))
+    (should (equal (cadr (t/get-error (t/error:unwind-protect #'ignore cleanup "abc"))) "abc"))
+    (should (= cleanups 4))))
+
+(ert-deftest error::classify ()
+  (should (equal (t/error:classify (lambda () (signal 'arith-error '(1 2))))
+                 '(signal arith-error (1 2))))
+  (should (equal (t/error:classify (lambda () (throw 'ball 5)))
+                 '(throw ball 5)))
+  (should (equal (t/error:classify (lambda () 5))
+                 '(return))))
+
 (ert-deftest error::handling-signal ()
   (should (eq (t/error:get-type (lambda () (error "?"))) 'error))
-  (should (eq (t/error:get-type (lambda () (user-error "?"))) 'user-error)))
+  (should (eq (t/error:get-type (lambda () (user-error "?"))) 'user-error))
+  (should (eq (t/error:get-type-and-free (lambda () (signal 'arith-error '(1)))) 'arith-error)))
+
+(ert-deftest error::dropped-values-queued ()
+  ;; The symbol and data of a handled signal are freed the next time a Rust function is called.
+  (should (= (t/error:drop-and-count-pending (lambda () (signal 'arith-error '(1)))) 2))
+  (should (= (t/owned-global-ref-pending-count) 0)))
+
+(ert-deftest error::is-quit ()
+  (should (eq (t/error:classify-quit (lambda () (signal 'quit nil))) 'quit))
+  (should (eq (t/error:classify-quit (lambda () (keyboard-quit))) 'quit))
+  (should (eq (t/error:classify-quit (lambda () (error "?"))) 'error))
+  (should (eq (t/error:classify-quit (lambda () 5)) 5))
+  (should (eq (catch 'ball (t/error:classify-quit (lambda () (throw 'ball 'thrown)))) 'thrown))
+  (define-error 't--sub-quit "Sub-quit" 'quit)
+  (should (eq (t/error:classify-quit (lambda () (signal 't--sub-quit nil))) 'quit)))
 
 (ert-deftest error::handling-throw ()
   (should (let ((msg "Catch this!"))
@@ -159,12 +1477,30 @@
                 msg)))
   (should-error (t/error:catch 'ball
                                (lambda () (throw 'knife "Watch out!")))
-                :type 'no-catch))
+                :type 'no-catch)
+  (should-error (t/error:catch 'ball
+                               (lambda () (error "Not thrown")))
+                :type 'error)
+  (should (equal (t/error:catch 'ball (lambda () 5)) 5)))
 
 (ert-deftest error::panic ()
   (should-error (t/error:parse-arg 5 "1") :type 'rust-panic)
-  (should (equal (t/get-error (t/error:apply #'t/error:panic '("abc")))
-                 '(rust-panic "abc"))))
+  (pcase-let ((`(,symbol ,message . ,backtrace)
+               (t/get-error (t/error:apply #'t/error:panic '("abc")))))
+    (should (eq symbol 'rust-panic))
+    (should (equal message "abc"))
+    (if (member (getenv "RUST_BACKTRACE") '(nil "" "0"))
+        (should-not backtrace)
+      (should (string-match-p "test_error" (car backtrace))))))
+
+(ert-deftest error::parse-keyword ()
+  (should (= (t/error:parse-keyword ":width" :width 10 :height 5) 10))
+  (should (= (t/error:parse-keyword ":height" :width 10 :height 5) 5))
+  (should (= (t/error:parse-keyword ":width" :width 1 :width 2) 1))
+  (should-not (t/error:parse-keyword ":depth" :width 10 :height 5))
+  (should-not (t/error:parse-keyword ":width"))
+  (should-error (t/error:parse-keyword ":width" :width 10 :height) :type 'wrong-type-argument)
+  (should-error (t/error:parse-keyword ":width" :width "10") :type 'wrong-type-argument))
 
 (ert-deftest error::signal ()
   (should-error (t/error:signal 'rust-error "BAZ") :type 'rust-error)
@@ -177,7 +1513,19 @@
   (condition-case err
       (t/error:signal 'emacs-module-rs-test-error "abc")
     (rust-error (should (equal err '(emacs-module-rs-test-error . ("abc"))))))
-  (should-error (signal 'error-defined-without-parent nil) :type 'error))
+  (should-error (signal 'error-defined-without-parent nil) :type 'error)
+  (should (equal (get 'error-with-computed-message 'error-message) "Computed by closure"))
+  (should-error (signal 'error-with-computed-message nil) :type 'rust-error))
+
+(ert-deftest error::define-checked ()
+  (t/error:define-checked 't--checked-error 'arith-error)
+  (should (member 'arith-error (get 't--checked-error 'error-conditions)))
+  (should-error (signal 't--checked-error nil) :type 'arith-error)
+  (let ((err (should-error (t/error:define-checked 't--unchecked-error 'no-such-error-abc)
+                           :type 'rust-error)))
+    (should (string-match-p "t--unchecked-error" (cadr err)))
+    (should (string-match-p "no-such-error-abc" (cadr err))))
+  (should-not (get 't--unchecked-error 'error-conditions)))
 
 ;;; ----------------------------------------------------------------------------
 ;;; Functions.
@@ -192,6 +1540,104 @@
   (should-error (t/call-value nil nil) :type 'void-function)
   (should-error (t/call-value 3 nil) :type 'invalid-function))
 
+(ert-deftest calling::funcall ()
+  (should (= (t/call-funcall-add 1 2) 3))
+  (should (= (t/call-funcall-sum-strings '("1.5" "2.5" "3.0")) 7.0))
+  (should (= (t/call-funcall-sum-strings nil) 0.0))
+  ;; `string-to-number' returns an integer here, which is not a float.
+  (should-error (t/call-funcall-sum-strings '("1")) :type 'wrong-type-argument))
+
+(ert-deftest calling::lisp-fn ()
+  (should (= (t/call-sum-cached 100) 4950))
+  (should (= (t/call-sum-by-name 100) 4950))
+  (should (= (t/call-sum-cached 0) 0))
+  (let (calls)
+    ;; The symbol is cached, not its function definition.
+    (fset 't--lisp-fn-target (lambda (x) (push (list 'a x) calls)))
+    (should (equal (t/call-lisp-fn-name 1) "t--lisp-fn-target"))
+    (fset 't--lisp-fn-target (lambda (x) (push (list 'b x) calls)))
+    (t/call-lisp-fn-name 2)
+    (should (equal calls '((b 2) (a 1))))
+    (should (equal (t/call-lisp-fn-static 3) '((b 3) (b 2) (a 1))))
+    (fmakunbound 't--lisp-fn-target)
+    (should-error (t/call-lisp-fn-name 3) :type 'void-function)
+    (should-error (t/call-lisp-fn-static 4) :type 'void-function)))
+
+(ert-deftest calling::apply ()
+  (should (= (t/call-apply-sum '(1 2 3)) 6))
+  (should (= (t/call-apply-sum nil) 0))
+  (should-error (t/call-apply-sum '(1 a)) :type 'wrong-type-argument)
+  (should-error (t/call-apply-sum 5) :type 'wrong-type-argument)
+  (should (equal (t/call-apply-with #'list '(3 4)) '(1 "two" 3 4)))
+  (should (equal (t/call-apply-with #'list nil) '(1 "two")))
+  (should (equal (t/call-apply-with (lambda (&rest args) (length args)) '(a)) 3))
+  ;; Errors signaled by the applied function are propagated.
+  (should (equal (should-error (t/call-apply-with (lambda (&rest args) (signal 'arith-error args))
+                                                  '(3))
+                               :type 'arith-error)
+                 '(arith-error 1 "two" 3)))
+  (should (eq (catch 'ball (t/call-apply-with (lambda (&rest _) (throw 'ball 'thrown)) nil))
+              'thrown)))
+
+(ert-deftest calling::if-bound ()
+  (should (equal (t/call-if-bound "symbol-name" 'abc) "abc"))
+  (fset 't--if-bound-target (lambda (x) (* x 2)))
+  (should (= (t/call-if-bound "t--if-bound-target" 3) 6))
+  (fmakunbound 't--if-bound-target)
+  (should (eq (t/call-if-bound "t--if-bound-target" 3) 'unbound))
+  (should (eq (t/call-if-bound "t--never-defined-function" 3) 'unbound)))
+
+(ert-deftest calling::define-obsolete-function-alias ()
+  (unwind-protect
+      (progn
+        (should (eq (t/call-define-obsolete "t--obsolete-list" "list" "0.19") 't--obsolete-list))
+        (should (equal (t--obsolete-list 1 2) '(1 2)))
+        ;; This is what makes the byte compiler warn about calls to it.
+        (should (equal (get 't--obsolete-list 'byte-obsolete-info) '(list nil "0.19"))))
+    (fmakunbound 't--obsolete-list)
+    (put 't--obsolete-list 'byte-obsolete-info nil)))
+
+(ert-deftest calling::intern-cached ()
+  (should (eq (t/call-intern-repeatedly "t--interned" 3 t) 't--interned))
+  (should (eq (t/call-intern-repeatedly "t--interned" 1 t) 't--interned))
+  (should (eq (t/call-intern-repeatedly "t--interned" 1 nil) 't--interned))
+  (should (eq (t/call-intern-repeatedly "t--interned" 0 t) nil))
+  (should (equal (t/call-nil-and-t) '(nil t))))
+
+(ert-deftest calling::eval-form ()
+  (defvar t--eval-form-var)
+  (setq t--eval-form-var nil)
+  (should (eq (t/call-eval-setq-when 't--eval-form-var t 'x) 'x))
+  (should (eq t--eval-form-var 'x))
+  (should (eq (t/call-eval-setq-when 't--eval-form-var nil 'x) nil))
+  (should (eq t--eval-form-var nil))
+  (should-error (t/call-eval-setq-when 5 t 'x) :type 'wrong-type-argument))
+
+(ert-deftest calling::eval-string ()
+  (should (= (t/call-eval-string "(+ 1 2)") 3))
+  (should (= (t/call-eval-string "  ;; Comment.\n (* 2 3) ; Another.\n\n") 6))
+  (should (= (t/call-eval-string "(funcall (let ((t--eval-x 1)) (lambda () t--eval-x)))") 1))
+  (should-error (t/call-eval-string "(funcall (let ((t--eval-x 1)) (lambda () t--eval-x)))" t)
+                :type 'void-variable)
+  (should-error (t/call-eval-string "(+ 1 2) (setq t--eval-x 5)") :type 'invalid-read-syntax)
+  (should-not (boundp 't--eval-x))
+  (should-error (t/call-eval-string "") :type 'end-of-file)
+  (should-error (t/call-eval-string "(+ 1") :type 'end-of-file)
+  (should-error (t/call-eval-string "(car 1)") :type 'wrong-type-argument))
+
+(ert-deftest function::closure ()
+  (let ((add (t/make-adder 2)))
+    (should (= (funcall add 1) 3))
+    (should (= (funcall add 1 4) 7))
+    (should (equal (documentation add) "Add N."))
+    (should-error (funcall add) :type 'wrong-number-of-arguments)
+    (should-error (funcall add 1 2 3) :type 'wrong-number-of-arguments))
+  (let ((dropped (t/adders-dropped)))
+    (dotimes (_ 100) (t/make-adder 1))
+    (garbage-collect)
+    ;; The GC is conservative, so some of them may survive.
+    (should (> (t/adders-dropped) dropped))))
+
 (ert-deftest function::create ()
   (let ((dec (t/make-dec)))
     (should (= (funcall dec 9) 8))
@@ -220,11 +1666,50 @@
     (should (equal (t/sum-and-diff x y)
                    (list (+ x y) (- x y))))))
 
+(ert-deftest function::optional-args ()
+  (should (equal (func-arity 't/greet) '(1 . 3)))
+  (should (equal (t/greet "Alice") "Hello, Alice!"))
+  (should (equal (t/greet "Alice" "Dr.") "Hello, Dr. Alice!"))
+  (should (equal (t/greet "Alice" nil ".") "Hello, Alice."))
+  (should (equal (t/greet "Alice" "Dr." "?") "Hello, Dr. Alice?"))
+  (should-error (t/greet) :type 'wrong-number-of-arguments)
+  (should-error (t/greet "Alice" nil nil nil) :type 'wrong-number-of-arguments)
+  (should (equal (t/sig 't/greet) "(t/greet NAME &optional TITLE SUFFIX)")))
+
+(ert-deftest function::defun-interactive ()
+  (should (commandp 't/double-prefix))
+  (should (commandp 't/no-arg-command))
+  (should-not (commandp 't/greet))
+  (should (= (t/double-prefix 3) 6))
+  (should (= (let ((current-prefix-arg 4)) (call-interactively #'t/double-prefix)) 8))
+  (should (equal (call-interactively #'t/no-arg-command) "called"))
+  (with-temp-buffer
+    (insert "abcd")
+    (set-mark 2)
+    (goto-char 4)
+    (should (equal (call-interactively #'t/region-bounds) '(2 4))))
+  (should (equal (interactive-form 't/double-prefix) '(interactive "p")))
+  (should (equal (t/sig 't/double-prefix) "(t/double-prefix N)"))
+  (should-error (t/double-prefix) :type 'wrong-number-of-arguments))
+
+(ert-deftest function::defun-docstring ()
+  (should (equal (documentation 't/doc-example t)
+                 (concat "Return X unchanged.\n"
+                         "\n"
+                         "Quotes: \"double\" and `single'.\n"
+                         "Backslashes: \\ and \\\\.\n"
+                         "    This line is indented.\n"
+                         "\n"
+                         "(fn X)")))
+  (should (equal (documentation 't/doc-explicit-signature t)
+                 "Return OBJECT unchanged.\n\n(fn OBJECT)"))
+  (should (equal (t/sig 't/doc-explicit-signature) "(t/doc-explicit-signature OBJECT)")))
+
 (ert-deftest function::defun-signature ()
   (should (equal (t/sig 't/ignore-args)
                  "(t/ignore-args _ _)"))
   (should (equal (t/sig 't/to-lowercase-or-nil)
-                 "(t/to-lowercase-or-nil INPUT)"))
+                 "(t/to-lowercase-or-nil &optional INPUT)"))
   (should (equal (t/sig 't/error:catch)
                  "(t/error:catch EXPECTED-TAG LAMBDA)")))
 
@@ -276,11 +1761,34 @@
                                       :type 'rust-error))
                    '("already borrowed")))))
 
+(ert-deftest transfer::custom-finalizer ()
+  (let ((r (t/ref-cell-make-finalized 1 nil)))
+    (should (= (t/ref-cell-finalized-id r) 1))
+    (should-error (t/ref-cell-inc r) :type 'rust-wrong-type-user-ptr))
+  ;; The finalizer of 100 panics, which should be caught.
+  (dotimes (i 100)
+    (t/ref-cell-make-finalized (+ 100 i) (= i 0)))
+  (garbage-collect)
+  (garbage-collect)
+  (let ((ids (t/ref-cell-finalized-ids)))
+    ;; The GC is conservative, so some values may not have been collected yet. The ones that were
+    ;; must have been finalized once, and only once.
+    (should (equal ids (delete-dups (copy-sequence ids))))
+    (should (cl-some (lambda (id) (>= id 100)) ids))))
+
 (ert-deftest transfer::type-check ()
   (should-error (t/ref-cell-inc (t/vector-make 1 2))
                 :type 'rust-wrong-type-user-ptr)
   (should-error (t/ref-cell-inc 5)
                 :type 'wrong-type-argument)
+  (ert-info ("The error message names both the expected and the actual type")
+    (let ((err (should-error (t/ref-cell-inc (t/vector-make 1 2))
+                             :type 'rust-wrong-type-user-ptr)))
+      (should (string-match-p "\\`expected: .*RefCell<i64>, found: .*vector::Vector.*\\'"
+                              (cadr err))))
+    (let ((err (should-error (t/ref-cell-inc (t/ref-cell-make-finalized 1 nil))
+                             :type 'rust-wrong-type-user-ptr)))
+      (should (string-match-p "found: .*RefCell<.*Finalized>" (cadr err)))))
   (ert-info ("'rust-wrong-type-user-ptr should be both 'rust-error and 'wrong-type-argument")
     (let ((parent-symbols (get 'rust-wrong-type-user-ptr 'error-conditions)))
       (should (member 'rust-error parent-symbols))
@@ -321,6 +1829,40 @@
 (destructive-test gc-after-retrieving lifetime)
 (destructive-test gc-after-catching lifetime)
 
+(defun t/owned-global-ref-lifecycle ()
+  (let ((value (list 1 "a")))
+    (t/owned-global-ref-drop-in-call value)
+    (t/owned-global-ref-drop-on-thread value)
+    ;; Freeing the queued global reference happens when a Rust function is called.
+    (t/owned-global-ref-pending-count)
+    (garbage-collect)))
+
+(destructive-test owned-global-ref-lifecycle global-ref)
+
+(defvar t--unloaded nil)
+(defvar t--user-unloaded nil)
+
+(defun t/unload-module ()
+  (should (fboundp 't-unload-function))
+  (unload-feature 't t)
+  (should (eq t--unloaded t))
+  (should (eq t--user-unloaded t)))
+
+(destructive-test unload-module module)
+
+(ert-deftest global-ref::owned-in-user-ptr ()
+  (t/owned-global-ref-in-user-ptr (list 1 "a"))
+  ;; The finalizer runs during GC, where it can only queue the global reference.
+  (garbage-collect)
+  (should (= (t/owned-global-ref-pending-count) 0)))
+
+(ert-deftest global-ref::owned-pending-queue ()
+  (let ((value (list 1 "a")))
+    (should (equal (t/owned-global-ref-drop-in-call value) value))
+    (should (= (t/owned-global-ref-drop-on-thread value) 1))
+    (should (= (t/owned-global-ref-pending-count) 0))
+    (should (equal value '(1 "a")))))
+
 ;;; ----------------------------------------------------------------------------
 ;;; Memory leak tests.
 